@@ -1,15 +1,27 @@
 use axum::{
-    extract::{Path, State},
-    http::{Method, StatusCode},
-    response::Json,
+    extract::{MatchedPath, Path, Query, Request, State},
+    http::{header, HeaderMap, Method, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Json, Response},
     routing::{get, post},
     Router,
 };
-use clawdbot::ore_stats::OreStatsService;
+use clawdbot::{
+    analytics::reconcile_ledger,
+    blockchain_parser::BlockchainParser,
+    db::SharedDb,
+    error::BotError,
+    learning_engine::{CompetitionDistribution, DetectedStrategy, LearningEngine, OreEstimateError, WinRecord},
+    ore_stats::OreStatsService,
+    request_metrics::RequestMetrics,
+    units::lamports_to_sol,
+};
 use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
 use std::{
     collections::HashMap,
     process::Stdio,
+    str::FromStr,
     sync::Arc,
 };
 use tokio::{
@@ -17,7 +29,7 @@ use tokio::{
     sync::RwLock,
 };
 use tower_http::cors::{Any, CorsLayer};
-use tracing::{info, warn, error};
+use tracing::{field, info, info_span, warn, error, Instrument};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 struct Bot {
@@ -32,8 +44,17 @@ struct AppState {
     bots: Arc<RwLock<HashMap<String, BotProcess>>>,
     ore_stats: Arc<RwLock<Option<OreStatsService>>>,
     rpc_url: String,
+    db: Option<Arc<SharedDb>>,
+    admin_token: Option<String>,
+    metrics: RequestMetrics,
 }
 
+/// Routes the dashboard polls constantly; their request logs are sampled
+const HOT_ROUTES: [&str; 3] = ["/health", "/api/ore/live", "/api/bots"];
+
+/// Winner share the recommendations' bet sizes aim for unless `target_share` is given
+const DEFAULT_TARGET_SHARE: f64 = 0.3;
+
 struct BotProcess {
     name: String,
     child: Option<Child>,
@@ -65,13 +86,43 @@ impl AppState {
         let rpc_url = std::env::var("RPC_URL")
             .unwrap_or_else(|_| "https://api.mainnet-beta.solana.com".to_string());
 
+        // Token required for admin (mutating) endpoints
+        let admin_token = std::env::var("ADMIN_API_TOKEN").ok().filter(|t| !t.is_empty());
+
         Self {
             bots: Arc::new(RwLock::new(bots)),
             ore_stats: Arc::new(RwLock::new(None)),
             rpc_url,
+            db: None,
+            admin_token,
+            metrics: RequestMetrics::new(&HOT_ROUTES),
+        }
+    }
+
+    /// Connect to the shared bot database if DATABASE_URL is set
+    async fn connect_db(&mut self) {
+        if !clawdbot::db::is_database_available() {
+            warn!("DATABASE_URL not set, database-backed endpoints disabled");
+            return;
+        }
+
+        match SharedDb::connect().await {
+            Ok(db) => self.db = Some(Arc::new(db)),
+            Err(e) => warn!("Database connection failed: {}", e),
         }
     }
     
+    fn require_db(&self) -> Result<&Arc<SharedDb>, ApiError> {
+        self.db.as_ref().ok_or(ApiError::DatabaseUnavailable)
+    }
+
+    async fn ore_stats_service(&self) -> Result<OreStatsService, ApiError> {
+        self.get_ore_stats().await.map_err(|e| {
+            error!("{}", e);
+            ApiError::Internal(e)
+        })
+    }
+
     async fn get_ore_stats(&self) -> Result<OreStatsService, String> {
         // Lazy initialization of OreStatsService
         {
@@ -91,10 +142,22 @@ async fn main() {
     // Initialize tracing
     tracing_subscriber::fmt::init();
 
-    let state = AppState::new();
+    let mut state = AppState::new();
+    state.connect_db().await;
+
+    let app = router(state);
 
-    // Build router
-    let app = Router::new()
+    let port = std::env::var("PORT").unwrap_or_else(|_| "3000".to_string());
+    let addr = format!("0.0.0.0:{}", port);
+    
+    info!("🚀 ClawdBot API listening on {}", addr);
+    
+    let listener = tokio::net::TcpListener::bind(&addr).await.unwrap();
+    axum::serve(listener, app).await.unwrap();
+}
+
+fn router(state: AppState) -> Router {
+    Router::new()
         .route("/health", get(health))
         .route("/api/bots", get(list_bots))
         .route("/api/bots/:id/start", post(start_bot))
@@ -107,27 +170,69 @@ async fn main() {
         .route("/api/ore/history", get(ore_round_history))
         .route("/api/ore/squares", get(ore_square_analysis))
         .route("/api/ore/recommendations", get(ore_recommendations))
+        .route("/api/ore/instruction-stats", get(ore_instruction_stats))
+        .route("/api/ore/round/:id/winners", get(ore_round_winners))
+        .route("/api/ore/rounds/recent", get(ore_recent_rounds))
+        .route("/api/ore/reconcile", get(ore_reconcile))
+        .route("/api/ore/analytics/ore-accuracy", get(ore_estimate_accuracy))
+        .route("/api/ore/analytics/winner-sources", get(ore_winner_sources))
+        .route("/api/ore/competition-distribution", get(ore_competition_distribution))
+        .route("/api/ore/config/effective", get(ore_effective_config))
+        .route("/api/ore/detect-strategies", post(ore_detect_strategies))
+        .route("/api/metrics", get(request_metrics))
+        .layer(middleware::from_fn_with_state(state.metrics.clone(), track_requests))
         .layer(
             CorsLayer::new()
                 .allow_origin(Any)
                 .allow_methods([Method::GET, Method::POST])
                 .allow_headers(Any),
         )
-        .with_state(state);
+        .with_state(state)
+}
 
-    let port = std::env::var("PORT").unwrap_or_else(|_| "3000".to_string());
-    let addr = format!("0.0.0.0:{}", port);
-    
-    info!("🚀 ClawdBot API listening on {}", addr);
-    
-    let listener = tokio::net::TcpListener::bind(&addr).await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+/// Time each request, record it in the latency histograms and log it (sampled for hot routes)
+async fn track_requests(State(metrics): State<RequestMetrics>, request: Request, next: Next) -> Response {
+    let method = request.method().clone();
+    // The route pattern (`/api/bots/:id/status`), not the raw path, keeps the histograms bounded
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+
+    let span = info_span!(
+        "request",
+        method = %method,
+        path = %route,
+        status = field::Empty,
+        latency_ms = field::Empty,
+    );
+    let start = std::time::Instant::now();
+    let response = next.run(request).instrument(span.clone()).await;
+    let latency_ms = start.elapsed().as_secs_f64() * 1000.0;
+    let status = response.status().as_u16();
+
+    span.record("status", status);
+    span.record("latency_ms", field::display(format!("{:.1}", latency_ms)));
+    if metrics.record(method.as_str(), &route, status, latency_ms) {
+        span.in_scope(|| info!("request completed"));
+    }
+
+    response
 }
 
 async fn health() -> &'static str {
     "OK"
 }
 
+/// Request counts and latency histograms per method and route
+async fn request_metrics(State(state): State<AppState>) -> Json<serde_json::Value> {
+    Json(serde_json::json!({
+        "buckets_ms": clawdbot::request_metrics::LATENCY_BUCKETS_MS,
+        "routes": state.metrics.snapshot(),
+    }))
+}
+
 async fn list_bots(State(state): State<AppState>) -> Json<serde_json::Value> {
     let bots = state.bots.read().await;
     
@@ -276,6 +381,74 @@ async fn bot_status(
     }))
 }
 
+// ═══════════════════════════════════════════════════════════════════════════════
+// API ERRORS
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Error returned by the `/api/ore/*` handlers as `{ "error": ..., "code": ... }`
+#[derive(Debug)]
+enum ApiError {
+    /// Solana RPC call failed (502)
+    Rpc(String),
+    /// No database configured, or it can't be reached (503)
+    DatabaseUnavailable,
+    /// Malformed request (400)
+    BadRequest(String),
+    /// Missing or wrong admin token (401)
+    Unauthorized,
+    /// Anything else (500)
+    Internal(String),
+}
+
+impl ApiError {
+    fn status(&self) -> StatusCode {
+        match self {
+            ApiError::Rpc(_) => StatusCode::BAD_GATEWAY,
+            ApiError::DatabaseUnavailable => StatusCode::SERVICE_UNAVAILABLE,
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::Unauthorized => StatusCode::UNAUTHORIZED,
+            ApiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Stable machine-readable code for clients
+    fn code(&self) -> &'static str {
+        match self {
+            ApiError::Rpc(_) => "rpc_error",
+            ApiError::DatabaseUnavailable => "database_unavailable",
+            ApiError::BadRequest(_) => "bad_request",
+            ApiError::Unauthorized => "unauthorized",
+            ApiError::Internal(_) => "internal_error",
+        }
+    }
+
+    fn message(&self) -> String {
+        match self {
+            ApiError::Rpc(msg) | ApiError::BadRequest(msg) | ApiError::Internal(msg) => msg.clone(),
+            ApiError::DatabaseUnavailable => "Database not configured or unreachable".to_string(),
+            ApiError::Unauthorized => "Missing or invalid admin token".to_string(),
+        }
+    }
+
+    /// A failed database query: 503 when the database is down, 500 otherwise
+    fn database(context: &str, e: BotError) -> Self {
+        match e {
+            BotError::DatabaseUnavailable(_) => ApiError::DatabaseUnavailable,
+            e => ApiError::Internal(format!("{}: {}", context, e)),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = serde_json::json!({
+            "error": self.message(),
+            "code": self.code(),
+        });
+        (self.status(), Json(body)).into_response()
+    }
+}
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // ORE STATS ENDPOINTS
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -283,142 +456,642 @@ async fn bot_status(
 /// Get live round data (5x5 grid, deployments, miners, timing)
 async fn ore_live_round(
     State(state): State<AppState>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
-    match state.get_ore_stats().await {
-        Ok(stats) => {
-            match stats.get_live_round() {
-                Ok(live) => Ok(Json(serde_json::json!(live))),
-                Err(e) => {
-                    error!("Failed to get live round: {}", e);
-                    Ok(Json(serde_json::json!({
-                        "error": format!("Failed to fetch live round: {}", e)
-                    })))
-                }
-            }
-        }
-        Err(e) => {
-            error!("Failed to create OreStatsService: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
-        }
-    }
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let stats = state.ore_stats_service().await?;
+    let live = stats.get_live_round().map_err(|e| {
+        error!("Failed to get live round: {}", e);
+        ApiError::Rpc(format!("Failed to fetch live round: {}", e))
+    })?;
+    Ok(Json(serde_json::json!(live)))
 }
 
 /// Get comprehensive ORE stats (live + protocol + history)
 async fn ore_full_stats(
     State(state): State<AppState>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
-    match state.get_ore_stats().await {
-        Ok(stats) => {
-            match stats.get_full_stats() {
-                Ok(full) => Ok(Json(serde_json::json!(full))),
-                Err(e) => {
-                    error!("Failed to get full stats: {}", e);
-                    Ok(Json(serde_json::json!({
-                        "error": format!("Failed to fetch stats: {}", e)
-                    })))
-                }
-            }
-        }
-        Err(e) => {
-            error!("Failed to create OreStatsService: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
-        }
-    }
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let stats = state.ore_stats_service().await?;
+    let full = stats.get_full_stats().map_err(|e| {
+        error!("Failed to get full stats: {}", e);
+        ApiError::Rpc(format!("Failed to fetch stats: {}", e))
+    })?;
+    Ok(Json(serde_json::json!(full)))
 }
 
 /// Get protocol-wide stats (treasury, motherlode, staking)
 async fn ore_protocol_stats(
     State(state): State<AppState>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
-    match state.get_ore_stats().await {
-        Ok(stats) => {
-            match stats.get_protocol_stats() {
-                Ok(protocol) => Ok(Json(serde_json::json!(protocol))),
-                Err(e) => {
-                    error!("Failed to get protocol stats: {}", e);
-                    Ok(Json(serde_json::json!({
-                        "error": format!("Failed to fetch protocol stats: {}", e)
-                    })))
-                }
-            }
-        }
-        Err(e) => {
-            error!("Failed to create OreStatsService: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
-        }
-    }
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let stats = state.ore_stats_service().await?;
+    let protocol = stats.get_protocol_stats().map_err(|e| {
+        error!("Failed to get protocol stats: {}", e);
+        ApiError::Rpc(format!("Failed to fetch protocol stats: {}", e))
+    })?;
+    Ok(Json(serde_json::json!(protocol)))
 }
 
 /// Get round history (last 20 completed rounds)
 async fn ore_round_history(
     State(state): State<AppState>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
-    match state.get_ore_stats().await {
-        Ok(stats) => {
-            match stats.get_round_history(20) {
-                Ok(history) => Ok(Json(serde_json::json!({
-                    "rounds": history,
-                    "count": history.len()
-                }))),
-                Err(e) => {
-                    error!("Failed to get round history: {}", e);
-                    Ok(Json(serde_json::json!({
-                        "error": format!("Failed to fetch history: {}", e)
-                    })))
-                }
-            }
-        }
-        Err(e) => {
-            error!("Failed to create OreStatsService: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
-        }
-    }
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let stats = state.ore_stats_service().await?;
+    let history = stats.get_round_history(20).map_err(|e| {
+        error!("Failed to get round history: {}", e);
+        ApiError::Rpc(format!("Failed to fetch history: {}", e))
+    })?;
+    Ok(Json(serde_json::json!({
+        "rounds": history,
+        "count": history.len()
+    })))
 }
 
 /// Get square analysis (win rates, patterns)
 async fn ore_square_analysis(
     State(state): State<AppState>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
-    match state.get_ore_stats().await {
-        Ok(stats) => {
-            match stats.analyze_squares(100) {
-                Ok(analysis) => Ok(Json(serde_json::json!({
-                    "squares": analysis
-                }))),
-                Err(e) => {
-                    error!("Failed to analyze squares: {}", e);
-                    Ok(Json(serde_json::json!({
-                        "error": format!("Failed to analyze squares: {}", e)
-                    })))
-                }
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let stats = state.ore_stats_service().await?;
+    let analysis = stats.analyze_squares(100).map_err(|e| {
+        error!("Failed to analyze squares: {}", e);
+        ApiError::Rpc(format!("Failed to analyze squares: {}", e))
+    })?;
+    Ok(Json(serde_json::json!({
+        "squares": analysis
+    })))
+}
+
+/// Get bot recommendations (which squares to deploy on)
+#[derive(Debug, Deserialize)]
+struct RecommendationsQuery {
+    /// Winner share (0-1) the per-square bet sizes aim for
+    target_share: Option<f64>,
+}
+
+async fn ore_recommendations(
+    State(state): State<AppState>,
+    Query(query): Query<RecommendationsQuery>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let target_share = query.target_share.unwrap_or(DEFAULT_TARGET_SHARE);
+    if !(target_share > 0.0 && target_share < 1.0) {
+        return Err(ApiError::BadRequest("target_share must be between 0 and 1".to_string()));
+    }
+    let stats = state.ore_stats_service().await?;
+    let recs = stats.get_bot_recommendations(target_share).map_err(|e| {
+        error!("Failed to get recommendations: {}", e);
+        ApiError::Rpc(format!("Failed to get recommendations: {}", e))
+    })?;
+    Ok(Json(serde_json::json!(recs)))
+}
+
+#[derive(Debug, Deserialize)]
+struct InstructionStatsQuery {
+    /// Number of recent ORE program transactions to count over
+    limit: Option<usize>,
+}
+
+/// Get per-instruction-type counts over recent ORE program transactions
+async fn ore_instruction_stats(
+    State(state): State<AppState>,
+    Query(query): Query<InstructionStatsQuery>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let limit = query.limit.unwrap_or(100).clamp(1, 1000);
+
+    let mut parser = BlockchainParser::new(&state.rpc_url).map_err(|e| {
+        error!("Failed to create BlockchainParser: {}", e);
+        ApiError::Internal(format!("Failed to create BlockchainParser: {}", e))
+    })?;
+
+    let transactions = parser.fetch_recent_transactions(limit).map_err(|e| {
+        error!("Failed to fetch transactions: {}", e);
+        ApiError::Rpc(format!("Failed to fetch transactions: {}", e))
+    })?;
+    let counts = parser.instruction_stats();
+
+    Ok(Json(serde_json::json!({
+        "window": limit,
+        "transactions": transactions.len(),
+        "instructions": counts,
+    })))
+}
+
+/// Shorten a wallet address to `abcd...wxyz` for public listings
+fn mask_address(address: &str) -> String {
+    if address.len() <= 8 {
+        return address.to_string();
+    }
+    format!("{}...{}", &address[..4], &address[address.len() - 4..])
+}
+
+/// Shape winner rows from `SharedDb::get_round_winners`, largest share first
+fn round_winners_json(round_id: i64, mut winners: Vec<(String, i64, i64, f32, f32)>) -> serde_json::Value {
+    winners.sort_by(|a, b| b.3.partial_cmp(&a.3).unwrap_or(std::cmp::Ordering::Equal));
+
+    let rows: Vec<serde_json::Value> = winners
+        .iter()
+        .map(|(address, bet, won, share, ore)| {
+            serde_json::json!({
+                "winner": mask_address(address),
+                "amount_bet_sol": lamports_to_sol(*bet),
+                "amount_won_sol": lamports_to_sol(*won),
+                // Stored as the 0..1 fraction of the winning square
+                "share_pct": f64::from(*share) * 100.0,
+                "ore_earned": ore,
+            })
+        })
+        .collect();
+
+    serde_json::json!({
+        "round_id": round_id,
+        "count": rows.len(),
+        "winners": rows,
+    })
+}
+
+/// List every recorded winner of a round
+async fn ore_round_winners(
+    State(state): State<AppState>,
+    Path(round_id): Path<i64>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let db = state.require_db()?;
+
+    let winners = db.get_round_winners(round_id).await.map_err(|e| {
+        error!("Failed to load winners for round {}: {}", round_id, e);
+        ApiError::database("Failed to load winners", e)
+    })?;
+
+    Ok(Json(round_winners_json(round_id, winners)))
+}
+
+#[derive(Debug, Deserialize)]
+struct RecentRoundsQuery {
+    limit: Option<i64>,
+}
+
+/// Recently completed rounds with winner and pot, from the database
+async fn ore_recent_rounds(
+    State(state): State<AppState>,
+    Query(query): Query<RecentRoundsQuery>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let db = state.require_db()?;
+    let limit = query.limit.unwrap_or(20).clamp(1, 500);
+
+    let rounds = db.get_recent_completed_rounds(limit).await.map_err(|e| {
+        error!("Failed to load recent rounds: {}", e);
+        ApiError::database("Failed to load recent rounds", e)
+    })?;
+
+    Ok(Json(serde_json::json!({
+        "count": rounds.len(),
+        "rounds": rounds,
+    })))
+}
+
+#[derive(Debug, Deserialize)]
+struct OreAccuracyQuery {
+    /// Number of recent played rounds to score
+    limit: Option<i64>,
+}
+
+/// Mean error and bias of the decision-time ORE estimate vs realized ORE
+async fn ore_estimate_accuracy(
+    State(state): State<AppState>,
+    Query(query): Query<OreAccuracyQuery>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let db = state.require_db()?;
+    let limit = query.limit.unwrap_or(200).clamp(1, 5000);
+
+    let rows = db.get_ore_estimate_pairs(limit).await.map_err(|e| {
+        error!("Failed to load ORE estimates: {}", e);
+        ApiError::database("Failed to load ORE estimates", e)
+    })?;
+
+    let pairs: Vec<(f64, f64)> = rows.iter().map(|(_, e, r)| (*e as f64, *r as f64)).collect();
+
+    Ok(Json(serde_json::json!({
+        "window": limit,
+        "estimate_error": OreEstimateError::from_pairs(&pairs),
+        "newest_round": rows.first().map(|(round_id, _, _)| round_id),
+    })))
+}
+
+/// Which source decided each completed round's winner, and how often the sources disagreed
+async fn ore_winner_sources(State(state): State<AppState>) -> Result<Json<serde_json::Value>, ApiError> {
+    let db = state.require_db()?;
+
+    let sources = db.get_winner_source_stats().await.map_err(|e| {
+        error!("Failed to load winner source stats: {}", e);
+        ApiError::database("Failed to load winner source stats", e)
+    })?;
+
+    let rounds: u64 = sources.iter().map(|s| s.rounds).sum();
+    let disagreements: u64 = sources.iter().map(|s| s.disagreements).sum();
+
+    Ok(Json(serde_json::json!({
+        "rounds": rounds,
+        "disagreement_rate": if rounds > 0 { disagreements as f64 / rounds as f64 } else { 0.0 },
+        "sources": sources,
+    })))
+}
+
+#[derive(Debug, Deserialize)]
+struct CompetitionDistributionQuery {
+    /// Number of recent rounds to bin
+    limit: Option<i64>,
+}
+
+/// Histograms of pot size and deployer count over recent rounds
+async fn ore_competition_distribution(
+    State(state): State<AppState>,
+    Query(query): Query<CompetitionDistributionQuery>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let db = state.require_db()?;
+    let limit = query.limit.unwrap_or(500).clamp(1, 10_000);
+
+    let rounds = db.get_recent_round_competition(limit).await.map_err(|e| {
+        error!("Failed to load round conditions: {}", e);
+        ApiError::database("Failed to load round conditions", e)
+    })?;
+
+    Ok(Json(serde_json::json!({
+        "window": limit,
+        "distribution": CompetitionDistribution::from_rounds(&rounds),
+    })))
+}
+
+#[derive(Debug, Deserialize)]
+struct ReconcileQuery {
+    wallet: String,
+    /// Number of wallet transactions to scan (RPC returns at most 1000)
+    limit: Option<usize>,
+    /// Allowed drift before the ledger is flagged
+    tolerance_pct: Option<f64>,
+}
+
+/// Compare recorded deploys/wins for a wallet against its on-chain ORE history
+async fn ore_reconcile(
+    State(state): State<AppState>,
+    Query(query): Query<ReconcileQuery>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let wallet = Pubkey::from_str(&query.wallet)
+        .map_err(|_| ApiError::BadRequest(format!("Invalid wallet address: {}", query.wallet)))?;
+    let db = state.require_db()?;
+
+    let parser = BlockchainParser::new(&state.rpc_url).map_err(|e| {
+        error!("Failed to create BlockchainParser: {}", e);
+        ApiError::Internal(format!("Failed to create BlockchainParser: {}", e))
+    })?;
+
+    let history = parser
+        .fetch_wallet_transactions(&wallet, query.limit.unwrap_or(1000))
+        .map_err(|e| {
+            error!("Failed to fetch wallet history: {}", e);
+            ApiError::Rpc(format!("Failed to fetch wallet history: {}", e))
+        })?;
+
+    // Only recorded deploys from the slots the fetched history covers are compared
+    let recorded_deploys: Vec<(u64, u64)> = match history.iter().map(|tx| tx.slot).min() {
+        Some(from_slot) => db
+            .get_player_deploys_since(&query.wallet, from_slot as i64)
+            .await
+            .map_err(|e| {
+                error!("Failed to load recorded deploys: {}", e);
+                ApiError::database("Failed to load recorded deploys", e)
+            })?
+            .into_iter()
+            .map(|(slot, amount)| (slot as u64, amount.max(0) as u64))
+            .collect(),
+        None => Vec::new(),
+    };
+
+    let (_, recorded_won, _, _) = db
+        .get_player_totals(&query.wallet)
+        .await
+        .map_err(|e| {
+            error!("Failed to load recorded totals: {}", e);
+            ApiError::database("Failed to load recorded totals", e)
+        })?
+        .unwrap_or((0, 0, 0, 0));
+
+    let onchain_rewards_sol = parser.get_miner(wallet).ok().flatten().map(|m| m.lifetime_rewards_sol);
+
+    let report = reconcile_ledger(
+        &query.wallet,
+        &recorded_deploys,
+        recorded_won.max(0) as u64,
+        &history,
+        onchain_rewards_sol,
+        query.tolerance_pct.unwrap_or(5.0),
+    );
+
+    if !report.within_tolerance {
+        warn!("⚠️ Ledger drift for {}: {:.1}% ({} lamports)",
+            query.wallet, report.drift_pct, report.deployed_discrepancy);
+    }
+
+    Ok(Json(serde_json::json!(report)))
+}
+
+/// Bot names that publish their config to `bot_state['<bot>_config']`
+const CONFIG_BOTS: [&str; 7] = [
+    "coordinator",
+    "miner-bot",
+    "analytics-bot",
+    "betting-bot",
+    "learning-bot",
+    "monitor-bot",
+    "parser-bot",
+];
+
+#[derive(Debug, Deserialize)]
+struct EffectiveConfigQuery {
+    /// Single bot to show (defaults to every bot that has published one)
+    bot: Option<String>,
+}
+
+/// Sanitized config each running bot actually loaded (env, file or defaults)
+async fn ore_effective_config(
+    State(state): State<AppState>,
+    Query(query): Query<EffectiveConfigQuery>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let db = state.require_db()?;
+
+    let bots: Vec<String> = match query.bot {
+        Some(bot) => vec![bot],
+        None => CONFIG_BOTS.iter().map(|b| b.to_string()).collect(),
+    };
+
+    let mut configs = serde_json::Map::new();
+    for bot in bots {
+        match db.get_state(&format!("{}_config", bot)).await {
+            Ok(Some(config)) => {
+                configs.insert(bot, config);
+            }
+            Ok(None) => {}
+            Err(e) => {
+                error!("Failed to load config for {}: {}", bot, e);
+                return Err(ApiError::database(&format!("Failed to load config for {}", bot), e));
             }
-        }
-        Err(e) => {
-            error!("Failed to create OreStatsService: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
         }
     }
+
+    Ok(Json(serde_json::Value::Object(configs)))
 }
 
-/// Get bot recommendations (which squares to deploy on)
-async fn ore_recommendations(
+// ═══════════════════════════════════════════════════════════════════════════════
+// ADMIN ENDPOINTS
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Check the `Authorization: Bearer <token>` header against the admin token
+/// Admin endpoints are disabled entirely when no token is configured
+fn is_authorized(headers: &HeaderMap, admin_token: Option<&str>) -> bool {
+    let Some(expected) = admin_token else {
+        return false;
+    };
+
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(|token| token == expected)
+        .unwrap_or(false)
+}
+
+/// Run a detection pass over stored wins (rows from `SharedDb::load_wins`)
+fn detect_strategies_from_wins(wins: &[serde_json::Value]) -> Vec<DetectedStrategy> {
+    let records: Vec<WinRecord> = wins.iter().filter_map(WinRecord::from_db_json).collect();
+    let mut engine = LearningEngine::new();
+    engine.detect_from_wins(records).to_vec()
+}
+
+/// Force a strategy detection run over all recorded wins and persist the results
+async fn ore_detect_strategies(
     State(state): State<AppState>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
-    match state.get_ore_stats().await {
-        Ok(stats) => {
-            match stats.get_bot_recommendations() {
-                Ok(recs) => Ok(Json(serde_json::json!(recs))),
-                Err(e) => {
-                    error!("Failed to get recommendations: {}", e);
-                    Ok(Json(serde_json::json!({
-                        "error": format!("Failed to get recommendations: {}", e)
-                    })))
-                }
+    headers: HeaderMap,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    if !is_authorized(&headers, state.admin_token.as_deref()) {
+        return Err(ApiError::Unauthorized);
+    }
+
+    let db = state.require_db()?;
+
+    let wins = db.load_wins(1000).await.map_err(|e| {
+        error!("Failed to load wins: {}", e);
+        ApiError::database("Failed to load wins", e)
+    })?;
+
+    let strategies = detect_strategies_from_wins(&wins);
+    info!("🧠 Detection run over {} wins found {} strategies", wins.len(), strategies.len());
+
+    for s in &strategies {
+        if let Err(e) = db.save_detected_strategy(
+            &s.name,
+            &s.description,
+            s.sample_size as i32,
+            s.win_rate as f32,
+            s.avg_roi as f32,
+            s.avg_ore_per_round as f32,
+            s.square_count as i16,
+            s.bet_size_sol as f32,
+            &s.target_competition,
+            &s.preferred_squares.iter().map(|&sq| sq as i32).collect::<Vec<_>>(),
+            s.play_motherlode,
+            s.confidence as f32,
+            s.consistent,
+            &s.examples,
+        ).await {
+            warn!("Failed to save strategy {}: {}", s.name, e);
+        }
+    }
+
+    Ok(Json(detection_report(wins.len(), &strategies)))
+}
+
+/// Response body for a detection run
+fn detection_report(wins_analyzed: usize, strategies: &[DetectedStrategy]) -> serde_json::Value {
+    serde_json::json!({
+        "wins_analyzed": wins_analyzed,
+        "count": strategies.len(),
+        "strategies": strategies,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_winners_sorted_by_share() {
+        let seeded = vec![
+            ("SmallFish11111111111111111111111111111111111".to_string(), 10_000_000, 20_000_000, 0.05, 0.05),
+            ("WhaleWallet1111111111111111111111111111111111".to_string(), 500_000_000, 900_000_000, 0.7, 0.7),
+            ("MidPlayer111111111111111111111111111111111111".to_string(), 100_000_000, 180_000_000, 0.25, 0.25),
+        ];
+
+        let json = round_winners_json(4242, seeded);
+        let winners = json["winners"].as_array().unwrap();
+
+        assert_eq!(json["round_id"], 4242);
+        assert_eq!(json["count"], 3);
+        let shares: Vec<f64> = winners.iter().map(|w| w["share_pct"].as_f64().unwrap()).collect();
+        for (share, expected) in shares.iter().zip([70.0, 25.0, 5.0]) {
+            assert!((share - expected).abs() < 1e-4, "{} vs {}", share, expected);
+        }
+        assert_eq!(winners[0]["winner"], "Whal...1111");
+        assert_eq!(winners[0]["amount_bet_sol"], 0.5);
+        assert!(!json.to_string().contains("WhaleWallet"));
+    }
+
+    #[test]
+    fn test_admin_auth() {
+        let mut headers = HeaderMap::new();
+        assert!(!is_authorized(&headers, Some("secret")));
+
+        headers.insert(header::AUTHORIZATION, "Bearer wrong".parse().unwrap());
+        assert!(!is_authorized(&headers, Some("secret")));
+
+        headers.insert(header::AUTHORIZATION, "Bearer secret".parse().unwrap());
+        assert!(is_authorized(&headers, Some("secret")));
+
+        // No token configured = admin endpoints disabled
+        assert!(!is_authorized(&headers, None));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_rpc_failure_returns_bad_gateway() {
+        let mut state = AppState::new();
+        state.rpc_url = "http://127.0.0.1:1".to_string(); // nothing listens here
+
+        let response = ore_live_round(State(state)).await.unwrap_err().into_response();
+        assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["code"], "rpc_error");
+        assert!(json["error"].as_str().unwrap().starts_with("Failed to fetch live round"));
+
+        // No database is a 503 with the same body shape
+        assert_eq!(ApiError::DatabaseUnavailable.into_response().status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        // A database outage is a 503 too; other query failures stay 500
+        let outage = ApiError::database("Failed to load wins", BotError::DatabaseUnavailable("pool timed out".to_string()));
+        assert_eq!(outage.into_response().status(), StatusCode::SERVICE_UNAVAILABLE);
+        let failed = ApiError::database("Failed to load wins", BotError::Other("syntax error".to_string()));
+        assert_eq!(failed.into_response().status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    /// Collects formatted log output so tests can check the request span
+    #[derive(Clone, Default)]
+    struct LogCapture(Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for LogCapture {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_request_logged_with_span_fields() {
+        use tower::ServiceExt;
+
+        let capture = LogCapture::default();
+        let writer = capture.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let state = AppState::new();
+        let metrics = state.metrics.clone();
+        let response = router(state)
+            .oneshot(axum::http::Request::builder().uri("/api/bots/miner/status").body(axum::body::Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let logs = String::from_utf8(capture.0.lock().unwrap().clone()).unwrap();
+        let line = logs.lines().find(|l| l.contains("request completed")).expect("request was not logged");
+        assert!(line.contains("method=GET"), "{}", line);
+        assert!(line.contains("path=/api/bots/:id/status"), "{}", line);
+        assert!(line.contains("status=200"), "{}", line);
+        assert!(line.contains("latency_ms="), "{}", line);
+
+        // The same request landed in the route's histogram
+        let routes = metrics.snapshot();
+        assert_eq!(routes[0].route, "/api/bots/:id/status");
+        assert_eq!(routes[0].latency.count, 1);
+    }
+
+    #[test]
+    fn test_detect_strategies_from_wins() {
+        let wins: Vec<serde_json::Value> = (0..30)
+            .map(|i| serde_json::json!({
+                "round_id": 5000 + i,
+                "winner": format!("Player{:04}", i),
+                "winning_square": 3,
+                "amount_bet": 20_000_000,
+                "amount_won": 40_000_000,
+                "squares_bet": [3],
+                "num_squares": 1,
+                "total_round_sol": 300_000_000,
+                "num_deployers": 4,
+                "is_motherlode": false,
+                "is_full_ore": true,
+                "ore_earned": 1.0,
+            }))
+            .collect();
+
+        let strategies = detect_strategies_from_wins(&wins);
+
+        assert!(!strategies.is_empty());
+        assert!(strategies.iter().any(|s| s.name == "Full ORE Winner" && s.sample_size == 30));
+
+        let report = detection_report(wins.len(), &strategies);
+        assert_eq!(report["wins_analyzed"], 30);
+        assert_eq!(report["count"], strategies.len());
+        let first = &report["strategies"][0];
+        for field in ["name", "win_rate", "square_count", "preferred_squares", "confidence"] {
+            assert!(!first[field].is_null(), "missing {} in {}", field, first);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_detect_strategies_endpoint_rejects_and_reports_errors() {
+        use tower::ServiceExt;
+
+        async fn detect(state: AppState, auth: Option<&str>) -> (StatusCode, serde_json::Value) {
+            let mut request = axum::http::Request::builder().method(Method::POST).uri("/api/ore/detect-strategies");
+            if let Some(auth) = auth {
+                request = request.header(header::AUTHORIZATION, auth);
             }
+            let response = router(state).oneshot(request.body(axum::body::Body::empty()).unwrap()).await.unwrap();
+            let status = response.status();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            (status, serde_json::from_slice(&body).unwrap())
         }
-        Err(e) => {
-            error!("Failed to create OreStatsService: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+
+        let mut state = AppState::new();
+        state.admin_token = Some("secret".to_string());
+
+        for auth in [None, Some("Bearer wrong"), Some("secret")] {
+            let (status, json) = detect(state.clone(), auth).await;
+            assert_eq!(status, StatusCode::UNAUTHORIZED, "{:?}", auth);
+            assert_eq!(json["code"], "unauthorized");
+            assert_eq!(json["error"], "Missing or invalid admin token");
         }
+
+        // No admin token configured rejects even a well-formed header
+        let mut disabled = state.clone();
+        disabled.admin_token = None;
+        let (status, _) = detect(disabled, Some("Bearer secret")).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        // Authorized, but there's no database to read wins from
+        let (status, json) = detect(state, Some("Bearer secret")).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(json["code"], "database_unavailable");
     }
 }
@@ -1,15 +1,22 @@
 use axum::{
-    extract::{ws::WebSocket, State, WebSocketUpgrade},
-    response::{Html, IntoResponse},
+    extract::{ws::WebSocket, MatchedPath, Request, State, WebSocketUpgrade},
+    middleware::{self, Next},
+    response::{Html, IntoResponse, Response},
     routing::{get, post},
     Json, Router,
 };
+use clawdbot::db::{is_database_available, SharedDb};
+use clawdbot::request_metrics::{RequestMetrics, LATENCY_BUCKETS_MS};
+use clawdbot::state_store::read_snapshot_key;
+use clawdbot::strategies::StrategyContribution;
+use clawdbot::units::lamports_to_sol;
 use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use tower_http::services::ServeDir;
 use tower_http::cors::{CorsLayer, Any};
+use tracing::{field, Instrument};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 mod bot_manager;
@@ -18,9 +25,15 @@ use bot_manager::BotManager;
 #[derive(Clone)]
 struct AppState {
     bot_manager: Arc<Mutex<BotManager>>,
-    db_url: Option<String>,
+    db: Option<Arc<SharedDb>>,
+    /// Coordinator state file written when it runs without a database
+    state_snapshot: Option<std::path::PathBuf>,
+    metrics: RequestMetrics,
 }
 
+/// Routes the dashboard polls constantly; their request logs are sampled
+const HOT_ROUTES: [&str; 2] = ["/api/state", "/api/bots"];
+
 #[tokio::main]
 async fn main() {
     // Initialize tracing
@@ -34,8 +47,20 @@ async fn main() {
 
     // Create bot manager
     let bot_manager = Arc::new(Mutex::new(BotManager::new()));
-    let db_url = std::env::var("DATABASE_URL").ok();
-    let state = AppState { bot_manager, db_url };
+    let db = if is_database_available() {
+        match SharedDb::connect().await {
+            Ok(db) => Some(Arc::new(db)),
+            Err(e) => {
+                tracing::warn!("Database connection failed: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let state_snapshot = std::env::var("STATE_SNAPSHOT_PATH").ok().map(std::path::PathBuf::from);
+    let metrics = RequestMetrics::new(&HOT_ROUTES);
+    let state = AppState { bot_manager, db, state_snapshot, metrics };
 
     // CORS layer for development
     let cors = CorsLayer::new()
@@ -51,8 +76,10 @@ async fn main() {
         .route("/api/bot/start", post(start_bot))
         .route("/api/bot/stop", post(stop_bot))
         .route("/ws", get(ws_handler))
+        .route("/api/metrics", get(request_metrics))
         .nest_service("/static", ServeDir::new("static"))
         .nest_service("/assets", ServeDir::new("../ore-dashboard/assets"))
+        .layer(middleware::from_fn_with_state(state.metrics.clone(), track_requests))
         .layer(cors)
         .with_state(state);
 
@@ -70,6 +97,44 @@ async fn index_handler() -> impl IntoResponse {
     Html(include_str!("../static/index.html"))
 }
 
+/// Time each request, record it in the latency histograms and log it (sampled for hot routes)
+async fn track_requests(State(metrics): State<RequestMetrics>, request: Request, next: Next) -> Response {
+    let method = request.method().clone();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+
+    let span = tracing::info_span!(
+        "request",
+        method = %method,
+        path = %route,
+        status = field::Empty,
+        latency_ms = field::Empty,
+    );
+    let start = std::time::Instant::now();
+    let response = next.run(request).instrument(span.clone()).await;
+    let latency_ms = start.elapsed().as_secs_f64() * 1000.0;
+    let status = response.status().as_u16();
+
+    span.record("status", status);
+    span.record("latency_ms", field::display(format!("{:.1}", latency_ms)));
+    if metrics.record(method.as_str(), &route, status, latency_ms) {
+        span.in_scope(|| tracing::info!("request completed"));
+    }
+
+    response
+}
+
+/// Request counts and latency histograms per method and route
+async fn request_metrics(State(state): State<AppState>) -> Json<serde_json::Value> {
+    Json(serde_json::json!({
+        "buckets_ms": LATENCY_BUCKETS_MS,
+        "routes": state.metrics.snapshot(),
+    }))
+}
+
 // Dashboard state response for the Dioxus frontend
 #[derive(Serialize)]
 struct DashboardState {
@@ -77,6 +142,17 @@ struct DashboardState {
     last_winner: Option<WinnerInfo>,
     stats: Option<DashboardStats>,
     recent_rounds: Option<Vec<RecentRound>>,
+    recommendation: Option<Recommendation>,
+}
+
+/// The coordinator's consensus pick and the votes behind it
+#[derive(Serialize, Debug, Default)]
+struct Recommendation {
+    /// 1-25
+    squares: Vec<usize>,
+    confidence: f64,
+    /// Per square (index 0 = square 1): strategies that voted for it and their weights
+    contributions: Vec<Vec<StrategyContribution>>,
 }
 
 #[derive(Serialize, Default)]
@@ -89,6 +165,9 @@ struct BoardState {
     time_remaining_secs: u64,
     round_duration_secs: u64,
     slots_remaining: u64,
+    num_deployers: u64,
+    /// Per-square "rising" / "steady" / "flat" fill trend this round
+    competition_trend: Vec<String>,
 }
 
 #[derive(Serialize)]
@@ -116,119 +195,121 @@ struct RecentRound {
     is_motherlode: bool,
 }
 
+/// Board from the coordinator's `monitor_status` state
+fn board_from_status(status: &serde_json::Value) -> BoardState {
+    // Parse deployed squares
+    let mut deployed = [0u64; 25];
+    if let Some(deployed_arr) = status.get("deployed_squares").and_then(|v| v.as_array()) {
+        for (i, val) in deployed_arr.iter().enumerate() {
+            if i < 25 {
+                deployed[i] = val.as_u64().unwrap_or(0);
+            }
+        }
+    }
+
+    BoardState {
+        round_id: status.get("round_id").and_then(|v| v.as_u64()).unwrap_or(0),
+        start_slot: status.get("start_slot").and_then(|v| v.as_u64()).unwrap_or(0),
+        end_slot: status.get("end_slot").and_then(|v| v.as_u64()).unwrap_or(0),
+        current_slot: status.get("current_slot").and_then(|v| v.as_u64()).unwrap_or(0),
+        deployed,
+        time_remaining_secs: status.get("time_remaining_secs").and_then(|v| v.as_u64()).unwrap_or(0),
+        round_duration_secs: status.get("round_duration_secs").and_then(|v| v.as_u64()).unwrap_or(60),
+        slots_remaining: status.get("slots_remaining").and_then(|v| v.as_u64()).unwrap_or(0),
+        num_deployers: status.get("num_deployers").and_then(|v| v.as_u64()).unwrap_or(0),
+        competition_trend: status.get("competition_trend")
+            .and_then(|v| v.as_array())
+            .map(|arr| arr.iter().filter_map(|t| t.as_str().map(String::from)).collect())
+            .unwrap_or_default(),
+    }
+}
+
+/// Recommendation from the coordinator's `consensus_recommendation` state
+/// Contributions are empty when the coordinator didn't publish them
+fn recommendation_from_state(rec: &serde_json::Value) -> Option<Recommendation> {
+    let squares: Vec<usize> = rec.get("squares")?
+        .as_array()?
+        .iter()
+        .filter_map(|s| s.as_u64().map(|n| n as usize))
+        .collect();
+    if squares.is_empty() {
+        return None;
+    }
+
+    Some(Recommendation {
+        squares,
+        confidence: rec.get("confidence").and_then(|v| v.as_f64()).unwrap_or(0.0),
+        contributions: rec.get("contributions")
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default(),
+    })
+}
+
+/// Rounds shown in the recent rounds list
+const RECENT_ROUNDS: i64 = 10;
+
 async fn get_dashboard_state(State(state): State<AppState>) -> Json<DashboardState> {
     // Try to fetch from database if available
-    if let Some(ref db_url) = state.db_url {
-        if let Ok(pool) = sqlx::postgres::PgPoolOptions::new()
-            .max_connections(1)
-            .connect(db_url)
-            .await
-        {
-            // Get monitor_status from bot_state table
-            if let Ok(row) = sqlx::query_as::<_, (serde_json::Value,)>(
-                "SELECT state_value FROM bot_state WHERE state_key = 'monitor_status'"
-            )
-            .fetch_one(&pool)
-            .await
-            {
-                let status = &row.0;
-                
-                // Parse deployed squares
-                let mut deployed = [0u64; 25];
-                if let Some(deployed_arr) = status.get("deployed_squares").and_then(|v| v.as_array()) {
-                    for (i, val) in deployed_arr.iter().enumerate() {
-                        if i < 25 {
-                            deployed[i] = val.as_u64().unwrap_or(0);
-                        }
-                    }
-                }
-                
-                let board = BoardState {
-                    round_id: status.get("round_id").and_then(|v| v.as_u64()).unwrap_or(0),
-                    start_slot: status.get("start_slot").and_then(|v| v.as_u64()).unwrap_or(0),
-                    end_slot: status.get("end_slot").and_then(|v| v.as_u64()).unwrap_or(0),
-                    current_slot: status.get("current_slot").and_then(|v| v.as_u64()).unwrap_or(0),
-                    deployed,
-                    time_remaining_secs: status.get("time_remaining_secs").and_then(|v| v.as_u64()).unwrap_or(0),
-                    round_duration_secs: status.get("round_duration_secs").and_then(|v| v.as_u64()).unwrap_or(60),
-                    slots_remaining: status.get("slots_remaining").and_then(|v| v.as_u64()).unwrap_or(0),
-                };
-                
-                // Get last winner
-                let last_winner = sqlx::query_as::<_, (i64, i16, i64, bool, Option<chrono::DateTime<chrono::Utc>>)>(
-                    "SELECT round_id, winning_square, total_pot, is_motherlode, timestamp 
-                     FROM wins ORDER BY round_id DESC LIMIT 1"
-                )
-                .fetch_optional(&pool)
-                .await
+    if let Some(ref db) = state.db {
+        if let Ok(Some(status)) = db.get_state("monitor_status").await {
+            let board = board_from_status(&status);
+            let rounds = db.get_recent_completed_rounds(RECENT_ROUNDS).await.unwrap_or_default();
+            
+            let last_winner = rounds.first().map(|r| WinnerInfo {
+                round_id: r.round_id,
+                winning_square: r.winning_square,
+                total_pot: r.total_pot,
+                is_motherlode: r.is_motherlode,
+                timestamp: r.completed_at.map(|t| t.to_rfc3339()),
+            });
+            
+            let recent_rounds = rounds.iter()
+                .map(|r| RecentRound {
+                    round_id: r.round_id,
+                    winning_square: r.winning_square,
+                    total_pot: lamports_to_sol(r.total_pot),
+                    is_motherlode: r.is_motherlode,
+                })
+                .collect();
+
+            let recommendation = db.get_state("consensus_recommendation").await
                 .ok()
                 .flatten()
-                .map(|(round_id, winning_square, total_pot, is_motherlode, timestamp)| {
-                    WinnerInfo {
-                        round_id: round_id as u64,
-                        winning_square: winning_square as u8,
-                        total_pot: total_pot as u64,
-                        is_motherlode,
-                        timestamp: timestamp.map(|t| t.to_rfc3339()),
-                    }
-                });
-                
-                // Get recent rounds
-                let recent_rounds = sqlx::query_as::<_, (i64, i16, i64, bool)>(
-                    "SELECT round_id, winning_square, total_pot, is_motherlode 
-                     FROM wins ORDER BY round_id DESC LIMIT 10"
-                )
-                .fetch_all(&pool)
-                .await
-                .ok()
-                .map(|rows| {
-                    rows.into_iter()
-                        .map(|(round_id, winning_square, total_pot, is_motherlode)| {
-                            RecentRound {
-                                round_id: round_id as u64,
-                                winning_square: winning_square as u8,
-                                total_pot: total_pot as f64 / 1_000_000_000.0,
-                                is_motherlode,
-                            }
-                        })
-                        .collect()
-                });
-                
-                // Get stats
-                let stats = sqlx::query_as::<_, (i64, i64, i64)>(
-                    "SELECT COUNT(*), COALESCE(SUM(total_pot), 0), 
-                            COUNT(*) FILTER (WHERE is_motherlode = true)
-                     FROM wins WHERE timestamp > NOW() - INTERVAL '24 hours'"
-                )
-                .fetch_one(&pool)
-                .await
-                .ok()
-                .map(|(rounds, total_pot, motherlodes)| {
-                    DashboardStats {
-                        total_rounds_today: rounds as u64,
-                        total_sol_deployed: total_pot as f64 / 1_000_000_000.0,
-                        avg_round_time: 55.0, // Could calculate from actual data
-                        motherlode_count: motherlodes as u64,
-                    }
-                })
-                .unwrap_or_default();
-                
-                return Json(DashboardState {
-                    board: Some(board),
-                    last_winner,
-                    stats: Some(stats),
-                    recent_rounds,
-                });
-            }
+                .and_then(|rec| recommendation_from_state(&rec));
+            
+            let today = db.get_completed_round_stats(24).await.unwrap_or_default();
+            let stats = DashboardStats {
+                total_rounds_today: today.rounds,
+                total_sol_deployed: lamports_to_sol(today.total_pot),
+                avg_round_time: 55.0, // Could calculate from actual data
+                motherlode_count: today.motherlodes,
+            };
+            
+            return Json(DashboardState {
+                board: Some(board),
+                last_winner,
+                stats: Some(stats),
+                recent_rounds: Some(recent_rounds),
+                recommendation,
+            });
         }
     }
     
-    // Return empty state if no database
+    // No database: show the live board from the coordinator's state file, without history
+    let snapshot_key = |key: &str| state.state_snapshot.as_ref()
+        .and_then(|path| read_snapshot_key(path, key).ok().flatten());
+    let board = snapshot_key("monitor_status")
+        .map(|status| board_from_status(&status))
+        .unwrap_or_default();
+    let recommendation = snapshot_key("consensus_recommendation")
+        .and_then(|rec| recommendation_from_state(&rec));
+
     Json(DashboardState {
-        board: Some(BoardState::default()),
+        board: Some(board),
         last_winner: None,
         stats: Some(DashboardStats::default()),
         recent_rounds: Some(vec![]),
+        recommendation,
     })
 }
 
@@ -336,3 +417,39 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_includes_contributions_when_available() {
+        let mut contributions = vec![serde_json::json!([]); 25];
+        contributions[3] = serde_json::json!([
+            { "strategy": "Hot Squares", "weight": 0.42 },
+            { "strategy": "Contrarian", "weight": 0.1 },
+        ]);
+        let rec = serde_json::json!({
+            "squares": [4, 9],
+            "weights": [0.6, 0.4],
+            "confidence": 0.55,
+            "contributions": contributions,
+        });
+
+        let recommendation = recommendation_from_state(&rec).unwrap();
+        assert_eq!(recommendation.squares, vec![4, 9]);
+        assert_eq!(recommendation.contributions.len(), 25);
+        assert_eq!(recommendation.contributions[3], vec![
+            StrategyContribution { strategy: "Hot Squares".to_string(), weight: 0.42 },
+            StrategyContribution { strategy: "Contrarian".to_string(), weight: 0.1 },
+        ]);
+
+        let json = serde_json::to_value(&recommendation).unwrap();
+        assert_eq!(json["contributions"][3][0]["strategy"], "Hot Squares");
+
+        // Older coordinators don't publish contributions: still show the pick
+        let older = recommendation_from_state(&serde_json::json!({ "squares": [4], "confidence": 0.5 })).unwrap();
+        assert!(older.contributions.is_empty());
+        assert!(recommendation_from_state(&serde_json::json!({ "squares": [] })).is_none());
+    }
+}
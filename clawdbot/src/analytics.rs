@@ -1,4 +1,6 @@
+use crate::blockchain_parser::{OreInstructionType, ParsedOreTransaction};
 use crate::error::Result;
+use crate::units::lamports_to_sol;
 use ore_api::state::{Miner, Round};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -345,3 +347,179 @@ impl Default for AnalyticsEngine {
         Self::new()
     }
 }
+
+/// Recorded deploy ledger vs on-chain history for one wallet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerReconciliation {
+    pub wallet: String,
+    /// Slots the fetched history covers; deploys on both sides are counted within them
+    pub from_slot: Option<u64>,
+    pub to_slot: Option<u64>,
+    pub recorded_deployed: u64,
+    pub recorded_deploy_count: u64,
+    /// Lifetime recorded winnings, compared with the lifetime rewards below
+    pub recorded_won: u64,
+    pub onchain_deployed: u64,
+    pub onchain_deploy_count: u64,
+    /// Lifetime SOL rewards from the Miner account, if it could be read
+    pub onchain_rewards_sol: Option<u64>,
+    /// recorded - on-chain (positive = double counted, negative = missed)
+    pub deployed_discrepancy: i64,
+    pub deploy_count_discrepancy: i64,
+    pub won_discrepancy: Option<i64>,
+    /// |deployed_discrepancy| as a % of on-chain deployed
+    pub drift_pct: f64,
+    pub within_tolerance: bool,
+}
+
+/// Compare recorded deploys, as (slot, lamports), against a wallet's ORE transaction history
+/// Only successful Deploy instructions made for the wallet (directly or by an executor) count as outflows,
+/// and only recorded deploys within the slots the history covers are compared with them
+pub fn reconcile_ledger(
+    wallet: &str,
+    recorded_deploys: &[(u64, u64)],
+    recorded_won: u64,
+    history: &[ParsedOreTransaction],
+    onchain_rewards_sol: Option<u64>,
+    tolerance_pct: f64,
+) -> LedgerReconciliation {
+    let from_slot = history.iter().map(|tx| tx.slot).min();
+    let to_slot = history.iter().map(|tx| tx.slot).max();
+    let in_window: Vec<u64> = match (from_slot, to_slot) {
+        (Some(from), Some(to)) => recorded_deploys.iter()
+            .filter(|(slot, _)| (from..=to).contains(slot))
+            .map(|&(_, amount)| amount)
+            .collect(),
+        _ => Vec::new(),
+    };
+    let recorded_deployed: u64 = in_window.iter().sum();
+    let recorded_deploy_count = in_window.len() as u64;
+
+    let deploys: Vec<_> = history.iter()
+        .filter(|tx| tx.success && tx.instruction_type == OreInstructionType::Deploy && tx.authority() == wallet)
+        .filter_map(|tx| tx.deploy_data.as_ref())
+        .collect();
+
+    let onchain_deployed: u64 = deploys.iter().map(|d| d.amount_lamports).sum();
+    let onchain_deploy_count = deploys.len() as u64;
+    let deployed_discrepancy = recorded_deployed as i64 - onchain_deployed as i64;
+
+    let drift_pct = if onchain_deployed > 0 {
+        deployed_discrepancy.unsigned_abs() as f64 / onchain_deployed as f64 * 100.0
+    } else if recorded_deployed > 0 {
+        100.0
+    } else {
+        0.0
+    };
+
+    LedgerReconciliation {
+        wallet: wallet.to_string(),
+        from_slot,
+        to_slot,
+        recorded_deployed,
+        recorded_deploy_count,
+        recorded_won,
+        onchain_deployed,
+        onchain_deploy_count,
+        onchain_rewards_sol,
+        deployed_discrepancy,
+        deploy_count_discrepancy: recorded_deploy_count as i64 - onchain_deploy_count as i64,
+        won_discrepancy: onchain_rewards_sol.map(|r| recorded_won as i64 - r as i64),
+        drift_pct,
+        within_tolerance: drift_pct <= tolerance_pct,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain_parser::DeployData;
+
+    const WALLET: &str = "OurWallet1111111111111111111111111111111111";
+
+    fn deploy_tx(signer: &str, amount_lamports: u64, success: bool) -> ParsedOreTransaction {
+        deploy_tx_at(signer, amount_lamports, success, 1000)
+    }
+
+    fn deploy_tx_at(signer: &str, amount_lamports: u64, success: bool, slot: u64) -> ParsedOreTransaction {
+        ParsedOreTransaction {
+            signature: format!("sig-{}-{}-{}", signer, amount_lamports, slot),
+            slot,
+            block_time: None,
+            instruction_type: OreInstructionType::Deploy,
+            signer: signer.to_string(),
+            accounts: vec![],
+            success,
+            deploy_data: Some(DeployData {
+                amount_lamports,
+                amount_sol: lamports_to_sol(amount_lamports),
+                squares_mask: 0b11,
+                squares: vec![0, 1],
+                num_squares: 2,
+            }),
+            automate_data: None,
+            deposit_data: None,
+            withdraw_data: None,
+            claim_yield_data: None,
+            reset_data: None,
+        }
+    }
+
+    #[test]
+    fn test_reconcile_ledger_discrepancy() {
+        let history = vec![
+            deploy_tx(WALLET, 10_000_000, true),
+            deploy_tx(WALLET, 20_000_000, true),
+            // Failed deploys and other wallets don't count
+            deploy_tx(WALLET, 50_000_000, false),
+            deploy_tx("SomeoneElse", 40_000_000, true),
+        ];
+
+        // Recorded one deploy twice (40M recorded vs 30M on-chain)
+        let recorded = [(1000, 10_000_000), (1000, 20_000_000), (1000, 10_000_000)];
+        let report = reconcile_ledger(WALLET, &recorded, 5_000_000, &history, Some(8_000_000), 5.0);
+
+        assert_eq!(report.onchain_deployed, 30_000_000);
+        assert_eq!(report.onchain_deploy_count, 2);
+        assert_eq!(report.deployed_discrepancy, 10_000_000);
+        assert_eq!(report.deploy_count_discrepancy, 1);
+        assert_eq!(report.won_discrepancy, Some(-3_000_000));
+        assert!((report.drift_pct - 33.333).abs() < 0.01);
+        assert!(!report.within_tolerance);
+    }
+
+    #[test]
+    fn test_reconcile_ledger_in_sync() {
+        let history = vec![deploy_tx(WALLET, 10_000_000, true)];
+        let report = reconcile_ledger(WALLET, &[(1000, 10_000_000)], 0, &history, None, 5.0);
+
+        assert_eq!(report.deployed_discrepancy, 0);
+        assert_eq!(report.won_discrepancy, None);
+        assert!(report.within_tolerance);
+    }
+
+    #[test]
+    fn test_reconcile_ledger_bounded_to_fetched_window() {
+        // RPC only returned the last two deploys; we've recorded a much longer history
+        let history = vec![
+            deploy_tx_at(WALLET, 10_000_000, true, 5_000),
+            deploy_tx_at(WALLET, 20_000_000, true, 5_200),
+        ];
+        let mut recorded: Vec<(u64, u64)> = (0..50).map(|i| (1_000 + i * 50, 30_000_000)).collect();
+        recorded.push((5_000, 10_000_000));
+        recorded.push((5_200, 20_000_000));
+
+        let report = reconcile_ledger(WALLET, &recorded, 0, &history, None, 5.0);
+
+        assert_eq!((report.from_slot, report.to_slot), (Some(5_000), Some(5_200)));
+        assert_eq!(report.recorded_deployed, 30_000_000);
+        assert_eq!(report.recorded_deploy_count, 2);
+        assert_eq!(report.deployed_discrepancy, 0);
+        assert!(report.within_tolerance);
+
+        // Nothing fetched: nothing to compare
+        let empty = reconcile_ledger(WALLET, &recorded, 0, &[], None, 5.0);
+        assert_eq!(empty.recorded_deploy_count, 0);
+        assert!(empty.within_tolerance);
+    }
+}
@@ -0,0 +1,153 @@
+//! Startup backfill of completed rounds
+//!
+//! On a cold start the coordinator walks back from the current round and
+//! stores completed rounds the database doesn't have yet. The walk is capped
+//! so cold-start time stays bounded, and progress is logged with an ETA.
+
+use crate::error::Result;
+use log::{info, warn};
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone)]
+pub struct BackfillConfig {
+    /// Rounds to walk back from the current one (0 = no backfill)
+    pub max_rounds: u64,
+    /// Log progress every this many rounds
+    pub log_every: u64,
+}
+
+impl Default for BackfillConfig {
+    fn default() -> Self {
+        Self {
+            max_rounds: 0,
+            log_every: 25,
+        }
+    }
+}
+
+/// What happened to one round
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackfillOutcome {
+    Stored,
+    AlreadyKnown,
+    /// Account closed or round never completed
+    Missing,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BackfillReport {
+    pub scanned: u64,
+    pub stored: u64,
+    pub already_known: u64,
+    pub missing: u64,
+    pub errors: u64,
+    /// Rounds done at each progress log
+    pub progress_points: Vec<u64>,
+}
+
+/// Round ids to backfill, newest first: at most `max_rounds` before `current_round`
+pub fn backfill_range(current_round: u64, max_rounds: u64) -> impl Iterator<Item = u64> {
+    (current_round.saturating_sub(max_rounds)..current_round).rev()
+}
+
+/// Remaining time at the rate so far
+pub fn eta(elapsed: Duration, done: u64, total: u64) -> Option<Duration> {
+    if done == 0 {
+        return None;
+    }
+    Some(elapsed.mul_f64(total.saturating_sub(done) as f64 / done as f64))
+}
+
+/// Walk back from `current_round`, calling `fetch` once per round
+/// Errors are counted and logged; the walk keeps going
+pub async fn backfill_rounds<F, Fut>(current_round: u64, config: &BackfillConfig, mut fetch: F) -> BackfillReport
+where
+    F: FnMut(u64) -> Fut,
+    Fut: Future<Output = Result<BackfillOutcome>>,
+{
+    let mut report = BackfillReport::default();
+    let total = current_round.min(config.max_rounds);
+    if total == 0 {
+        return report;
+    }
+
+    info!("⏪ Backfilling up to {} rounds before round {}", total, current_round);
+    let started = Instant::now();
+
+    for round_id in backfill_range(current_round, config.max_rounds) {
+        match fetch(round_id).await {
+            Ok(BackfillOutcome::Stored) => report.stored += 1,
+            Ok(BackfillOutcome::AlreadyKnown) => report.already_known += 1,
+            Ok(BackfillOutcome::Missing) => report.missing += 1,
+            Err(e) => {
+                warn!("Backfill of round {} failed: {}", round_id, e);
+                report.errors += 1;
+            }
+        }
+        report.scanned += 1;
+
+        let log_every = config.log_every.max(1);
+        if report.scanned % log_every == 0 && report.scanned < total {
+            report.progress_points.push(report.scanned);
+            let eta_secs = eta(started.elapsed(), report.scanned, total).map_or(0, |d| d.as_secs());
+            info!("⏪ Backfill: {} of {} rounds ({} stored), ETA {}s",
+                report.scanned, total, report.stored, eta_secs);
+        }
+    }
+
+    info!("⏪ Backfill done in {:.1}s: {} stored, {} already known, {} missing, {} errors",
+        started.elapsed().as_secs_f64(), report.stored, report.already_known, report.missing, report.errors);
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::BotError;
+    use std::sync::{Arc, Mutex};
+
+    #[tokio::test]
+    async fn test_backfill_respects_cap_and_logs_progress() {
+        let fetched = Arc::new(Mutex::new(Vec::new()));
+        let seen = fetched.clone();
+        let config = BackfillConfig { max_rounds: 60, log_every: 25 };
+
+        let report = backfill_rounds(10_000, &config, move |round_id| {
+            seen.lock().unwrap().push(round_id);
+            async move {
+                match round_id % 10 {
+                    0 => Ok(BackfillOutcome::AlreadyKnown),
+                    1 => Ok(BackfillOutcome::Missing),
+                    2 => Err(BotError::Other("rpc".to_string())),
+                    _ => Ok(BackfillOutcome::Stored),
+                }
+            }
+        }).await;
+
+        // Only the 60 rounds before the current one, newest first
+        let fetched = fetched.lock().unwrap();
+        assert_eq!(fetched.len(), 60);
+        assert_eq!(fetched.first(), Some(&9_999));
+        assert_eq!(fetched.last(), Some(&9_940));
+
+        assert_eq!(report.scanned, 60);
+        assert_eq!(report.already_known + report.missing + report.errors + report.stored, 60);
+        assert_eq!(report.errors, 6);
+        assert_eq!(report.progress_points, vec![25, 50]);
+
+        // Near genesis the cap is the number of rounds that exist
+        let report = backfill_rounds(3, &config, |_| async { Ok::<_, BotError>(BackfillOutcome::Stored) }).await;
+        assert_eq!(report.stored, 3);
+        assert!(report.progress_points.is_empty());
+
+        // Cap of 0 disables it
+        let report = backfill_rounds(10_000, &BackfillConfig { max_rounds: 0, log_every: 25 }, |_| async {
+            Ok::<_, BotError>(BackfillOutcome::Stored)
+        }).await;
+        assert_eq!(report.scanned, 0);
+
+        assert_eq!(eta(Duration::from_secs(10), 25, 100), Some(Duration::from_secs(30)));
+    }
+}
@@ -0,0 +1,186 @@
+//! Walk-forward backtest over recorded rounds
+//!
+//! Each round, the picker sees only the rounds before it plus what is known
+//! when the round opens (no winner, no final board), and returns the squares
+//! to bet. Transaction fees
+//! and the chance that a late deploy misses the round can be simulated so the
+//! reported ROI is closer to what the live bot gets.
+
+use crate::learning_engine::WinnerAllocation;
+use crate::strategies::RoundHistory;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacktestConfig {
+    /// Total SOL (lamports) bet per round, split evenly across squares
+    pub bet_per_round_lamports: u64,
+    /// Fee paid for every deploy attempt, landed or not
+    pub fee_lamports: u64,
+    /// Chance a deploy lands before the round closes (1.0 = always)
+    pub landing_probability: f64,
+    /// Seed for the landing draw so runs are repeatable
+    pub seed: u64,
+}
+
+impl Default for BacktestConfig {
+    fn default() -> Self {
+        Self {
+            bet_per_round_lamports: 40_000_000, // 0.04 SOL
+            fee_lamports: 5_000,                // base signature fee
+            landing_probability: 0.95,
+            seed: 42,
+        }
+    }
+}
+
+impl BacktestConfig {
+    /// No fees, every deploy lands
+    pub fn idealized(bet_per_round_lamports: u64) -> Self {
+        Self {
+            bet_per_round_lamports,
+            fee_lamports: 0,
+            landing_probability: 1.0,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BacktestReport {
+    pub rounds_played: u32,
+    pub rounds_landed: u32,
+    pub rounds_won: u32,
+    pub total_bet: u64,
+    pub fees_paid: u64,
+    pub total_returned: u64,
+    pub net_lamports: i64,
+    /// (returned - bet - fees) / (bet + fees)
+    pub roi: f64,
+}
+
+/// What the picker knows about a round before betting on it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoundStart {
+    pub round_id: u64,
+    pub timestamp: Option<i64>,
+}
+
+impl RoundStart {
+    fn of(round: &RoundHistory) -> Self {
+        Self { round_id: round.round_id, timestamp: round.timestamp }
+    }
+}
+
+/// Replay `rounds` (oldest first) through `pick`, which returns 0-24 squares
+/// An empty pick skips the round
+pub fn run_backtest<F>(rounds: &[RoundHistory], config: &BacktestConfig, mut pick: F) -> BacktestReport
+where
+    F: FnMut(&[RoundHistory], RoundStart) -> Vec<usize>,
+{
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let mut report = BacktestReport::default();
+
+    for (i, round) in rounds.iter().enumerate() {
+        let squares: Vec<usize> = pick(&rounds[..i], RoundStart::of(round)).into_iter().filter(|&s| s < 25).collect();
+        if squares.is_empty() {
+            continue;
+        }
+
+        report.rounds_played += 1;
+        report.fees_paid += config.fee_lamports;
+
+        if rng.gen::<f64>() >= config.landing_probability {
+            continue; // landed after the round closed
+        }
+
+        report.rounds_landed += 1;
+        report.total_bet += config.bet_per_round_lamports;
+
+        let winner = round.winning_square as usize;
+        if squares.contains(&winner) {
+            let stake = config.bet_per_round_lamports / squares.len() as u64;
+            let allocation = WinnerAllocation::compute(
+                config.bet_per_round_lamports,
+                squares.len(),
+                round.deployed[winner] + stake,
+                round.total_pot + config.bet_per_round_lamports,
+            );
+            report.rounds_won += 1;
+            report.total_returned += allocation.stake_on_square + allocation.amount_won;
+        }
+    }
+
+    let cost = report.total_bet + report.fees_paid;
+    report.net_lamports = report.total_returned as i64 - cost as i64;
+    report.roi = if cost > 0 {
+        report.net_lamports as f64 / cost as f64
+    } else {
+        0.0
+    };
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rounds(n: u64) -> Vec<RoundHistory> {
+        (0..n)
+            .map(|i| {
+                let mut deployed = [100_000_000u64; 25];
+                let winning_square = (i * 7 % 25) as u8;
+                deployed[winning_square as usize] = 50_000_000;
+                RoundHistory {
+                    round_id: 1000 + i,
+                    winning_square,
+                    deployed,
+                    total_pot: deployed.iter().sum(),
+                    motherlode: false,
+                    timestamp: None,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_fees_and_missed_landings_lower_roi() {
+        let history = rounds(200);
+        // Covers the whole board so every landed round pays out
+        let pick = |_: &[RoundHistory], _: RoundStart| -> Vec<usize> { (0..25).collect() };
+
+        let ideal = run_backtest(&history, &BacktestConfig::idealized(40_000_000), pick);
+        let realistic = run_backtest(
+            &history,
+            &BacktestConfig {
+                bet_per_round_lamports: 40_000_000,
+                fee_lamports: 500_000,
+                landing_probability: 0.7,
+                seed: 7,
+            },
+            pick,
+        );
+
+        assert_eq!(ideal.rounds_landed, 200);
+        assert_eq!(ideal.fees_paid, 0);
+        assert_eq!(realistic.rounds_played, 200);
+        assert!(realistic.rounds_landed < realistic.rounds_played);
+        assert_eq!(realistic.fees_paid, 200 * 500_000);
+        assert!(realistic.roi < ideal.roi, "realistic {} vs ideal {}", realistic.roi, ideal.roi);
+    }
+
+    #[test]
+    fn test_picker_sees_only_earlier_rounds() {
+        let history = rounds(20);
+        let mut seen = Vec::new();
+        run_backtest(&history, &BacktestConfig::idealized(40_000_000), |past, start| {
+            assert!(past.iter().all(|r| r.round_id < start.round_id));
+            seen.push((past.len(), start.round_id));
+            vec![0]
+        });
+
+        let expected: Vec<(usize, u64)> = history.iter().enumerate().map(|(i, r)| (i, r.round_id)).collect();
+        assert_eq!(seen, expected);
+    }
+}
@@ -281,6 +281,11 @@ async fn main() {
         }
     };
 
+    #[cfg(feature = "database")]
+    if let Some(ref db) = db {
+        db.publish_config(BOT_NAME, &config).await.ok();
+    }
+
     // Keypair is optional for analytics (read-only operations)
     let keypair = match load_keypair(&config.keypair_path) {
         Ok(kp) => {
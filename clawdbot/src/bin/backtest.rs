@@ -0,0 +1,113 @@
+//! Replay recorded rounds through the consensus picker and print the backtest report
+//!
+//! Rounds come from the shared database (DATABASE_URL). Knobs, all optional:
+//!   BACKTEST_ROUNDS          rounds to load, newest first (default 500)
+//!   BACKTEST_SQUARES         squares the consensus picks per round (default 5)
+//!   BACKTEST_BET_SOL         SOL bet per round, split across the squares (default 0.04)
+//!   BACKTEST_FEE_LAMPORTS    fee per deploy attempt (default 5000)
+//!   BACKTEST_LANDING_PROB    chance a deploy lands in time (default 0.95)
+//!   BACKTEST_SEED            seed for the landing draw (default 42)
+//! Pass `--json` to print the report as JSON instead.
+
+use clawdbot::{
+    backtest::BacktestConfig,
+    db::is_database_available,
+    units::{lamports_to_sol, sol_to_lamports},
+};
+use log::error;
+
+#[cfg(feature = "database")]
+use clawdbot::{
+    backtest::{run_backtest, RoundStart},
+    db::SharedDb,
+    strategies::{RoundHistory, StrategyEngine},
+    units::LAMPORTS_PER_SOL,
+};
+
+fn env_or<T: std::str::FromStr>(key: &str, default: T) -> T {
+    std::env::var(key).ok().and_then(|v| v.parse().ok()).unwrap_or(default)
+}
+
+#[tokio::main]
+async fn main() {
+    env_logger::Builder::from_env(
+        env_logger::Env::default().default_filter_or("info")
+    ).init();
+
+    if !is_database_available() {
+        error!("❌ DATABASE_URL not set - the backtest replays rounds from the shared database");
+        std::process::exit(1);
+    }
+
+    let defaults = BacktestConfig::default();
+    let config = BacktestConfig {
+        bet_per_round_lamports: sol_to_lamports(env_or("BACKTEST_BET_SOL", lamports_to_sol(defaults.bet_per_round_lamports))),
+        fee_lamports: env_or("BACKTEST_FEE_LAMPORTS", defaults.fee_lamports),
+        landing_probability: env_or("BACKTEST_LANDING_PROB", defaults.landing_probability),
+        seed: env_or("BACKTEST_SEED", defaults.seed),
+    };
+    let limit: i32 = env_or("BACKTEST_ROUNDS", 500);
+    let num_squares: usize = env_or("BACKTEST_SQUARES", 5);
+    let as_json = std::env::args().any(|a| a == "--json");
+
+    #[cfg(feature = "database")]
+    {
+        let db = match SharedDb::connect().await {
+            Ok(db) => db,
+            Err(e) => {
+                error!("❌ Database connection failed: {}", e);
+                std::process::exit(1);
+            }
+        };
+        let rows = match db.load_round_history(limit).await {
+            Ok(rows) => rows,
+            Err(e) => {
+                error!("❌ Failed to load round history: {}", e);
+                std::process::exit(1);
+            }
+        };
+
+        // The engine converts the stored 1-25 winners and orders the rounds oldest first
+        let mut loader = StrategyEngine::new();
+        loader.load_rounds_from_db(rows);
+        let rounds = loader.history().to_vec();
+        if rounds.is_empty() {
+            error!("❌ No completed rounds recorded yet");
+            std::process::exit(1);
+        }
+
+        // Nothing is deployed yet when the round opens, so the picker sees an empty board
+        let report = run_backtest(&rounds, &config, |past: &[RoundHistory], _: RoundStart| {
+            let mut engine = StrategyEngine::new();
+            engine.load_history(past.to_vec());
+            engine
+                .get_consensus_recommendation_n(&[0; 25], num_squares)
+                .squares
+                .into_iter()
+                .map(|s| s - 1)
+                .collect()
+        });
+
+        if as_json {
+            println!("{}", serde_json::to_string_pretty(&report).unwrap());
+            return;
+        }
+
+        println!("Backtest over {} rounds ({} → {})", rounds.len(), rounds[0].round_id, rounds[rounds.len() - 1].round_id);
+        println!("  Squares per round: {}", num_squares);
+        println!("  Bet per round:     {:.4} SOL", lamports_to_sol(config.bet_per_round_lamports));
+        println!("  Played / landed / won: {} / {} / {}", report.rounds_played, report.rounds_landed, report.rounds_won);
+        println!("  Bet:      {:.4} SOL", lamports_to_sol(report.total_bet));
+        println!("  Fees:     {:.4} SOL", lamports_to_sol(report.fees_paid));
+        println!("  Returned: {:.4} SOL", lamports_to_sol(report.total_returned));
+        println!("  Net:      {:+.4} SOL", report.net_lamports as f64 / LAMPORTS_PER_SOL as f64);
+        println!("  ROI:      {:+.2}%", report.roi * 100.0);
+    }
+
+    #[cfg(not(feature = "database"))]
+    {
+        let _ = (config, limit, num_squares, as_json);
+        error!("❌ Built without the database feature. Rebuild with --features database");
+        std::process::exit(1);
+    }
+}
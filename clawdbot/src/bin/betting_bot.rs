@@ -5,6 +5,7 @@ use clawdbot::{
     db::is_database_available,
     error::Result,
     strategy::BettingStrategy,
+    units::{lamports_to_sol, sol_to_lamports},
 };
 use log::{error, info, warn};
 use solana_sdk::signature::{read_keypair_file, Keypair, Signer};
@@ -220,7 +221,7 @@ impl BettingBot {
             }
 
             let balance = self.client.get_balance()?;
-            let balance_sol = balance as f64 / 1_000_000_000.0;
+            let balance_sol = lamports_to_sol(balance);
 
             // Use fixed sol_per_square 
             let sol_per_square = self.config.sol_per_square.unwrap_or(0.001);
@@ -254,7 +255,7 @@ impl BettingBot {
             }
             
             // Convert SOL to lamports
-            let lamports_per_square = (sol_per_square * 1_000_000_000.0) as u64;
+            let lamports_per_square = sol_to_lamports(sol_per_square);
 
             // Execute deploy transaction immediately!
             if lamports_per_square > 0 {
@@ -384,6 +385,11 @@ async fn main() {
         }
     };
 
+    #[cfg(feature = "database")]
+    if let Some(ref db) = db {
+        db.publish_config(BOT_NAME, &config).await.ok();
+    }
+
     let keypair = match load_keypair(&config.keypair_path) {
         Ok(kp) => kp,
         Err(e) => {
@@ -1,11 +1,21 @@
 use clawdbot::{
     ai_advisor::AIAdvisor,
-    blockchain_parser::{BlockchainParser, OreInstructionType},
+    board_trend::{BoardSnapshots, FinalWindowDeploys},
+    blockchain_parser::{resolve_winning_square, unique_deployers, BlockchainParser, OreInstructionType},
     config::BotConfig,
+    error::DeployError,
     db::{is_database_available, Signal, SignalType},
-    strategies::{StrategyEngine, RoundHistory, StrategyRecommendation},
-    ore_strategy::{OreStrategyEngine, CompetitionLevel, DeployDecision},
-    learning_engine::{LearningEngine, WinRecord},
+    strategies::{square_contributions, EdgeThresholds, StrategyEngine, StrategyRecommendation},
+    ore_strategy::{coordinator_skip_reason, OreStrategyEngine, CompetitionLevel, DeployDecision},
+    learning_engine::{LearningEngine, StrategyPrunePolicy, WinRecord, WinnerAllocation},
+    round_lifecycle::RoundLifecycle,
+    round_phase::{secs_remaining, CatchUp, PhaseThresholds, RoundPhase},
+    state_store::{
+        load_last_round, publish_cycle_heartbeat, resume_last_round, MemoryStateStore, SignalBudget,
+        StateBackend, StateStore,
+    },
+    treasury_alerts::TreasuryAlerts,
+    units::{lamports_to_sol, raw_ore_to_ore, sol_to_lamports},
 };
 use colored::*;
 use log::{error, info, warn};
@@ -16,14 +26,28 @@ use solana_sdk::{
 };
 use solana_client::rpc_client::RpcClient;
 use tokio::time::{sleep, Duration};
-use std::collections::HashMap;
 
 #[cfg(feature = "database")]
-use clawdbot::db::{SharedDb, DbRound, DbTransaction};
+use clawdbot::db::{find_dead_bots, HistoryWindow, SharedDb, DbRound, DbTransaction};
+#[cfg(feature = "database")]
+use clawdbot::round_lifecycle::RoundSummary;
+#[cfg(feature = "database")]
+use clawdbot::writer::BackgroundWriter;
+#[cfg(feature = "database")]
+use clawdbot::backfill::{backfill_rounds, BackfillConfig, BackfillOutcome};
 
-const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
 const MIN_WALLET_SOL: f64 = 0.05;
 const MAX_BET_PER_ROUND_SOL: f64 = 0.04;
+#[cfg(feature = "database")]
+const TX_WRITE_QUEUE: usize = 1000;
+#[cfg(feature = "database")]
+const TX_WRITE_MAX_WAIT_MS: u64 = 50;
+/// Run transaction retention once every this many rounds (~1 hour)
+#[cfg(feature = "database")]
+const RETENTION_EVERY_ROUNDS: u64 = 60;
+/// Transactions older than this go regardless of the slot window
+#[cfg(feature = "database")]
+const TX_MAX_AGE_DAYS: i32 = 30;
 
 /// Load keypair from file path or from environment variable
 fn load_keypair(keypair_path: &str) -> Result<Keypair, String> {
@@ -95,7 +119,7 @@ async fn execute_deploy(
     // Send and confirm
     info!("   📤 Sending transaction...");
     let signature = rpc_client.send_and_confirm_transaction(&tx)
-        .map_err(|e| format!("Transaction failed: {}", e))?;
+        .map_err(|e| format!("Transaction failed: {}", DeployError::from_client_error(&e)))?;
     
     info!("{}", format!("   ✅ Transaction confirmed: {}", signature).green());
     
@@ -114,6 +138,90 @@ fn get_balance(rpc_url: &str, pubkey: &solana_sdk::pubkey::Pubkey) -> Result<u64
 
 const BOT_NAME: &str = "coordinator";
 
+/// Database writes for a completed round: winner, winner source, consensus and
+/// test-20 performance, and the detected wins
+#[cfg(feature = "database")]
+async fn persist_round_summary(db: &SharedDb, summary: &RoundSummary) {
+    let round_id = summary.round_id;
+    let winning_square = summary.winning_square;
+    if let Err(e) = db.complete_round(
+        round_id as i64,
+        winning_square as i16,
+        summary.motherlode
+    ).await {
+        warn!("Failed to update round {} with winning square: {}", round_id, e);
+    } else {
+        info!("✅ Updated rounds table: round {} winning_square = {}", 
+            round_id, winning_square);
+    }
+    if let Some(origin) = summary.origin {
+        db.record_winner_source(round_id as i64, origin, summary.disagreement).await.ok();
+    }
+    
+    // Also record strategy performance for consensus
+    if let Ok(state) = db.get_state("consensus_recommendation").await {
+        if let Some(rec) = state {
+            if let Some(squares) = rec["squares"].as_array() {
+                let our_picks: Vec<i32> = squares.iter()
+                    .filter_map(|s| s.as_i64().map(|n| n as i32))
+                    .collect();
+                let hit = our_picks.contains(&(winning_square as i32));
+                let confidence = rec["confidence"].as_f64().unwrap_or(0.5) as f32;
+                
+                db.record_strategy_performance(
+                    "consensus",
+                    round_id as i64,
+                    &our_picks,
+                    winning_square as i16,
+                    confidence
+                ).await.ok();
+                
+                info!("📊 Recorded consensus performance: picks={:?}, winner={}, hit={}",
+                    our_picks, winning_square, hit);
+            }
+        }
+    }
+    
+    // TEST-20 TRACKING: Complete the round and record result
+    match db.complete_test_20_round(round_id as i64, winning_square as i16).await {
+        Ok(is_hit) => {
+            if is_hit {
+                info!("📊 Test-20: ✅ Round {} HIT (winner {} in our top 20)", 
+                    round_id, winning_square);
+            } else {
+                info!("📊 Test-20: ❌ Round {} MISS (winner {} was in skip list)", 
+                    round_id, winning_square);
+            }
+        }
+        Err(e) => {
+            // Not an error if round wasn't tracked (happens on startup)
+            info!("📊 Test-20: Round {} not tracked ({})", round_id, e);
+        }
+    }
+
+    // Record detected winners in database
+    for win in &summary.wins {
+        let squares_i32: Vec<i32> = win.squares_bet.iter().map(|s| *s as i32).collect();
+        db.record_win(
+            win.round_id as i64,
+            &win.winner_address,
+            win.winning_square as i16,
+            win.amount_bet as i64,
+            win.amount_won as i64,
+            &squares_i32,
+            win.num_squares as i16,
+            win.total_round_sol as i64,
+            win.num_deployers as i32,
+            win.is_motherlode,
+            win.is_full_ore,
+            win.ore_earned as f32,
+            win.competition_on_square as i64,
+            win.winner_share_pct as f32,
+            win.slot as i64,
+        ).await.ok();
+    }
+}
+
 #[tokio::main]
 async fn main() {
     env_logger::Builder::from_env(
@@ -138,15 +246,14 @@ async fn main() {
     info!("═══════════════════════════════════════════════════════════════");
 
     // Check database availability
-    if !is_database_available() {
-        error!("❌ DATABASE_URL not set!");
-        error!("   The coordinator requires PostgreSQL for bot coordination.");
-        error!("   Add a PostgreSQL database in Railway and it will auto-configure.");
-        return;
+    if is_database_available() {
+        info!("✅ Database URL found");
+    } else {
+        warn!("⚠️  DATABASE_URL not set - running with in-memory state");
+        warn!("   Live board and consensus are still published, but no history is kept.");
+        warn!("   Add a PostgreSQL database in Railway and it will auto-configure.");
     }
 
-    info!("✅ Database URL found");
-
     // Load configuration
     let config = if std::env::var("RPC_URL").is_ok() {
         info!("📋 Loading config from environment variables");
@@ -173,21 +280,33 @@ async fn main() {
 
     // Connect to database
     #[cfg(feature = "database")]
-    let db = match SharedDb::connect().await {
-        Ok(db) => {
-            info!("✅ Database connected and schema initialized");
-            Some(db)
-        }
-        Err(e) => {
-            error!("❌ Database connection failed: {}", e);
-            return;
+    let db = if is_database_available() {
+        match SharedDb::connect().await {
+            Ok(db) => {
+                info!("✅ Database connected and schema initialized");
+                db.publish_config(BOT_NAME, &config).await.ok();
+                Some(db)
+            }
+            Err(e) => {
+                error!("❌ Database connection failed: {}", e);
+                return;
+            }
         }
+    } else {
+        None
     };
 
+    // Live state for the dashboard goes to the database when there is one
+    #[cfg(feature = "database")]
+    let store = match db {
+        Some(ref db) => StateBackend::Postgres(db.clone()),
+        None => StateBackend::Memory(MemoryStateStore::from_env()),
+    };
     #[cfg(not(feature = "database"))]
-    {
-        error!("❌ Coordinator requires database feature. Build with: cargo build --features database");
-        return;
+    let store = StateBackend::Memory(MemoryStateStore::from_env());
+
+    if !store.is_persistent() {
+        store.set_state(&format!("{}_config", BOT_NAME), config.sanitized()).await.ok();
     }
 
     // Create parser
@@ -213,9 +332,13 @@ async fn main() {
     info!("═══════════════════════════════════════════════════════════════\n");
 
     // Track state for detecting changes
-    let mut last_round_id: u64 = 0;
-    let mut last_slot: u64 = 0;
-    let mut round_start_detected = false;
+    // Round seen before a restart; picked up on the first cycle if still recent
+    let mut resume_from = load_last_round(&store).await;
+    if let Some(round) = resume_from {
+        info!("⏯️  Last round seen before restart: {}", round);
+    }
+    // Current round's first slot; earlier deploys in a fetched page belong to the previous round
+    let mut round_start_slot: u64 = 0;
     
     // Initialize strategy engine
     let mut strategy_engine = StrategyEngine::new();
@@ -224,6 +347,19 @@ async fn main() {
     info!("   • Streak Reversal, Low Competition, Whale Following");
     info!("   • Pattern Detection, Kelly Criterion, Quadrant Analysis");
     info!("   • Mean Reversion, Consensus (weighted combination)");
+    strategy_engine.set_edge_thresholds(EdgeThresholds {
+        min_square_edge: config.analytics.min_square_edge,
+        min_quadrant_edge: config.analytics.min_quadrant_edge,
+        ..Default::default()
+    });
+    if config.analytics.neighbor_overlay_weight > 0.0 {
+        strategy_engine.set_neighbor_overlay(config.analytics.neighbor_overlay_weight);
+        info!("   • Neighbor overlay: last winner + neighbors (weight {:.2})", config.analytics.neighbor_overlay_weight);
+    }
+    if config.analytics.min_quadrants > 0 {
+        strategy_engine.set_min_quadrants(config.analytics.min_quadrants);
+        info!("   • Consensus spread across at least {} quadrants", config.analytics.min_quadrants.min(3));
+    }
 
     // Initialize ORE-specific strategy engine for ALL player learning
     let mut ore_strategy = OreStrategyEngine::new();
@@ -234,6 +370,52 @@ async fn main() {
     info!("   • Min wallet: {:.4} SOL, Max bet: {:.4} SOL", 
         ore_strategy.min_wallet_sol, ore_strategy.max_bet_per_round_sol);
 
+    // Fill in rounds completed while we weren't running (bounded by BACKFILL_MAX_ROUNDS)
+    #[cfg(feature = "database")]
+    if let (Some(db), Ok(board)) = (db.as_ref(), parser.get_board()) {
+        let backfill = BackfillConfig {
+            max_rounds: config.analytics.backfill_max_rounds,
+            ..Default::default()
+        };
+        let known: std::collections::HashSet<u64> = db.get_recent_completed_rounds(backfill.max_rounds as i64)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|r| r.round_id)
+            .collect();
+        let parser = &parser;
+        let known = &known;
+        
+        backfill_rounds(board.round_id, &backfill, |round_id| async move {
+            if known.contains(&round_id) {
+                return Ok(BackfillOutcome::AlreadyKnown);
+            }
+            let Ok(round) = parser.get_round(round_id) else {
+                return Ok(BackfillOutcome::Missing);
+            };
+            let Some(rng) = round.rng() else {
+                return Ok(BackfillOutcome::Missing);
+            };
+            
+            // Deploy count and completion time aren't on the Round account; the
+            // missing completed_at keeps these rounds out of time-windowed stats
+            let stored = db.insert_backfilled_round(&DbRound {
+                round_id: round_id as i64,
+                start_slot: None,
+                end_slot: None,
+                winning_square: Some(round.winning_square(rng) as i16 + 1), // 0-24 → 1-25
+                total_deployed: round.deployed.iter().sum::<u64>() as i64,
+                deployed_squares: round.deployed.iter().map(|&d| d as i64).collect(),
+                total_winnings: 0,
+                total_vaulted: 0,
+                motherlode: round.did_hit_motherlode(rng),
+                num_deploys: 0,
+                completed_at: None,
+            }).await?;
+            Ok::<_, clawdbot::error::BotError>(if stored { BackfillOutcome::Stored } else { BackfillOutcome::AlreadyKnown })
+        }).await;
+    }
+
     // Load persisted learning data from database
     #[cfg(feature = "database")]
     if let Some(ref db) = db {
@@ -257,7 +439,8 @@ async fn main() {
         }
         
         // Load historical rounds
-        if let Ok(rounds) = db.load_round_history(500).await {
+        let history_window = HistoryWindow::from_config(&config.analytics);
+        if let Ok(rounds) = db.load_round_history_within(500, &history_window).await {
             if !rounds.is_empty() {
                 let count = rounds.len();
                 strategy_engine.load_rounds_from_db(rounds);
@@ -369,14 +552,73 @@ async fn main() {
 
     // Initialize learning engine for deep analysis
     let mut learning_engine = LearningEngine::new();
+    #[cfg(feature = "database")]
+    let prune_policy = StrategyPrunePolicy {
+        max_age_secs: config.analytics.strategy_max_age_hours as i64 * 3600,
+        min_confidence: config.analytics.strategy_min_confidence,
+        max_count: config.analytics.max_detected_strategies,
+    };
     info!("🧠 Learning Engine initialized for deep pattern analysis");
 
-    // Track deploys per round for win detection
-    // We keep both current and previous round deploys so we can detect wins
-    // when the Reset transaction comes (which happens AFTER new round starts)
-    let mut round_deploys: HashMap<String, (u64, Vec<u8>)> = HashMap::new();
-    let mut previous_round_deploys: HashMap<String, (u64, Vec<u8>)> = HashMap::new();
+    // Bots already reported dead (alert once until they heartbeat again)
+    #[cfg(feature = "database")]
+    let dead_bot_timeout = config.monitor.alerts.dead_bot_timeout_secs;
+    #[cfg(feature = "database")]
+    let mut dead_bots: std::collections::HashSet<String> = std::collections::HashSet::new();
+
+    // Round flips, tracked deploys for win detection and winning squares
+    // (0-24) seen from each source, reconciled per WINNER_SOURCE. Deploys of
+    // the previous round are kept so wins can be detected when the Reset
+    // transaction comes (which happens AFTER the new round starts)
+    let winner_source = config.analytics.winner_source;
+    let mut lifecycle = RoundLifecycle::new(BOT_NAME, winner_source);
     let mut pending_round_clear = false;
+    // Distinct wallets that deployed in the current round
+    let mut round_deployers: std::collections::HashSet<String> = std::collections::HashSet::new();
+    // Phase thresholds follow the deploy mode the miners run in
+    let phase_thresholds = PhaseThresholds::for_mode(&config.mode);
+    let mut ending_soon_round: u64 = 0;
+    let mut board_snapshots = BoardSnapshots::new();
+    // Share of each round's deploys landing in its final second (bot-heaviness)
+    let mut final_window = FinalWindowDeploys::new(
+        config.analytics.final_window_secs,
+        config.analytics.bot_heavy_fraction,
+    );
+    // Hold deploy signals after startup/downtime until a round starts while we watch
+    let mut catch_up = CatchUp::new(config.analytics.catch_up_gap_secs);
+    // Edge-triggered treasury alerts (MOTHERLODE_ALERT_ORE / TREASURY_ALERT_SOL)
+    let mut treasury_alerts = TreasuryAlerts::new(
+        config.analytics.motherlode_alert_ore,
+        config.analytics.treasury_alert_sol,
+    );
+    // Drop repeated signals within a round and space out heartbeats
+    let mut signal_budget = SignalBudget::new(
+        config.analytics.max_signals_per_type,
+        config.analytics.heartbeat_interval_secs,
+    );
+
+    // Persist transactions from a background task so a slow DB doesn't stall parsing
+    #[cfg(feature = "database")]
+    let (tx_writer, tx_writer_handle) = match db {
+        Some(ref db) => {
+            let db = db.clone();
+            let (writer, handle) = BackgroundWriter::spawn(
+                "tx-writer",
+                TX_WRITE_QUEUE,
+                Duration::from_millis(TX_WRITE_MAX_WAIT_MS),
+                move |tx: DbTransaction| {
+                    let db = db.clone();
+                    async move {
+                        if let Err(e) = db.insert_transaction(&tx).await {
+                            warn!("Failed to store tx {}: {}", tx.signature, e);
+                        }
+                    }
+                },
+            );
+            (Some(writer), Some(handle))
+        }
+        None => (None, None),
+    };
 
     // Set up Ctrl+C handler
     let running = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(true));
@@ -395,201 +637,49 @@ async fn main() {
         match parser.get_board() {
             Ok(board) => {
                 let current_round = board.round_id;
-                let current_slot = board.end_slot;
+                let catching_up = catch_up.observe(chrono::Utc::now().timestamp(), current_round);
+                signal_budget.begin_cycle(current_round);
+                final_window.observe_round(current_round, board.start_slot, board.end_slot);
+                round_start_slot = board.start_slot;
+                if let Some(stored) = resume_from.take() {
+                    lifecycle.last_round_id = resume_last_round(Some(stored), current_round);
+                    if lifecycle.last_round_id != 0 && lifecycle.last_round_id != current_round {
+                        info!("⏯️  Resuming from round {} - processing it as completed", lifecycle.last_round_id);
+                    }
+                }
 
                 // Detect new round
-                if current_round != last_round_id && last_round_id != 0 {
-                    info!("{}", format!("🆕 NEW ROUND DETECTED: {} → {}", last_round_id, current_round).green().bold());
-                    
-                    // IMPORTANT: Save current deploys for win detection
-                    // The Reset transaction will come in this cycle's transactions
-                    // so we need to preserve the deploys until after we process it
-                    previous_round_deploys = round_deploys.clone();
+                let flip = lifecycle.on_board(
+                    &parser,
+                    &store,
+                    current_round,
+                    &mut strategy_engine,
+                    &mut ore_strategy,
+                    &mut learning_engine,
+                ).await;
+                if let Some(flip) = flip {
                     pending_round_clear = true;
-                    info!("📋 Saved {} deploys from round {} for win detection", 
-                        previous_round_deploys.len(), last_round_id);
-                    
-                    // Try to get the completed round data and add to strategy engine
-                    // Get the winning square from the completed round's slot_hash
-                    let winning_result = parser.get_round_result(last_round_id);
-                    if let Ok(completed) = parser.get_round(last_round_id) {
-                        // Note: ore_api returns 0-24, we convert to 1-25 for display
-                        let (winning_square, motherlode) = match winning_result {
-                            Ok(Some((sq, ml))) => {
-                                let sq_display = sq + 1; // Convert 0-24 to 1-25
-                                info!("🎯 Round {} RESULT: Winning square {} {}", 
-                                    last_round_id, sq_display, if ml { "🎰 MOTHERLODE!" } else { "" });
-                                (sq_display, ml)
-                            },
-                            _ => {
-                                warn!("⚠️ Could not determine winning square for round {}", last_round_id);
-                                (1, false) // Default to 1 instead of 0
-                            }
-                        };
-                        
-                        // *** CRITICAL: Update rounds table with winning square ***
-                        #[cfg(feature = "database")]
-                        if let Some(ref db) = db {
-                            if winning_square > 0 || winning_result.is_ok() {
-                                if let Err(e) = db.complete_round(
-                                    last_round_id as i64,
-                                    winning_square as i16,
-                                    motherlode
-                                ).await {
-                                    warn!("Failed to update round {} with winning square: {}", last_round_id, e);
-                                } else {
-                                    info!("✅ Updated rounds table: round {} winning_square = {}", 
-                                        last_round_id, winning_square);
-                                }
-                                
-                                // Also record strategy performance for consensus
-                                if let Ok(state) = db.get_state("consensus_recommendation").await {
-                                    if let Some(rec) = state {
-                                        if let Some(squares) = rec["squares"].as_array() {
-                                            let our_picks: Vec<i32> = squares.iter()
-                                                .filter_map(|s| s.as_i64().map(|n| n as i32))
-                                                .collect();
-                                            let hit = our_picks.contains(&(winning_square as i32));
-                                            let confidence = rec["confidence"].as_f64().unwrap_or(0.5) as f32;
-                                            
-                                            db.record_strategy_performance(
-                                                "consensus",
-                                                last_round_id as i64,
-                                                &our_picks,
-                                                winning_square as i16,
-                                                confidence
-                                            ).await.ok();
-                                            
-                                            info!("📊 Recorded consensus performance: picks={:?}, winner={}, hit={}",
-                                                our_picks, winning_square, hit);
-                                        }
-                                    }
-                                }
-                                
-                                // TEST-20 TRACKING: Complete the round and record result
-                                match db.complete_test_20_round(last_round_id as i64, winning_square as i16).await {
-                                    Ok(is_hit) => {
-                                        if is_hit {
-                                            info!("📊 Test-20: ✅ Round {} HIT (winner {} in our top 20)", 
-                                                last_round_id, winning_square);
-                                        } else {
-                                            info!("📊 Test-20: ❌ Round {} MISS (winner {} was in skip list)", 
-                                                last_round_id, winning_square);
-                                        }
-                                    }
-                                    Err(e) => {
-                                        // Not an error if round wasn't tracked (happens on startup)
-                                        info!("📊 Test-20: Round {} not tracked ({})", last_round_id, e);
-                                    }
-                                }
-                            }
-                        }
-                        
-                        let round_history = RoundHistory {
-                            round_id: last_round_id,
-                            winning_square,
-                            deployed: completed.deployed,
-                            total_pot: completed.deployed.iter().sum(),
-                            motherlode,
-                            timestamp: None,
-                        };
-                        strategy_engine.add_round(round_history.clone());
-                        ore_strategy.record_round(&round_history.deployed, round_history.winning_square);
-                        info!("📚 Added round {} to strategy history (winning square: {})", 
-                            last_round_id, winning_square);
-                        
-                        // LEARNING: Check which tracked deploys hit the winning square
-                        let winning_sq_usize = winning_square as usize;
-                        let total_deployed: u64 = completed.deployed.iter().sum();
-                        let is_full_ore = (total_deployed as f64 / 1_000_000_000.0) < 2.0;
-                        
-                        let mut winners_found = 0;
-                        for (address, (deploy_amount, squares)) in &previous_round_deploys {
-                            if squares.contains(&winning_square) {
-                                // This player won!
-                                winners_found += 1;
-                                let num_squares = squares.len() as u8;
-                                
-                                // Record win in learning engine
-                                let competition_on_sq = if winning_sq_usize < 25 { completed.deployed[winning_sq_usize] } else { 0 };
-                                let winner_share: f64 = if competition_on_sq > 0 { *deploy_amount as f64 / competition_on_sq as f64 } else { 1.0 };
-                                learning_engine.record_win(WinRecord {
-                                    round_id: last_round_id,
-                                    winner_address: address.clone(),
-                                    winning_square,
-                                    squares_bet: squares.clone(),
-                                    amount_bet: *deploy_amount,
-                                    amount_won: if winning_sq_usize < 25 { total_deployed.saturating_sub(completed.deployed[winning_sq_usize]) * (*deploy_amount) / competition_on_sq.max(1) } else { 0 },
-                                    num_squares: num_squares,
-                                    total_round_sol: total_deployed,
-                                    num_deployers: previous_round_deploys.len() as u32,
-                                    is_motherlode: motherlode,
-                                    is_full_ore,
-                                    ore_earned: if is_full_ore { 1.0 } else { 0.5 },
-                                    competition_on_square: competition_on_sq,
-                                    winner_share_pct: winner_share,
-                                    slot: 0,
-                                    timestamp: Some(std::time::SystemTime::now()
-                                        .duration_since(std::time::UNIX_EPOCH)
-                                        .unwrap_or_default()
-                                        .as_secs() as i64),
-                                });
-                                
-                                // Record in ore_strategy
-                                let winnings = if winning_sq_usize < 25 {
-                                    total_deployed.saturating_sub(completed.deployed[winning_sq_usize])
-                                } else { 0 };
-                                ore_strategy.record_win(
-                                    address, 
-                                    winnings * (*deploy_amount) / completed.deployed[winning_sq_usize].max(1),
-                                    if is_full_ore { 1.0 } else { 0.5 },
-                                    num_squares
-                                );
-                                
-                                // Record in database
-                                #[cfg(feature = "database")]
-                                if let Some(ref db) = db {
-                                    let squares_i32: Vec<i32> = squares.iter().map(|s| *s as i32).collect();
-                                    let amount_won_calc = (winnings * (*deploy_amount) / competition_on_sq.max(1)) as i64;
-                                    db.record_win(
-                                        last_round_id as i64,
-                                        address,
-                                        winning_square as i16,
-                                        *deploy_amount as i64,
-                                        amount_won_calc,
-                                        &squares_i32,
-                                        num_squares as i16,
-                                        total_deployed as i64,
-                                        previous_round_deploys.len() as i32,
-                                        motherlode,
-                                        is_full_ore,
-                                        if is_full_ore { 1.0 } else { 0.5 },
-                                        competition_on_sq as i64,
-                                        winner_share as f32,
-                                        0_i64,
-                                    ).await.ok();
-                                }
+                    round_deployers.clear();
+
+                    // Bounded transaction history
+                    #[cfg(feature = "database")]
+                    if let Some(ref db) = db {
+                        if current_round % RETENTION_EVERY_ROUNDS == 0 {
+                            match db.cleanup_old_data(TX_MAX_AGE_DAYS, config.analytics.tx_retention_slots as i64).await {
+                                Ok(removed) if removed > 0 => info!("🧹 Pruned {} old transactions", removed),
+                                Ok(_) => {}
+                                Err(e) => warn!("Transaction retention failed: {}", e),
                             }
                         }
-                        
-                        if winners_found > 0 {
-                            info!("🏆 Detected {} winners on square {} (full ORE: {})", 
-                                winners_found, winning_square, is_full_ore);
-                        }
                     }
-                    
+
+                    // *** CRITICAL: Update rounds table with winning square ***
                     #[cfg(feature = "database")]
-                    if let Some(ref db) = db {
-                        // Signal round started
-                        let signal = Signal::round_started(BOT_NAME, current_round);
-                        if let Err(e) = db.send_signal(&signal).await {
-                            warn!("Failed to send round_started signal: {}", e);
-                        } else {
-                            info!("📤 Sent ROUND_STARTED signal");
-                        }
+                    if let (Some(db), Some(summary)) = (&db, &flip.summary) {
+                        persist_round_summary(db, summary).await;
                     }
-                    
-                    round_start_detected = true;
+                    #[cfg(not(feature = "database"))]
+                    let _ = flip;
                 }
 
                 // Detect round ending soon (within 10 slots ~4 seconds)
@@ -598,7 +688,7 @@ async fn main() {
                     
                     info!("📊 Round {} | Deployed: {:.4} SOL | Slot: {}/{}", 
                         current_round,
-                        total_deployed as f64 / 1_000_000_000.0,
+                        lamports_to_sol(total_deployed),
                         board.start_slot,
                         board.end_slot);
 
@@ -622,27 +712,60 @@ async fn main() {
                         if let Err(e) = db.upsert_round(&db_round).await {
                             warn!("Failed to store round: {}", e);
                         }
-                        
-                        // Update monitor_status with timing info for frontend
-                        // Use actual block times for accurate countdown
-                        let (time_remaining_secs, round_duration_secs) = parser.get_round_timing(&board);
-                        let current_slot = parser.get_slot().unwrap_or(board.start_slot);
-                        let slots_remaining = board.end_slot.saturating_sub(current_slot);
-                        
-                        db.set_state("monitor_status", serde_json::json!({
-                            "round_id": current_round,
-                            "total_deployed": total_deployed,
-                            "active_squares": current.deployed.iter().filter(|&&d| d > 0).count(),
-                            "start_slot": board.start_slot,
-                            "end_slot": board.end_slot,
-                            "current_slot": current_slot,
-                            "round_duration_secs": round_duration_secs,
-                            "time_remaining_secs": time_remaining_secs,
-                            "slots_remaining": slots_remaining,
-                            "deployed_squares": current.deployed.iter().map(|&d| d).collect::<Vec<_>>(),
-                            "updated_at": chrono::Utc::now().to_rfc3339(),
-                        })).await.ok();
                     }
+                    
+                    // Update monitor_status with timing info for frontend
+                    // Use actual block times for accurate countdown
+                    let (time_remaining_secs, round_duration_secs) = parser.get_round_timing(&board);
+                    let current_slot = parser.get_slot().unwrap_or(board.start_slot);
+                    let slots_remaining = board.end_slot.saturating_sub(current_slot);
+                    let phase = RoundPhase::from_slots(slots_remaining, &phase_thresholds);
+                    
+                    // Per-square fill rate over this round's snapshots
+                    board_snapshots.record(current_round, current_slot, current.deployed);
+                    let fill_rates = board_snapshots.fill_rates();
+                    let competition_trend = board_snapshots.trends();
+                    
+                    // Tell the other bots once per round, on the last poll before the deploy window
+                    if RoundPhase::ending_before_next_poll(slots_remaining, update_interval as f64, &phase_thresholds)
+                        && ending_soon_round != current_round
+                        && catch_up.allows(&SignalType::RoundEndingSoon)
+                    {
+                        ending_soon_round = current_round;
+                        let signal = Signal::new(
+                            SignalType::RoundEndingSoon,
+                            BOT_NAME,
+                            serde_json::json!({
+                                "round_id": current_round,
+                                "phase": phase,
+                                "slots_remaining": slots_remaining,
+                                "time_remaining_secs": secs_remaining(slots_remaining),
+                            }),
+                        );
+                        if store.send_signal(&signal).await.is_ok() {
+                            info!("📤 Sent ROUND_ENDING_SOON signal ({} slots left)", slots_remaining);
+                        }
+                    }
+                    
+                    store.set_state("monitor_status", serde_json::json!({
+                        "round_id": current_round,
+                        "total_deployed": total_deployed,
+                        "active_squares": current.deployed.iter().filter(|&&d| d > 0).count(),
+                        "num_deployers": round_deployers.len(),
+                        "start_slot": board.start_slot,
+                        "end_slot": board.end_slot,
+                        "current_slot": current_slot,
+                        "round_duration_secs": round_duration_secs,
+                        "time_remaining_secs": time_remaining_secs,
+                        "slots_remaining": slots_remaining,
+                        "phase": phase,
+                        "progress_pct": clawdbot::progress::round_progress_pct(round_duration_secs, time_remaining_secs),
+                        "deployed_squares": current.deployed.iter().map(|&d| d).collect::<Vec<_>>(),
+                        "fill_rates": fill_rates,
+                        "competition_trend": competition_trend,
+                        "bot_heaviness": final_window.last_round(),
+                        "updated_at": chrono::Utc::now().to_rfc3339(),
+                    })).await.ok();
 
                     // Run strategy analysis
                     // Get optimal square count from learning engine (can be 1-25)
@@ -689,18 +812,24 @@ async fn main() {
                     
                     if let Some(ref keypair) = wallet_info {
                         let balance = get_balance(&config.rpc_url, &keypair.pubkey()).unwrap_or(0);
-                        let balance_sol = balance as f64 / LAMPORTS_PER_SOL as f64;
+                        let balance_sol = lamports_to_sol(balance);
                         let competition = CompetitionLevel::from_deployed(total_deployed);
                         
                         // Make deploy decision
-                        let should_deploy = consensus.confidence > 0.4 
-                            && !consensus.squares.is_empty()
-                            && balance_sol >= MIN_WALLET_SOL
-                            && !matches!(competition, CompetitionLevel::VeryHigh);
+                        let skip_reason = coordinator_skip_reason(
+                            catching_up,
+                            consensus.confidence,
+                            &consensus.squares,
+                            balance_sol,
+                            MIN_WALLET_SOL,
+                            competition,
+                        );
                         
-                        if should_deploy {
+                        if let Some(reason) = skip_reason {
+                            info!("   ⏸️  Skipping deploy: {}", reason);
+                        } else {
                             let available_sol = (balance_sol - MIN_WALLET_SOL).min(MAX_BET_PER_ROUND_SOL);
-                            let total_lamports = (available_sol * LAMPORTS_PER_SOL as f64) as u64;
+                            let total_lamports = sol_to_lamports(available_sol);
                             
                             info!("\n{}", "🚀 CLAWDOREDINATOR DEPLOYING!".green().bold());
                             info!("   Squares: {:?}", consensus.squares);
@@ -739,23 +868,39 @@ async fn main() {
                             } else {
                                 info!("   📋 SIMULATION - no transaction sent");
                             }
-                        } else {
-                            let skip_reason = if balance_sol < MIN_WALLET_SOL {
-                                format!("Low balance ({:.4} SOL)", balance_sol)
-                            } else if matches!(competition, CompetitionLevel::VeryHigh) {
-                                "Very high competition".to_string()
-                            } else if consensus.confidence <= 0.4 {
-                                format!("Low confidence ({:.0}%)", consensus.confidence * 100.0)
-                            } else {
-                                "No squares recommended".to_string()
-                            };
-                            info!("   ⏸️  Skipping deploy: {}", skip_reason);
                         }
                     }
 
-                    // Send strategy signals to database
+                    // Store all strategy recommendations as state
+                    let strategies_json: Vec<serde_json::Value> = recommendations.iter()
+                        .filter(|r| r.confidence > 0.2)
+                        .map(|r| serde_json::json!({
+                            "name": r.strategy_name,
+                            "squares": r.squares,
+                            "weights": r.weights,
+                            "confidence": r.confidence,
+                            "expected_roi": r.expected_roi,
+                            "reasoning": r.reasoning
+                        }))
+                        .collect();
+                    
+                    if catching_up {
+                        info!("⏩ Catching up - recommendations and deploy signals held back this cycle");
+                    } else {
+                        store.set_state("current_strategies", serde_json::json!(strategies_json)).await.ok();
+                        store.set_state("consensus_recommendation", serde_json::json!({
+                            "squares": consensus.squares,
+                            "weights": consensus.weights,
+                            "confidence": consensus.confidence,
+                            "optimal_count": optimal_count,
+                            "count_reasoning": count_reasoning,
+                            "contributions": square_contributions(&recommendations),
+                        })).await.ok();
+                    }
+
+                    // Send strategy signals to database (not while catching up)
                     #[cfg(feature = "database")]
-                    if let Some(ref db) = db {
+                    if let Some(db) = db.as_ref().filter(|_| catch_up.allows(&SignalType::DeployOpportunity)) {
                         // Send consensus recommendation as deploy opportunity
                         if consensus.confidence > 0.4 && !consensus.squares.is_empty() {
                             let signal = Signal::deploy_opportunity(
@@ -765,7 +910,9 @@ async fn main() {
                                     consensus.confidence * 100.0,
                                     consensus.reasoning)
                             );
-                            db.send_signal(&signal).await.ok();
+                            if signal_budget.allow(&signal) {
+                                db.send_signal(&signal).await.ok();
+                            }
                         }
                         
                         // Send top strategy as separate signal
@@ -783,32 +930,12 @@ async fn main() {
                                         "reasoning": top.reasoning
                                     }),
                                 );
-                                db.send_signal(&signal).await.ok();
+                                if signal_budget.allow(&signal) {
+                                    db.send_signal(&signal).await.ok();
+                                }
                             }
                         }
                         
-                        // Store all strategy recommendations as state
-                        let strategies_json: Vec<serde_json::Value> = recommendations.iter()
-                            .filter(|r| r.confidence > 0.2)
-                            .map(|r| serde_json::json!({
-                                "name": r.strategy_name,
-                                "squares": r.squares,
-                                "weights": r.weights,
-                                "confidence": r.confidence,
-                                "expected_roi": r.expected_roi,
-                                "reasoning": r.reasoning
-                            }))
-                            .collect();
-                        
-                        db.set_state("current_strategies", serde_json::json!(strategies_json)).await.ok();
-                        db.set_state("consensus_recommendation", serde_json::json!({
-                            "squares": consensus.squares,
-                            "weights": consensus.weights,
-                            "confidence": consensus.confidence,
-                            "optimal_count": optimal_count,
-                            "count_reasoning": count_reasoning
-                        })).await.ok();
-                        
                         // TEST-20 TRACKING: Calculate best 20 squares using REAL historical data
                         // Get historical win rates from database
                         let historical_wins: std::collections::HashMap<i16, i64> = 
@@ -889,8 +1016,7 @@ async fn main() {
                     info!("");
                 }
 
-                last_round_id = current_round;
-                last_slot = current_slot;
+                lifecycle.last_round_id = current_round;
             }
             Err(e) => {
                 warn!("Could not fetch board: {}", e);
@@ -901,9 +1027,10 @@ async fn main() {
         match parser.fetch_recent_transactions(tx_limit) {
             Ok(transactions) => {
                 info!("📥 Processed {} transactions", transactions.len());
+                round_deployers.extend(unique_deployers(&transactions, round_start_slot));
                 
                 #[cfg(feature = "database")]
-                if let Some(ref db) = db {
+                if let Some(ref writer) = tx_writer {
                     for tx in &transactions {
                         let db_tx = DbTransaction {
                             signature: tx.signature.clone(),
@@ -920,11 +1047,15 @@ async fn main() {
                             success: tx.success,
                         };
                         
-                        db.insert_transaction(&db_tx).await.ok();
+                        writer.submit(db_tx).await;
+                    }
+                    if writer.pending() > TX_WRITE_QUEUE / 2 {
+                        warn!("⚠️ DB writes falling behind: {} transactions queued", writer.pending());
                     }
                 }
                 
                 // TRACK ALL PLAYERS (not just whales!) - this is key for learning
+                // Credit the authority, not the executor that signed automation deploys
                 let mut deploy_count = 0;
                 for tx in &transactions {
                     if let Some(ref deploy) = tx.deploy_data {
@@ -934,14 +1065,14 @@ async fn main() {
                         
                         // Track in ore_strategy engine (in-memory)
                         ore_strategy.record_deploy(
-                            &tx.signer,
+                            tx.authority(),
                             deploy.amount_lamports,
                             square_count,
                         );
                         
                         // Track in learning engine with more context
                         learning_engine.record_deploy(
-                            &tx.signer,
+                            tx.authority(),
                             deploy.amount_lamports,
                             &squares_u8,
                             0, // Will get total from round data
@@ -949,9 +1080,11 @@ async fn main() {
                             tx.slot,
                         );
                         
+                        final_window.record(&tx.signature, tx.slot);
+                        
                         // Track for win detection (1-25)
-                        round_deploys.insert(
-                            tx.signer.clone(),
+                        lifecycle.round_deploys.insert(
+                            tx.authority().to_string(),
                             (deploy.amount_lamports, squares_u8.clone())
                         );
                         
@@ -961,7 +1094,7 @@ async fn main() {
                         #[cfg(feature = "database")]
                         if let Some(ref db) = db {
                             db.record_player_deploy(
-                                &tx.signer,
+                                tx.authority(),
                                 deploy.amount_lamports as i64,
                                 square_count as i16,
                                 tx.slot as i64,
@@ -977,7 +1110,7 @@ async fn main() {
                         // Still track whales separately for whale-following strategy
                         if deploy.amount_lamports > 1_000_000_000 { // > 1 SOL = whale
                             strategy_engine.track_whale(
-                                tx.signer.clone(),
+                                tx.authority().to_string(),
                                 deploy.squares.iter().map(|&s| s as usize).collect()
                             );
                             
@@ -985,15 +1118,15 @@ async fn main() {
                             if let Some(ref db) = db {
                                 let squares: Vec<i32> = deploy.squares.iter().map(|&s| s as i32).collect();
                                 db.track_whale(
-                                    &tx.signer, 
+                                    tx.authority(), 
                                     deploy.amount_lamports as i64,
                                     &squares
                                 ).await.ok();
                             }
                             
                             info!("🐋 Whale: {} → {:.2} SOL on {:?}",
-                                &tx.signer[..8],
-                                deploy.amount_lamports as f64 / 1_000_000_000.0,
+                                &tx.authority()[..8],
+                                lamports_to_sol(deploy.amount_lamports),
                                 deploy.squares);
                         }
                     }
@@ -1001,13 +1134,38 @@ async fn main() {
                     // Detect Reset transactions (round completions with winning squares)
                     // Note: reset.winning_square is 0-24 from blockchain, convert to 1-25 for display
                     if let Some(ref reset) = tx.reset_data {
-                        let winning_sq_display = reset.winning_square + 1; // Convert to 1-25
-                        let winning_sq_idx = reset.winning_square as usize; // Keep 0-24 for array access
+                        // A flip that was waiting on this Reset gets recorded now
+                        let deferred = lifecycle.on_reset(
+                            reset,
+                            &mut strategy_engine,
+                            &mut ore_strategy,
+                            &mut learning_engine,
+                        );
+                        #[cfg(feature = "database")]
+                        if let (Some(db), Some(summary)) = (&db, &deferred) {
+                            persist_round_summary(db, summary).await;
+                        }
+                        #[cfg(not(feature = "database"))]
+                        let _ = deferred;
+                    }
+                    let resolved_reset = tx.reset_data.as_ref().and_then(|reset| {
+                        let resolution = resolve_winning_square(
+                            winner_source,
+                            reset.round_id,
+                            lifecycle.round_account_winners.get(&reset.round_id).copied(),
+                            Some((reset.winning_square, reset.origin)),
+                        );
+                        resolution.winning_square.map(|sq| (reset, sq, resolution))
+                    });
+                    if let Some((reset, winning_square, resolution)) = resolved_reset {
+                        let winning_sq_display = winning_square + 1; // Convert to 1-25
+                        let winning_sq_idx = winning_square as usize; // Keep 0-24 for array access
                         
                         info!("{}", format!(
-                            "🎯 ROUND {} COMPLETED! Winning square: {} {}",
+                            "🎯 ROUND {} COMPLETED! Winning square: {} (from {}) {}",
                             reset.round_id,
                             winning_sq_display,
+                            resolution.origin.unwrap_or_default(),
                             if reset.motherlode { "🎰 MOTHERLODE!" } else { "" }
                         ).yellow().bold());
                         
@@ -1020,11 +1178,14 @@ async fn main() {
                                 winning_sq_display as i16,
                                 reset.motherlode
                             ).await.ok();
+                            if let Some(origin) = resolution.origin {
+                                db.record_winner_source(reset.round_id as i64, origin, resolution.disagreement.is_some()).await.ok();
+                            }
                             
                             // Try to get the round's deployment data for learning
                             if let Ok(round) = parser.get_round(reset.round_id) {
                                 let deployed: [i64; 25] = round.deployed.map(|d| d as i64);
-                                db.update_square_stats(winning_sq_display as i16, &deployed).await.ok();
+                                db.update_square_stats(winning_sq_idx as i16, &deployed).await.ok();
                                 
                                 // Record round conditions for competition analysis
                                 let total_deployed: i64 = deployed.iter().sum();
@@ -1034,7 +1195,7 @@ async fn main() {
                                 let competition_on_square = if winning_sq_idx < 25 { deployed[winning_sq_idx] } else { 0 };
                                 
                                 // Determine if this could be a full ORE win
-                                let is_full_ore = (total_deployed as f64 / LAMPORTS_PER_SOL as f64) < 2.0;
+                                let is_full_ore = lamports_to_sol(total_deployed) < 2.0;
                                 let ore_earned = if is_full_ore { 1.0 } else {
                                     1.0 / (num_deployers.max(1) as f64 / 2.0)
                                 };
@@ -1050,19 +1211,19 @@ async fn main() {
                                 
                                 info!("📊 Round {} Analysis:", reset.round_id);
                                 info!("   • Total deployed: {:.4} SOL ({:?})", 
-                                    total_deployed as f64 / LAMPORTS_PER_SOL as f64, competition);
+                                    lamports_to_sol(total_deployed), competition);
                                 info!("   • Competition on square {}: {:.4} SOL", 
-                                    winning_sq_display, competition_on_square as f64 / LAMPORTS_PER_SOL as f64);
+                                    winning_sq_display, lamports_to_sol(competition_on_square));
                                 info!("   • Full ORE: {} | Est. ORE: {:.2}", 
                                     if is_full_ore { "YES ✅" } else { "No" }, ore_earned);
                                 
                                 // FIND AND RECORD ALL WINNERS
                                 // Use previous_round_deploys since round_deploys may have been 
                                 // cleared or started accumulating for the new round
-                                let deploys_to_check = if previous_round_deploys.is_empty() {
-                                    &round_deploys
+                                let deploys_to_check = if lifecycle.previous_round_deploys.is_empty() {
+                                    &lifecycle.round_deploys
                                 } else {
-                                    &previous_round_deploys
+                                    &lifecycle.previous_round_deploys
                                 };
                                 
                                 info!("   📋 Checking {} tracked deploys for winners", deploys_to_check.len());
@@ -1072,18 +1233,20 @@ async fn main() {
                                     // Use 1-25 for comparison (squares tracked as 1-25)
                                     if squares.contains(&(winning_sq_display as u8)) {
                                         let num_squares = squares.len() as u8;
-                                        let winner_share = if competition_on_square > 0 {
-                                            *amount as f64 / competition_on_square as f64
-                                        } else {
-                                            1.0
-                                        };
-                                        let amount_won = (competition_on_square as f64 * winner_share) as i64;
+                                        let allocation = WinnerAllocation::compute(
+                                            *amount,
+                                            squares.len(),
+                                            competition_on_square as u64,
+                                            total_deployed as u64,
+                                        );
+                                        let winner_share = allocation.share;
+                                        let amount_won = allocation.amount_won as i64;
                                         
                                         info!("   🏆 Winner: {} bet {:.4} SOL on {} squares → won {:.4} SOL ({:.1}% share)",
                                             &address[..8],
-                                            *amount as f64 / LAMPORTS_PER_SOL as f64,
+                                            lamports_to_sol(*amount),
                                             num_squares,
-                                            amount_won as f64 / LAMPORTS_PER_SOL as f64,
+                                            lamports_to_sol(amount_won),
                                             winner_share * 100.0);
                                         
                                         // Record comprehensive win to database (1-25)
@@ -1203,6 +1366,15 @@ async fn main() {
                                 best.consistent,
                                 &best.examples,
                             ).await.ok();
+                            
+                            // Drop strategies that stopped being re-detected or lost confidence
+                            match db.prune_detected_strategies(&prune_policy).await {
+                                Ok(pruned) if !pruned.is_empty() => {
+                                    info!("🧹 Pruned {} detected strategies: {:?}", pruned.len(), pruned);
+                                }
+                                Ok(_) => {}
+                                Err(e) => warn!("Failed to prune detected strategies: {}", e),
+                            }
                         }
                     }
                 }
@@ -1231,8 +1403,8 @@ async fn main() {
 
         // Now that we've processed transactions (including Reset), clear if needed
         if pending_round_clear {
-            round_deploys.clear();
-            previous_round_deploys.clear();
+            lifecycle.round_deploys.clear();
+            lifecycle.previous_round_deploys.clear();
             pending_round_clear = false;
             info!("🗑️ Cleared deploy tracking for new round");
         }
@@ -1241,8 +1413,15 @@ async fn main() {
         match parser.get_treasury() {
             Ok(treasury) => {
                 info!("🏦 Treasury: {:.4} SOL | Staked: {:.4} ORE",
-                    treasury.balance as f64 / 1_000_000_000.0,
-                    treasury.total_staked as f64 / 1e11);
+                    lamports_to_sol(treasury.balance),
+                    raw_ore_to_ore(treasury.total_staked));
+
+                for signal in treasury_alerts.check(BOT_NAME, treasury.motherlode, treasury.balance) {
+                    info!("🚨 Treasury alert {}: {}", signal.signal_type, signal.payload);
+                    if let Err(e) = store.send_signal(&signal).await {
+                        warn!("Failed to send {} signal: {}", signal.signal_type, e);
+                    }
+                }
             }
             Err(e) => {
                 warn!("Could not fetch treasury: {}", e);
@@ -1252,8 +1431,8 @@ async fn main() {
         // 4. Check wallet and send claim signals if rewards available
         if let Some(ref wallet) = wallet_info {
             if let Ok(Some(miner)) = parser.get_miner(wallet.pubkey()) {
-                let sol_rewards = miner.rewards_sol as f64 / 1_000_000_000.0;
-                let ore_rewards = miner.rewards_ore as f64 / 1e11;
+                let sol_rewards = lamports_to_sol(miner.rewards_sol);
+                let ore_rewards = raw_ore_to_ore(miner.rewards_ore);
                 
                 if sol_rewards > 0.01 || ore_rewards > 0.1 {
                     info!("💰 Your claimable: {:.4} SOL | {:.4} ORE", sol_rewards, ore_rewards);
@@ -1270,28 +1449,59 @@ async fn main() {
                             }),
                         ).to_bot("miner-bot");
                         
-                        db.send_signal(&signal).await.ok();
+                        if signal_budget.allow(&signal) {
+                            db.send_signal(&signal).await.ok();
+                        }
                     }
                 }
             }
         }
 
-        // 5. Send heartbeat
+        // 5. Send heartbeat and store current state
+        let heartbeat_due = signal_budget.heartbeat_due(chrono::Utc::now().timestamp());
+        publish_cycle_heartbeat(&store, BOT_NAME, lifecycle.last_round_id, heartbeat_due).await.ok();
+        if let Err(e) = store.flush().await {
+            warn!("Failed to write state snapshot: {}", e);
+        }
+
         #[cfg(feature = "database")]
         if let Some(ref db) = db {
-            let heartbeat = Signal::new(
-                SignalType::Heartbeat,
-                BOT_NAME,
-                serde_json::json!({
-                    "timestamp": chrono::Utc::now().to_rfc3339(),
-                    "last_round": last_round_id,
-                }),
-            );
-            db.send_signal(&heartbeat).await.ok();
-            
-            // Store current state
-            db.set_state("current_round", serde_json::json!(last_round_id)).await.ok();
-            db.set_state("last_update", serde_json::json!(chrono::Utc::now().to_rfc3339())).await.ok();
+            // 6. Check other bots are still alive
+            if let Ok(heartbeats) = db.get_last_heartbeats().await {
+                let now = chrono::Utc::now().timestamp();
+                let dead = find_dead_bots(&heartbeats, now, dead_bot_timeout, BOT_NAME);
+                
+                for (bot, age) in &dead {
+                    if dead_bots.insert(bot.clone()) {
+                        error!("💀 {} has not sent a heartbeat for {}s", bot, age);
+                        let alert = Signal::new(
+                            SignalType::Error,
+                            BOT_NAME,
+                            serde_json::json!({
+                                "error": format!("{} missed heartbeat", bot),
+                                "bot": bot,
+                                "last_heartbeat_secs_ago": age,
+                                "timestamp": chrono::Utc::now().to_rfc3339(),
+                            }),
+                        );
+                        db.send_signal(&alert).await.ok();
+                    }
+                }
+                
+                // Recovered bots can be alerted on again
+                dead_bots.retain(|bot| dead.iter().any(|(b, _)| b == bot));
+                
+                let health: serde_json::Map<String, serde_json::Value> = heartbeats.iter()
+                    .map(|(bot, last)| {
+                        let alive = !dead.iter().any(|(b, _)| b == bot);
+                        (bot.clone(), serde_json::json!({
+                            "alive": alive,
+                            "last_heartbeat_secs_ago": now - last,
+                        }))
+                    })
+                    .collect();
+                db.set_state("bot_health", serde_json::Value::Object(health)).await.ok();
+            }
         }
 
         info!("⏳ Next update in {} seconds...\n", update_interval);
@@ -1304,5 +1514,15 @@ async fn main() {
         }
     }
 
+    // Let queued transaction writes drain before exiting
+    #[cfg(feature = "database")]
+    if let (Some(writer), Some(handle)) = (tx_writer, tx_writer_handle) {
+        let dropped = writer.dropped();
+        drop(writer);
+        if let Ok(written) = handle.await {
+            info!("💾 Transaction writer drained ({} written, {} dropped)", written, dropped);
+        }
+    }
+
     info!("✅ Coordinator stopped gracefully.");
 }
@@ -3,6 +3,7 @@ use clawdbot::{
     config::BotConfig,
     db::is_database_available,
     learning_engine::{LearningEngine, WinRecord, DetectedStrategy},
+    units::{lamports_to_sol, LAMPORTS_PER_SOL},
 };
 use colored::*;
 use log::{error, info, warn};
@@ -12,7 +13,6 @@ use tokio::time::{sleep, Duration};
 use clawdbot::db::SharedDb;
 
 const BOT_NAME: &str = "learning-bot";
-const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
 
 #[tokio::main]
 async fn main() {
@@ -72,6 +72,7 @@ async fn main() {
     let db = match SharedDb::connect().await {
         Ok(db) => {
             info!("✅ Database connected");
+            db.publish_config(BOT_NAME, &config).await.ok();
             Some(db)
         }
         Err(e) => {
@@ -108,27 +109,8 @@ async fn main() {
             info!("   ✅ Loaded {} historical wins", wins.len());
             
             // Convert and load into learning engine
-            for win_json in &wins {
-                if let Ok(win) = serde_json::from_value::<WinRecordFromDb>(win_json.clone()) {
-                    learning_engine.record_win(WinRecord {
-                        round_id: win.round_id as u64,
-                        winner_address: win.winner.clone(),
-                        winning_square: win.winning_square as u8,
-                        amount_bet: win.amount_bet as u64,
-                        amount_won: win.amount_won as u64,
-                        squares_bet: win.squares_bet.iter().map(|&s| s as u8).collect(),
-                        num_squares: win.num_squares as u8,
-                        total_round_sol: win.total_round_sol as u64,
-                        num_deployers: win.num_deployers as u32,
-                        is_motherlode: win.is_motherlode,
-                        is_full_ore: win.is_full_ore,
-                        ore_earned: win.ore_earned as f64,
-                        competition_on_square: 0,
-                        winner_share_pct: 0.0,
-                        slot: 0,
-                        timestamp: None,
-                    });
-                }
+            for win in wins.iter().filter_map(WinRecord::from_db_json) {
+                learning_engine.record_win(win);
             }
         }
         
@@ -234,10 +216,10 @@ async fn main() {
                             };
                             
                             info!("   📊 Round Analysis:");
-                            info!("      • Total deployed: {:.4} SOL", total_sol as f64 / LAMPORTS_PER_SOL as f64);
+                            info!("      • Total deployed: {:.4} SOL", lamports_to_sol(total_sol));
                             info!("      • Deployers: {}", num_deployers);
                             info!("      • Competition on square {}: {:.4} SOL", 
-                                winning_square, competition_on_square as f64 / LAMPORTS_PER_SOL as f64);
+                                winning_square, lamports_to_sol(competition_on_square));
                             info!("      • Full ORE: {}", if is_full_ore { "YES ✅" } else { "No" });
                             
                             // Find who won from tracked deploys (use 1-25 for comparison since we stored that way)
@@ -255,7 +237,7 @@ async fn main() {
                                     info!("{}", format!(
                                         "   👤 Winner: {} bet {:.4} SOL on {} squares, share: {:.1}%",
                                         &address[..8.min(address.len())],
-                                        *amount as f64 / LAMPORTS_PER_SOL as f64,
+                                        lamports_to_sol(*amount),
                                         num_squares,
                                         winner_share * 100.0
                                     ).green());
@@ -345,7 +327,7 @@ async fn main() {
                         
                         // Record in learning engine (1-25)
                         learning_engine.record_deploy(
-                            &tx.signer,
+                            tx.authority(),
                             deploy.amount_lamports,
                             &squares_1_25,
                             total_round_sol,
@@ -355,7 +337,7 @@ async fn main() {
                         
                         // Track for this round (1-25)
                         current_round_deploys.insert(
-                            tx.signer.clone(),
+                            tx.authority().to_string(),
                             (deploy.amount_lamports, squares_1_25.clone())
                         );
                         
@@ -363,7 +345,7 @@ async fn main() {
                         #[cfg(feature = "database")]
                         if let Some(ref db) = db {
                             db.record_player_deploy(
-                                &tx.signer,
+                                tx.authority(),
                                 deploy.amount_lamports as i64,
                                 square_count as i16,
                                 tx.slot as i64,
@@ -409,10 +391,10 @@ async fn main() {
                             };
                             
                             info!("   📊 Round Analysis:");
-                            info!("      • Total deployed: {:.4} SOL", total_sol as f64 / LAMPORTS_PER_SOL as f64);
+                            info!("      • Total deployed: {:.4} SOL", lamports_to_sol(total_sol));
                             info!("      • Deployers: {}", num_deployers);
                             info!("      • Competition on winning square: {:.4} SOL", 
-                                competition_on_square as f64 / LAMPORTS_PER_SOL as f64);
+                                lamports_to_sol(competition_on_square));
                             info!("      • Estimated ORE: {:.2}", ore_earned);
                             info!("      • Full ORE: {}", if is_full_ore { "YES ✅" } else { "No" });
                             
@@ -429,7 +411,7 @@ async fn main() {
                                     info!("{}", format!(
                                         "   👤 Winner: {} bet {:.4} SOL on {} squares, share: {:.1}%",
                                         &address[..8],
-                                        *amount as f64 / LAMPORTS_PER_SOL as f64,
+                                        lamports_to_sol(*amount),
                                         num_squares,
                                         winner_share * 100.0
                                     ).green());
@@ -591,19 +573,3 @@ async fn main() {
     info!("✅ Learning bot stopped gracefully.");
 }
 
-// Helper struct for deserializing win records from database
-#[derive(serde::Deserialize)]
-struct WinRecordFromDb {
-    round_id: i64,
-    winner: String,
-    winning_square: i16,
-    amount_bet: i64,
-    amount_won: i64,
-    squares_bet: Vec<i32>,
-    num_squares: i16,
-    total_round_sol: i64,
-    num_deployers: i32,
-    is_motherlode: bool,
-    is_full_ore: bool,
-    ore_earned: f32,
-}
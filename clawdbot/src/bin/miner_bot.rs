@@ -1,12 +1,17 @@
 use clawdbot::{
     ai_advisor::AIAdvisor,
     blockchain_parser::BlockchainParser,
+    board_trend::SquareTrend,
     bot::BotStatus,
-    client::OreClient,
+    client::{send_with_policy, ConfirmPolicy, OreClient},
     config::BotConfig,
     db::is_database_available,
-    error::Result,
-    ore_strategy::{OreStrategyEngine, DeployDecision, CompetitionLevel, PlayerPerformance, SquareCountStats},
+    error::{BotError, DeployAction, DeployError, Result},
+    learning_engine::CheckpointTracker,
+    round_phase::{check_accepting_deploys, secs_remaining, GameGate, PhaseThresholds, RoundPhase},
+    ore_strategy::{deploy_with_retry, BalanceGuard, BetJitter, ClaimPlan, OreStrategyEngine, DeployDecision, CompetitionLevel, PlayerPerformance, RoundThrottle, SquareCountStats},
+    units::{lamports_to_sol, raw_ore_to_ore, sol_to_lamports},
+    webhook::{WebhookNotifier, WebhookPayload},
 };
 use colored::*;
 use log::{error, info, warn};
@@ -21,7 +26,9 @@ use std::sync::{Arc, RwLock};
 use tokio::time::{sleep, Duration};
 
 #[cfg(feature = "database")]
-use clawdbot::db::SharedDb;
+use clawdbot::db::{SharedDb, Signal, SignalType};
+#[cfg(feature = "database")]
+use clawdbot::state_store::SignalBudget;
 
 /// ORE Game Configuration
 /// Key rules from user:
@@ -33,18 +40,8 @@ use clawdbot::db::SharedDb;
 
 const MIN_WALLET_SOL: f64 = 0.05;
 const MAX_BET_PER_ROUND_SOL: f64 = 0.04;
-const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
-
-/// Timing thresholds for MANUAL mode (slower, we sign ourselves)
-const MANUAL_DECISION_TIME: f64 = 5.0;
-const MANUAL_SIGN_DEADLINE: f64 = 3.0;
-const MANUAL_TOO_LATE: f64 = 1.5;
-
-/// Timing thresholds for EXECUTOR mode (fast, automation pre-funded)
-/// We can push much closer to 0 since we sign instantly
-const EXECUTOR_DECISION_TIME: f64 = 2.0;   // Start analyzing
-const EXECUTOR_SIGN_DEADLINE: f64 = 0.8;   // Execute here - max intel, still safe
-const EXECUTOR_TOO_LATE: f64 = 0.4;        // ~1 slot, too risky
+#[cfg(feature = "database")]
+const BOT_NAME: &str = "miner-bot";
 
 /// Load keypair from file path or from environment variable
 fn load_keypair(keypair_path: &str) -> std::result::Result<Keypair, String> {
@@ -87,6 +84,19 @@ struct SmartMinerBot {
     mode: String,           // "simulation", "live", or "executor"
     authority: Option<Pubkey>,  // For executor mode: whose automation to trigger
     ai_advisor: AIAdvisor,  // AI-powered decision enhancement
+    webhook: Option<WebhookNotifier>,  // Deploy/win alerts
+    max_inflight_deploys: u32,  // Executor deploys per authority per round, fleet-wide
+    lease_holder: String,       // This executor's name in deploy_leases
+    #[cfg(feature = "database")]
+    db: Option<SharedDb>,       // Opened once at startup; None = no database (and no deploy lease)
+    #[cfg(feature = "database")]
+    heartbeats: SignalBudget,   // Spaces out our heartbeat signals
+    game_gate: GameGate,        // Pauses us while the board says the game is halted
+    claim_threshold_sol: f64,   // Auto-claim SOL rewards at/above this
+    claim_threshold_ore: f64,   // Auto-claim ORE rewards at/above this
+    round_throttle: Option<RoundThrottle>,  // Hourly cap on rounds played
+    balance_guard: Option<BalanceGuard>,    // Pauses on unexplained wallet drops
+    confirm_policy: ConfirmPolicy,          // When a live deploy counts as done
     
     // Tracking
     rounds_played: u32,
@@ -94,6 +104,21 @@ struct SmartMinerBot {
     total_deployed: u64,
     total_won: u64,
     ore_earned: f64,
+    total_claimed_sol: u64,
+    total_claimed_ore: u64,
+    last_deploy: Option<SentDeploy>,
+    awaiting_checkpoint: Vec<(SentDeploy, bool)>, // Finished deploys (and whether they won) not yet credited
+    aborted_round: Option<u64>,                    // Round a deploy failed in for good
+    checkpoints: CheckpointTracker,
+}
+
+/// Our most recent on-chain deploy, kept to check for a win when the round ends
+struct SentDeploy {
+    round_id: u64,
+    squares: Vec<usize>, // 0-24
+    amount_lamports: u64,
+    expected_ore: f64,
+    signature: String,
 }
 
 impl SmartMinerBot {
@@ -122,21 +147,37 @@ impl SmartMinerBot {
             mode,
             authority,
             ai_advisor,
+            webhook: None,
+            max_inflight_deploys: 1,
+            lease_holder: std::env::var("EXECUTOR_ID")
+                .unwrap_or_else(|_| format!("executor-{}-{:08x}", std::process::id(), rand::random::<u32>())),
+            #[cfg(feature = "database")]
+            db: None,
+            #[cfg(feature = "database")]
+            heartbeats: SignalBudget::new(0, 30),
+            game_gate: GameGate::new(),
+            claim_threshold_sol: 0.0,
+            claim_threshold_ore: 0.0,
+            round_throttle: None,
+            balance_guard: None,
+            confirm_policy: ConfirmPolicy::default(),
             rounds_played: 0,
             rounds_won: 0,
             total_deployed: 0,
             total_won: 0,
             ore_earned: 0.0,
+            total_claimed_sol: 0,
+            total_claimed_ore: 0,
+            last_deploy: None,
+            awaiting_checkpoint: Vec::new(),
+            aborted_round: None,
+            checkpoints: CheckpointTracker::default(),
         })
     }
     
-    /// Get timing thresholds based on mode
-    fn get_timing(&self) -> (f64, f64, f64) {
-        if self.mode == "executor" {
-            (EXECUTOR_DECISION_TIME, EXECUTOR_SIGN_DEADLINE, EXECUTOR_TOO_LATE)
-        } else {
-            (MANUAL_DECISION_TIME, MANUAL_SIGN_DEADLINE, MANUAL_TOO_LATE)
-        }
+    /// Timing thresholds for this bot's mode
+    fn thresholds(&self) -> PhaseThresholds {
+        PhaseThresholds::for_mode(&self.mode)
     }
 
     /// Load learned strategies from database
@@ -200,6 +241,16 @@ impl SmartMinerBot {
             }
         }
         
+        // Load recent winners so detected strategies can be checked for recent form
+        if let Ok(rounds) = db.load_round_history(self.ore_strategy.recent_form_rounds as i32).await {
+            let winners: Vec<u8> = rounds.iter()
+                .rev() // oldest first
+                .filter(|(_, sq, _, _, _)| *sq >= 1 && *sq <= 25)
+                .map(|(_, sq, _, _, _)| *sq as u8)
+                .collect();
+            self.ore_strategy.load_recent_winners(winners);
+        }
+        
         // Load detected strategies (the key learning!)
         if let Ok(strategies) = db.load_detected_strategies().await {
             if !strategies.is_empty() {
@@ -247,13 +298,8 @@ impl SmartMinerBot {
     async fn execute_deploy(&self, decision: &DeployDecision, round_id: u64) -> Result<String> {
         info!("{}", "⚡ EXECUTING MANUAL DEPLOY...".green().bold());
         
-        // Convert squares Vec to [bool; 25] array
-        let mut squares_arr = [false; 25];
-        for &sq in &decision.squares {
-            if sq < 25 {
-                squares_arr[sq] = true;
-            }
-        }
+        // Decision squares are 1-25; the instruction takes a 0-24 mask
+        let squares_arr = decision.square_mask();
         
         // Build the deploy instruction using ore_api
         let ix = ore_api::sdk::deploy(
@@ -282,16 +328,63 @@ impl SmartMinerBot {
             blockhash,
         );
         
-        // Send and confirm
+        // Send, then confirm to the configured commitment
         info!("   📤 Sending transaction...");
-        let signature = rpc_client.send_and_confirm_transaction(&tx)
-            .map_err(|e| clawdbot::error::BotError::RpcTimeout(format!("Transaction failed: {}", e)))?;
+        let signature = send_with_policy(&rpc_client, &tx, self.confirm_policy)
+            .map_err(|e| DeployError::from_client_error(&e))?;
         
-        info!("{}", format!("   ✅ Transaction confirmed: {}", signature).green());
+        if self.confirm_policy.wait {
+            info!("{}", format!("   ✅ Transaction {:?}: {}", self.confirm_policy.commitment.commitment, signature).green());
+        } else {
+            info!("   ⏩ Not waiting for confirmation: {}", signature);
+        }
         
         Ok(signature.to_string())
     }
 
+    /// Live deploy; if the wallet can't cover it (fees moved), retry once with
+    /// what it can still cover above the wallet reserves while the round takes deploys
+    /// Returns the signature and the decision that was sent
+    async fn execute_live_deploy(
+        &self,
+        decision: &DeployDecision,
+        round_id: u64,
+        board: &ore_api::state::Board,
+    ) -> Result<(String, DeployDecision)> {
+        let (signature, sent) = deploy_with_retry(
+            decision,
+            move |d| async move { self.execute_deploy(&d, round_id).await },
+            move || self.get_balance().map(|balance| self.ore_strategy.deployable_lamports(balance)),
+            move || !matches!(self.get_round_phase(board).0, RoundPhase::TooLate | RoundPhase::Ended),
+        )
+        .await?;
+        if sent.total_amount_lamports != decision.total_amount_lamports {
+            warn!("   ↳ Insufficient funds - sent {:.4} SOL instead of {:.4} SOL",
+                lamports_to_sol(sent.total_amount_lamports),
+                lamports_to_sol(decision.total_amount_lamports));
+        }
+        Ok((signature, sent))
+    }
+
+    /// Executor deploy with the same in-round retry as live deploys
+    /// The automation account's balance isn't ours to cut a bet down to, so
+    /// insufficient funds is not retried here
+    async fn execute_executor_with_retry(
+        &self,
+        decision: &DeployDecision,
+        round_id: u64,
+        board: &ore_api::state::Board,
+    ) -> Result<String> {
+        deploy_with_retry(
+            decision,
+            move |d| async move { self.execute_leased_executor_deploy(&d, round_id).await },
+            || Ok(0),
+            move || !matches!(self.get_round_phase(board).0, RoundPhase::TooLate | RoundPhase::Ended),
+        )
+        .await
+        .map(|(signature, _)| signature)
+    }
+
     /// Execute a deploy via automation account (EXECUTOR mode)
     /// This is FAST - we sign with our keypair, SOL comes from pre-funded automation
     async fn execute_executor_deploy(&self, decision: &DeployDecision, round_id: u64) -> Result<String> {
@@ -303,13 +396,8 @@ impl SmartMinerBot {
         info!("   Authority: {}", authority);
         info!("   Squares: {:?}", decision.squares);
         
-        // Convert squares Vec to [bool; 25] array
-        let mut squares_arr = [false; 25];
-        for &sq in &decision.squares {
-            if sq < 25 {
-                squares_arr[sq] = true;
-            }
-        }
+        // Decision squares are 1-25; the instruction takes a 0-24 mask
+        let squares_arr = decision.square_mask();
         
         // Build the deploy instruction - WE are signer, AUTHORITY owns the automation
         // In Discretionary mode, we (executor) choose the squares via the mask
@@ -342,7 +430,7 @@ impl SmartMinerBot {
         // Send transaction (don't wait for full confirmation for speed)
         info!("   📤 Sending executor transaction...");
         let signature = rpc_client.send_transaction(&tx)
-            .map_err(|e| clawdbot::error::BotError::RpcTimeout(format!("Transaction failed: {}", e)))?;
+            .map_err(|e| DeployError::from_client_error(&e))?;
         
         info!("{}", format!("   ✅ Transaction sent: {}", signature).green());
         info!("   ⏱️  Deployed at ~{:.2}s before round end", self.get_time_remaining(&self.parser.get_board()?));
@@ -350,20 +438,221 @@ impl SmartMinerBot {
         Ok(signature.to_string())
     }
 
+    /// Executor deploy guarded by a fleet-wide lease so executors sharing an
+    /// authority don't exceed `max_inflight_deploys` in one round
+    /// If the lease database can't be reached the deploy goes out without one
+    async fn execute_leased_executor_deploy(&self, decision: &DeployDecision, round_id: u64) -> Result<String> {
+        #[cfg(feature = "database")]
+        if let (Some(authority), Some(db)) = (self.authority, self.db.as_ref()) {
+            let authority = authority.to_string();
+            
+            match db.acquire_deploy_lease(&authority, round_id as i64, &self.lease_holder, self.max_inflight_deploys).await {
+                Ok(true) => {
+                    let result = self.execute_executor_deploy(decision, round_id).await;
+                    if result.is_err() {
+                        db.release_deploy_lease(&authority, round_id as i64, &self.lease_holder).await.ok();
+                    }
+                    return result;
+                }
+                Ok(false) => {
+                    return Err(clawdbot::error::BotError::Other(format!(
+                        "{} executor deploy(s) already in flight for round {}", self.max_inflight_deploys, round_id
+                    )));
+                }
+                Err(e) => warn!("⚠️ Deploy lease unavailable ({}) - deploying without it", e),
+            }
+        }
+        
+        self.execute_executor_deploy(decision, round_id).await
+    }
+
+    /// Claim SOL/ORE rewards that reached the configured thresholds
+    /// Only called outside the deploy window; executor rewards belong to the
+    /// authority's miner account, which we can't sign for
+    async fn claim_rewards(&mut self) {
+        if self.mode == "executor" {
+            return;
+        }
+        
+        let miner = match self.parser.get_miner(self.keypair.pubkey()) {
+            Ok(Some(miner)) => miner,
+            Ok(None) => return,
+            Err(e) => {
+                warn!("Failed to fetch miner rewards: {}", e);
+                return;
+            }
+        };
+        
+        let plan = ClaimPlan::decide(miner.rewards_sol, miner.rewards_ore, self.claim_threshold_sol, self.claim_threshold_ore);
+        if plan.is_empty() {
+            return;
+        }
+        
+        let sol = lamports_to_sol(plan.sol_lamports);
+        let ore = raw_ore_to_ore(plan.ore_grams);
+        if self.mode != "live" {
+            info!("💰 SIMULATION MODE - would claim {:.4} SOL / {:.4} ORE", sol, ore);
+            return;
+        }
+        
+        info!("💰 Claiming {:.4} SOL / {:.4} ORE...", sol, ore);
+        match self.execute_claim(&plan).await {
+            Ok(sig) => {
+                self.total_claimed_sol += plan.sol_lamports;
+                self.total_claimed_ore += plan.ore_grams;
+                info!("{}", format!("   ✅ Claim confirmed: {}", sig).green());
+                
+                #[cfg(feature = "database")]
+                if let Some(ref db) = self.db {
+                    db.set_state("miner_claims", serde_json::json!({
+                        "total_claimed_sol": lamports_to_sol(self.total_claimed_sol),
+                        "total_claimed_ore": raw_ore_to_ore(self.total_claimed_ore),
+                        "last_signature": sig,
+                        "timestamp": chrono::Utc::now().to_rfc3339(),
+                    })).await.ok();
+                }
+            }
+            Err(e) => {
+                error!("   ❌ Claim failed: {}", e);
+            }
+        }
+    }
+
+    /// Read the miner account and credit realized ORE to finished deploys it has checkpointed
+    async fn poll_checkpoint(&mut self) {
+        let owner = self.authority.unwrap_or_else(|| self.keypair.pubkey());
+        let miner = match self.parser.get_miner(owner) {
+            Ok(Some(miner)) => miner,
+            Ok(None) => return,
+            Err(e) => {
+                warn!("Failed to fetch miner rewards: {}", e);
+                return;
+            }
+        };
+        let Some((checkpointed, ore)) = self.checkpoints.observe(miner.checkpoint_id, miner.rewards_ore, self.total_claimed_ore) else {
+            return;
+        };
+
+        for (deploy, won) in std::mem::take(&mut self.awaiting_checkpoint) {
+            if deploy.round_id > checkpointed {
+                self.awaiting_checkpoint.push((deploy, won));
+                continue;
+            }
+            if deploy.round_id < checkpointed {
+                // Its checkpoint fell between reads we missed
+                info!("   Round {} ORE: expected {:.3}, realized unknown", deploy.round_id, deploy.expected_ore);
+                continue;
+            }
+            self.ore_earned += ore;
+            info!("   Round {} ORE: expected {:.3}, realized {:.3}", deploy.round_id, deploy.expected_ore, ore);
+
+            // Keep both for checking the ORE model
+            #[cfg(feature = "database")]
+            if let Some(ref db) = self.db {
+                db.record_expected_ore(deploy.round_id as i64, deploy.expected_ore as f32).await.ok();
+                db.record_our_round_result(deploy.round_id as i64, won, ore as f32).await.ok();
+            }
+        }
+    }
+
+    /// Send ClaimSOL/ClaimORE for the plan in one transaction
+    async fn execute_claim(&self, plan: &ClaimPlan) -> Result<String> {
+        let mut ixs = Vec::new();
+        if plan.sol_lamports > 0 {
+            ixs.push(ore_api::sdk::claim_sol(self.keypair.pubkey()));
+        }
+        if plan.ore_grams > 0 {
+            ixs.push(ore_api::sdk::claim_ore(self.keypair.pubkey()));
+        }
+        
+        let rpc_client = RpcClient::new_with_commitment(
+            self.rpc_url.clone(),
+            CommitmentConfig::confirmed(),
+        );
+        
+        let blockhash = rpc_client.get_latest_blockhash()
+            .map_err(|e| clawdbot::error::BotError::RpcTimeout(format!("Failed to get blockhash: {}", e)))?;
+        
+        let tx = Transaction::new_signed_with_payer(
+            &ixs,
+            Some(&self.keypair.pubkey()),
+            &[&self.keypair],
+            blockhash,
+        );
+        
+        let signature = rpc_client.send_and_confirm_transaction(&tx)
+            .map_err(|e| clawdbot::error::BotError::RpcTimeout(format!("Claim transaction failed: {}", e)))?;
+        
+        Ok(signature.to_string())
+    }
+
+    /// Count a round as played (sent or simulated)
+    fn record_round_played(&mut self, decision: &DeployDecision) {
+        self.rounds_played += 1;
+        self.total_deployed += decision.total_amount_lamports;
+        if let Some(ref mut throttle) = self.round_throttle {
+            throttle.record(chrono::Utc::now().timestamp());
+        }
+    }
+
+    /// Remember a sent deploy and alert the webhook
+    fn on_deploy_sent(&mut self, decision: &DeployDecision, round_id: u64, signature: &str) {
+        if let Some(ref webhook) = self.webhook {
+            webhook.notify(WebhookPayload::deploy(
+                &self.name,
+                round_id,
+                &decision.board_indexes(),
+                decision.total_amount_lamports,
+                signature,
+            ));
+        }
+        self.last_deploy = Some(SentDeploy {
+            round_id,
+            squares: decision.board_indexes(),
+            amount_lamports: decision.total_amount_lamports,
+            expected_ore: decision.expected_ore,
+            signature: signature.to_string(),
+        });
+        if let Some(ref mut guard) = self.balance_guard {
+            guard.record_spend(decision.total_amount_lamports);
+        }
+    }
+
+    /// React to a deploy that still failed after any in-round retry
+    fn on_deploy_failed(&mut self, error: &BotError, round_id: u64) {
+        error!("   ❌ Deploy failed: {}", error);
+        let BotError::Deploy(reason) = error else {
+            return;
+        };
+        match reason.action() {
+            DeployAction::Halt => {
+                error!("🚨 {} - pausing, resume once it's fixed", reason);
+                *self.status.write().unwrap() = BotStatus::Paused;
+                // Held like an operator pause, so a game restart doesn't resume us
+                self.game_gate = GameGate::new();
+            }
+            // Retry and ReduceAmount already had their one extra send
+            DeployAction::Abort | DeployAction::Retry | DeployAction::ReduceAmount => {
+                warn!("   ↳ {} - no more deploys in round {}", reason, round_id);
+                self.aborted_round = Some(round_id);
+            }
+        }
+    }
+
     /// Calculate time remaining in current round
     fn get_time_remaining(&self, board: &ore_api::state::Board) -> f64 {
+        self.get_round_phase(board).1
+    }
+
+    /// Current round phase for this mode, plus seconds remaining
+    fn get_round_phase(&self, board: &ore_api::state::Board) -> (RoundPhase, f64) {
         let current_slot = match self.parser.get_slot() {
             Ok(s) => s,
-            Err(_) => return 60.0, // Default to full round on error
+            Err(_) => return (RoundPhase::Early, 60.0), // Default to full round on error
         };
         
-        if current_slot >= board.end_slot {
-            return 0.0;
-        }
-        
         let slots_remaining = board.end_slot.saturating_sub(current_slot);
-        // Solana targets ~400ms/slot but often runs faster (~350-380ms)
-        slots_remaining as f64 / 2.7
+        (RoundPhase::from_slots(slots_remaining, &self.thresholds()), secs_remaining(slots_remaining))
     }
 
     /// Main mining loop
@@ -371,6 +660,7 @@ impl SmartMinerBot {
         info!("⛏️  Smart Miner started!");
         info!("   Min wallet: {:.4} SOL", self.ore_strategy.min_wallet_sol);
         info!("   Max bet/round: {:.4} SOL", self.ore_strategy.max_bet_per_round_sol);
+        info!("   Claim reserve: {:.4} SOL", lamports_to_sol(self.ore_strategy.reserve_for_claims_lamports));
         
         let mut last_round_id: u64 = 0;
         let update_interval = 10; // Check every 10 seconds
@@ -382,7 +672,8 @@ impl SmartMinerBot {
                 if *status == BotStatus::Stopped {
                     break;
                 }
-                if *status == BotStatus::Paused {
+                // Paused by the operator; a halted game is rechecked below
+                if *status == BotStatus::Paused && self.game_gate.reason().is_none() {
                     sleep(Duration::from_secs(1)).await;
                     continue;
                 }
@@ -397,8 +688,34 @@ impl SmartMinerBot {
                     continue;
                 }
             };
+
+            // Stop deploying if funds left the wallet without us sending them
+            if let Some(Err(drop)) = self.balance_guard.as_mut().map(|g| g.check(balance)) {
+                error!("🚨 {} - pausing, resume once the wallet is checked", drop);
+                *self.status.write().unwrap() = BotStatus::Paused;
+                // Held like an operator pause, so a game restart doesn't resume us
+                self.game_gate = GameGate::new();
+                if let Some(ref webhook) = self.webhook {
+                    webhook.notify(WebhookPayload::balance_drop(&self.name, last_round_id, drop.shortfall_lamports));
+                }
+                #[cfg(feature = "database")]
+                if let Some(ref db) = self.db {
+                    let alert = Signal::new(
+                        SignalType::Error,
+                        BOT_NAME,
+                        serde_json::json!({
+                            "kind": "balance_drop",
+                            "expected_sol": lamports_to_sol(drop.expected_lamports),
+                            "actual_sol": lamports_to_sol(drop.actual_lamports),
+                            "shortfall_sol": lamports_to_sol(drop.shortfall_lamports),
+                        }),
+                    );
+                    db.send_signal(&alert).await.ok();
+                }
+                continue;
+            }
             
-            let balance_sol = balance as f64 / LAMPORTS_PER_SOL as f64;
+            let balance_sol = lamports_to_sol(balance);
             let rounds_remaining = self.ore_strategy.estimate_rounds_remaining(balance);
 
             // Get current round
@@ -411,6 +728,17 @@ impl SmartMinerBot {
                 }
             };
             
+            // Pause instead of failing deploys while the game is halted
+            let current_slot = self.parser.get_slot().unwrap_or(board.start_slot);
+            let check = check_accepting_deploys(board.round_id, board.start_slot, board.end_slot, current_slot);
+            let status = self.game_gate.update(*self.status.read().unwrap(), check);
+            *self.status.write().unwrap() = status;
+            if let Some(halt) = self.game_gate.reason() {
+                warn!("⏸️  Paused: {} - rechecking in 30s", DeployError::from(halt.clone()));
+                sleep(Duration::from_secs(30)).await;
+                continue;
+            }
+            
             let round = match self.parser.get_round(board.round_id) {
                 Ok(r) => r,
                 Err(e) => {
@@ -431,29 +759,53 @@ impl SmartMinerBot {
             info!("💰 Balance: {:.4} SOL | Est. rounds: {}", balance_sol, rounds_remaining);
             info!("📊 Round {} | Deployed: {:.4} SOL | Competition: {:?}", 
                 current_round_id,
-                total_deployed as f64 / LAMPORTS_PER_SOL as f64,
+                lamports_to_sol(total_deployed),
                 competition);
             info!("⏱️  Time remaining: {:.1}s", time_remaining);
             
             // Get consensus recommendation from coordinator (THE SINGLE SOURCE OF TRUTH)
             let mut coordinator_squares: Vec<usize> = Vec::new();
+            let mut coordinator_weights: Vec<f64> = Vec::new();
             let mut coordinator_confidence: f64 = 0.0;
             let mut coordinator_optimal_count: u8 = 0;
+            let mut competition_trend: Option<[SquareTrend; 25]> = None;
             
             #[cfg(feature = "database")]
-            if is_database_available() {
-                if let Ok(db) = SharedDb::connect().await {
-                    if let Ok(Some(rec)) = db.get_state("consensus_recommendation").await {
-                        if let Some(squares) = rec["squares"].as_array() {
-                            coordinator_squares = squares.iter()
-                                .filter_map(|s| s.as_u64().map(|n| n as usize))
-                                .collect();
-                        }
-                        coordinator_confidence = rec["confidence"].as_f64().unwrap_or(0.0);
-                        coordinator_optimal_count = rec["optimal_count"].as_u64().unwrap_or(5) as u8;
-                        
-                        info!("📡 Coordinator decision: {:?} ({} squares, {:.0}% confidence)", 
-                            coordinator_squares, coordinator_optimal_count, coordinator_confidence * 100.0);
+            if let Some(ref db) = self.db {
+                // Heartbeat so the coordinator can tell we're alive
+                if self.heartbeats.heartbeat_due(chrono::Utc::now().timestamp()) {
+                    let heartbeat = Signal::new(
+                        SignalType::Heartbeat,
+                        BOT_NAME,
+                        serde_json::json!({
+                            "timestamp": chrono::Utc::now().to_rfc3339(),
+                            "round": current_round_id,
+                            "balance_sol": balance_sol,
+                        }),
+                    );
+                    db.send_signal(&heartbeat).await.ok();
+                }
+                
+                if let Ok(Some(rec)) = db.get_state("consensus_recommendation").await {
+                    if let Some(squares) = rec["squares"].as_array() {
+                        coordinator_squares = squares.iter()
+                            .filter_map(|s| s.as_u64().map(|n| n as usize))
+                            .collect();
+                    }
+                    if let Some(weights) = rec["weights"].as_array() {
+                        coordinator_weights = weights.iter().filter_map(|w| w.as_f64()).collect();
+                    }
+                    coordinator_confidence = rec["confidence"].as_f64().unwrap_or(0.0);
+                    coordinator_optimal_count = rec["optimal_count"].as_u64().unwrap_or(5) as u8;
+                    
+                    info!("📡 Coordinator decision: {:?} ({} squares, {:.0}% confidence)", 
+                        coordinator_squares, coordinator_optimal_count, coordinator_confidence * 100.0);
+                }
+                
+                // This round's per-square fill trend, as the coordinator last saw it
+                if let Ok(Some(status)) = db.get_state("monitor_status").await {
+                    if status["round_id"].as_u64() == Some(current_round_id) {
+                        competition_trend = serde_json::from_value(status["competition_trend"].clone()).ok();
                     }
                 }
             }
@@ -466,7 +818,7 @@ impl SmartMinerBot {
                 self.ai_advisor.get_recommendation(
                     current_round_id,
                     &round.deployed,
-                    total_deployed as f64 / LAMPORTS_PER_SOL as f64,
+                    lamports_to_sol(total_deployed),
                     num_deployers,
                     time_remaining,
                     &coordinator_squares,
@@ -479,12 +831,12 @@ impl SmartMinerBot {
             };
             
             // COORDINATOR IS THE DECIDER - only override if AI has MUCH higher confidence
-            let final_squares = if let Some(ref ai_rec) = ai_recommendation {
+            let (final_squares, final_weights) = if let Some(ref ai_rec) = ai_recommendation {
                 if ai_rec.confidence > 0.85 && ai_rec.confidence > coordinator_confidence + 0.2 {
                     // AI is very confident and significantly more confident than coordinator
                     info!("🤖 AI override! (conf: {:.0}% >> coordinator {:.0}%)", 
                         ai_rec.confidence * 100.0, coordinator_confidence * 100.0);
-                    ai_rec.suggested_squares.clone()
+                    (ai_rec.suggested_squares.clone(), Vec::new())
                 } else {
                     // Use coordinator decision (the single source of truth)
                     if !ai_rec.suggested_squares.is_empty() {
@@ -496,10 +848,10 @@ impl SmartMinerBot {
                             info!("🤖 AI agrees on squares: {:?}", overlap);
                         }
                     }
-                    coordinator_squares.clone()
+                    (coordinator_squares.clone(), coordinator_weights.clone())
                 }
             } else {
-                coordinator_squares.clone()
+                (coordinator_squares.clone(), coordinator_weights.clone())
             };
             
             let final_confidence = if let Some(ref ai_rec) = ai_recommendation {
@@ -514,56 +866,68 @@ impl SmartMinerBot {
             };
 
             // Execute coordinator's decision (miner just executes, doesn't decide)
-            let decision = self.ore_strategy.make_deploy_decision(
+            let mut decision = self.ore_strategy.make_deploy_decision(
                 balance,
                 &round.deployed,
                 num_deployers,
                 &final_squares,
+                &final_weights,
                 final_confidence,
+                competition_trend.as_ref(),
             );
+            if decision.should_deploy {
+                if let Some(reason) = self.round_throttle.as_mut().and_then(|t| t.check(chrono::Utc::now().timestamp())) {
+                    decision = DeployDecision::skip(reason);
+                }
+            }
+            if decision.should_deploy && self.aborted_round == Some(current_round_id) {
+                info!("   A deploy already failed for good this round");
+                decision.should_deploy = false;
+            }
 
             if decision.should_deploy {
                 info!("{}", format!("🎯 DEPLOY DECISION: YES").green().bold());
                 info!("   Squares: {:?} ({} total)", decision.squares, decision.squares.len());
                 info!("   Amount: {:.4} SOL ({:.6} per square)", 
-                    decision.total_amount_lamports as f64 / LAMPORTS_PER_SOL as f64,
-                    decision.per_square_lamports as f64 / LAMPORTS_PER_SOL as f64);
+                    lamports_to_sol(decision.total_amount_lamports),
+                    lamports_to_sol(decision.per_square_lamports));
                 info!("   Expected ORE: {:.2}", decision.expected_ore);
                 info!("   Reasoning: {}", decision.reasoning);
                 
-                // Get timing thresholds based on mode
-                let (decision_time, sign_deadline, too_late) = self.get_timing();
-                let time_remaining = self.get_time_remaining(&board);
+                // Where are we in the round for this mode's timing?
+                let timing = self.thresholds();
+                let (phase, time_remaining) = self.get_round_phase(&board);
                 
-                info!("   Mode: {} | Timing: decide@{:.1}s, sign@{:.1}s, late@{:.1}s", 
-                    self.mode, decision_time, sign_deadline, too_late);
+                info!("   Mode: {} | Phase: {} | Timing: decide@{:.1}s, sign@{:.1}s, late@{:.1}s", 
+                    self.mode, phase, timing.decision_secs, timing.sign_secs, timing.too_late_secs);
                 
-                if time_remaining <= too_late {
-                    // Too late - skip this round
-                    warn!("   💀 TOO LATE ({:.1}s remaining) - waiting for next round", time_remaining);
-                } else if time_remaining <= sign_deadline {
-                    // In the signing window - execute immediately!
-                    let result = match self.mode.as_str() {
-                        "executor" => self.execute_executor_deploy(&decision, current_round_id).await,
-                        "live" => self.execute_deploy(&decision, current_round_id).await,
-                        _ => {
-                            info!("   📋 SIMULATION MODE - would execute at {:.1}s", time_remaining);
-                            self.rounds_played += 1;
-                            self.total_deployed += decision.total_amount_lamports;
-                            Ok("simulation".to_string())
-                        }
-                    };
+                match phase {
+                    RoundPhase::TooLate | RoundPhase::Ended => {
+                        // Too late - skip this round
+                        warn!("   💀 TOO LATE ({:.1}s remaining) - waiting for next round", time_remaining);
+                    }
+                    RoundPhase::Signing => {
+                        // In the signing window - execute immediately!
+                        let result = match self.mode.as_str() {
+                            "executor" => self.execute_executor_with_retry(&decision, current_round_id, &board).await,
+                            "live" => self.execute_live_deploy(&decision, current_round_id, &board).await
+                                .map(|(sig, sent)| { decision = sent; sig }),
+                            _ => {
+                                info!("   📋 SIMULATION MODE - would execute at {:.1}s", time_remaining);
+                                self.record_round_played(&decision);
+                                Ok("simulation".to_string())
+                            }
+                        };
                     
-                    match result {
-                        Ok(sig) if sig != "simulation" => {
-                            info!("   🎉 Deploy successful! Signature: {}", sig);
-                            self.rounds_played += 1;
-                            self.total_deployed += decision.total_amount_lamports;
+                        match result {
+                            Ok(sig) if sig != "simulation" => {
+                                info!("   🎉 Deploy successful! Signature: {}", sig);
+                                self.record_round_played(&decision);
+                                self.on_deploy_sent(&decision, current_round_id, &sig);
                             
-                            // Log to database
-                            #[cfg(feature = "database")]
-                            if is_database_available() {
-                                if let Ok(db) = SharedDb::connect().await {
+                                // Log to database
+                                #[cfg(feature = "database")]
+                                if let Some(ref db) = self.db {
                                     db.set_state("last_deploy", serde_json::json!({
                                         "round_id": current_round_id,
                                         "squares": decision.squares,
@@ -575,47 +939,45 @@ impl SmartMinerBot {
                                     })).await.ok();
                                 }
                             }
+                            Err(e) => self.on_deploy_failed(&e, current_round_id),
+                            _ => {}
                         }
-                        Err(e) => {
-                            error!("   ❌ Deploy failed: {}", e);
-                        }
-                        _ => {}
                     }
-                } else if time_remaining <= decision_time {
-                    // In decision window - wait for optimal timing
-                    let wait_time = (time_remaining - sign_deadline).max(0.1);
-                    info!("   ⏳ Waiting {:.1}s for optimal timing ({:.1}s target)...", 
-                        wait_time, sign_deadline);
-                    sleep(Duration::from_secs_f64(wait_time)).await;
+                    RoundPhase::Decision => {
+                        // In decision window - wait for optimal timing
+                        let wait_time = (time_remaining - timing.sign_secs).max(0.1);
+                        info!("   ⏳ Waiting {:.1}s for optimal timing ({:.1}s target)...", 
+                            wait_time, timing.sign_secs);
+                        sleep(Duration::from_secs_f64(wait_time)).await;
                     
-                    // Now execute
-                    let result = match self.mode.as_str() {
-                        "executor" => self.execute_executor_deploy(&decision, current_round_id).await,
-                        "live" => self.execute_deploy(&decision, current_round_id).await,
-                        _ => {
-                            info!("   📋 SIMULATION MODE - no transaction sent");
-                            self.rounds_played += 1;
-                            self.total_deployed += decision.total_amount_lamports;
-                            Ok("simulation".to_string())
-                        }
-                    };
+                        // Now execute
+                        let result = match self.mode.as_str() {
+                            "executor" => self.execute_executor_with_retry(&decision, current_round_id, &board).await,
+                            "live" => self.execute_live_deploy(&decision, current_round_id, &board).await
+                                .map(|(sig, sent)| { decision = sent; sig }),
+                            _ => {
+                                info!("   📋 SIMULATION MODE - no transaction sent");
+                                self.record_round_played(&decision);
+                                Ok("simulation".to_string())
+                            }
+                        };
                     
-                    match result {
-                        Ok(sig) if sig != "simulation" => {
-                            info!("   🎉 Deploy successful! Signature: {}", sig);
-                            self.rounds_played += 1;
-                            self.total_deployed += decision.total_amount_lamports;
-                        }
-                        Err(e) => {
-                            error!("   ❌ Deploy failed: {}", e);
+                        match result {
+                            Ok(sig) if sig != "simulation" => {
+                                info!("   🎉 Deploy successful! Signature: {}", sig);
+                                self.record_round_played(&decision);
+                                self.on_deploy_sent(&decision, current_round_id, &sig);
+                            }
+                            Err(e) => self.on_deploy_failed(&e, current_round_id),
+                            _ => {}
                         }
-                        _ => {}
                     }
-                } else {
-                    // Too early - wait for decision window
-                    let wait_time = (time_remaining - decision_time).max(0.1);
-                    info!("   ⏳ Too early ({:.1}s remaining) - waiting {:.1}s for decision window...", 
-                        time_remaining, wait_time);
+                    RoundPhase::Early => {
+                        // Too early - wait for decision window
+                        let wait_time = (time_remaining - timing.decision_secs).max(0.1);
+                        info!("   ⏳ Too early ({:.1}s remaining) - waiting {:.1}s for decision window...", 
+                            time_remaining, wait_time);
+                    }
                 }
                 
             } else {
@@ -636,19 +998,49 @@ impl SmartMinerBot {
                     
                     // Update strategy with round result
                     if let Ok(completed_round) = self.parser.get_round(last_round_id) {
-                        self.ore_strategy.record_round(&completed_round.deployed, winning_square);
+                        // record_round expects 1-25
+                        self.ore_strategy.record_round(&completed_round.deployed, winning_square + 1);
                         
                         // Check if WE won (if we played)
-                        if self.rounds_played > 0 {
-                            let last_decision = self.ore_strategy.get_optimal_square_count();
-                            // This is a simplified check - ideally track our actual squares
-                            info!("   Checking our squares against winner...");
+                        // (a deploy made this iteration belongs to the new round - leave it)
+                        let finished_deploy = match self.last_deploy {
+                            Some(ref d) if d.round_id == last_round_id => self.last_deploy.take(),
+                            _ => None,
+                        };
+                        if let Some(deploy) = finished_deploy {
+                            let won = deploy.squares.contains(&(winning_square as usize));
+                            
+                            if won {
+                                self.rounds_won += 1;
+                                info!("{}", format!("   🏆 WE WON round {} on square {}!", last_round_id, winning_square + 1).green().bold());
+                                
+                                if let Some(ref webhook) = self.webhook {
+                                    webhook.notify(WebhookPayload::win(
+                                        &self.name,
+                                        last_round_id,
+                                        &deploy.squares,
+                                        deploy.amount_lamports,
+                                        &deploy.signature,
+                                        winning_square,
+                                    ));
+                                }
+                            } else {
+                                info!("   Our squares {:?} missed", deploy.squares.iter().map(|s| s + 1).collect::<Vec<_>>());
+                            }
+                            // Realized ORE is credited once the miner is checkpointed for the round
+                            self.awaiting_checkpoint.push((deploy, won));
                         }
                     }
                 }
             }
             last_round_id = current_round_id;
 
+            // Claims and miner reads never compete with the deploy window
+            if self.get_round_phase(&board).0 == RoundPhase::Early {
+                self.poll_checkpoint().await;
+                self.claim_rewards().await;
+            }
+
             // Display learning stats
             let summary = self.ore_strategy.get_learning_summary();
             let (optimal_count, _, reasoning) = self.ore_strategy.get_optimal_square_count();
@@ -657,7 +1049,12 @@ impl SmartMinerBot {
             info!("   Optimal squares: {} ({})", optimal_count, reasoning);
             info!("   My stats: {} rounds, {} won, {:.4} SOL deployed", 
                 self.rounds_played, self.rounds_won, 
-                self.total_deployed as f64 / LAMPORTS_PER_SOL as f64);
+                lamports_to_sol(self.total_deployed));
+            if self.total_claimed_sol > 0 || self.total_claimed_ore > 0 {
+                info!("   Claimed: {:.4} SOL, {:.4} ORE",
+                    lamports_to_sol(self.total_claimed_sol),
+                    raw_ore_to_ore(self.total_claimed_ore));
+            }
             
             info!("\n⏳ Next check in {} seconds...\n", update_interval);
             sleep(Duration::from_secs(update_interval)).await;
@@ -788,11 +1185,69 @@ async fn main() {
         }
     };
 
+    bot.max_inflight_deploys = config.mining.max_inflight_executor_deploys.max(1);
+    #[cfg(feature = "database")]
+    bot.heartbeats = SignalBudget::new(0, config.analytics.heartbeat_interval_secs);
+    bot.ore_strategy.reserve_for_claims_lamports = config.mining.reserve_for_claims_lamports;
+    bot.claim_threshold_sol = config.mining.auto_claim_threshold_sol;
+    bot.ore_strategy.consensus_blend_weight = config.mining.consensus_blend_weight.clamp(0.0, 1.0);
+    if bot.ore_strategy.consensus_blend_weight < 1.0 {
+        info!("🧮 Blending coordinator squares at {:.0}% with our own picks", bot.ore_strategy.consensus_blend_weight * 100.0);
+    }
+    bot.ore_strategy.consensus_ev_margin = config.mining.consensus_ev_margin;
+    if let Some(margin) = bot.ore_strategy.consensus_ev_margin {
+        info!("⚖️  Following coordinator squares only if EV beats a low-competition spread by {:.0}% of the bet", margin * 100.0);
+    }
+    bot.claim_threshold_ore = config.mining.auto_claim_threshold_ore;
+    bot.ore_strategy.recent_form_rounds = config.mining.strategy_form_rounds;
+    bot.ore_strategy.min_recent_hit_rate = config.mining.strategy_min_hit_rate;
+    if config.mining.max_rounds_per_hour > 0 {
+        info!("⏱️  Playing at most {} rounds per hour", config.mining.max_rounds_per_hour);
+        bot.round_throttle = Some(RoundThrottle::new(config.mining.max_rounds_per_hour));
+    }
+    bot.confirm_policy = match ConfirmPolicy::new(&config.mining.deploy_commitment, config.mining.deploy_wait_for_confirmation) {
+        Ok(policy) => policy,
+        Err(e) => {
+            error!("❌ {}", e);
+            return;
+        }
+    };
+    if bot.mode == "live" {
+        if bot.confirm_policy.wait {
+            info!("✅ Live deploys wait for {:?} commitment", bot.confirm_policy.commitment.commitment);
+        } else {
+            info!("⏩ Live deploys don't wait for confirmation");
+        }
+    }
+    if config.mining.balance_drop_tolerance_sol > 0.0 {
+        info!("🛡️  Pausing if the wallet drops more than {:.4} SOL beyond our deploys", config.mining.balance_drop_tolerance_sol);
+        bot.balance_guard = Some(BalanceGuard::new(sol_to_lamports(config.mining.balance_drop_tolerance_sol)));
+    }
+    if config.mining.bet_jitter_pct > 0.0 {
+        info!("🎲 Bet size randomized by ±{:.0}%", config.mining.bet_jitter_pct);
+        bot.ore_strategy.bet_jitter = Some(BetJitter::new(
+            config.mining.bet_jitter_pct / 100.0,
+            config.mining.bet_jitter_seed,
+        ));
+    }
+    bot.webhook = WebhookNotifier::from_url(config.mining.webhook_url.as_deref());
+    if bot.webhook.is_some() {
+        info!("🔔 Deploy/win webhook enabled");
+    }
+
     // Load learned data from database
     #[cfg(feature = "database")]
     if is_database_available() {
-        if let Ok(db) = SharedDb::connect().await {
-            bot.load_learned_data(&db).await;
+        match SharedDb::connect().await {
+            Ok(db) => {
+                db.publish_config(BOT_NAME, &config).await.ok();
+                bot.load_learned_data(&db).await;
+                bot.db = Some(db);
+            }
+            Err(e) if bot.mode == "executor" => {
+                warn!("⚠️ Database unreachable ({}) - executor deploys run without a lease", e);
+            }
+            Err(_) => {}
         }
     }
 
@@ -2,6 +2,7 @@ use clawdbot::{
     blockchain_parser::BlockchainParser,
     config::BotConfig,
     db::is_database_available,
+    units::{lamports_to_sol, raw_ore_to_ore},
 };
 use colored::*;
 use log::{error, info, warn};
@@ -92,6 +93,11 @@ async fn main() {
         }
     };
 
+    #[cfg(feature = "database")]
+    if let Some(ref db) = db {
+        db.publish_config(BOT_NAME, &config).await.ok();
+    }
+
     let wallet_info = match load_keypair(&config.keypair_path) {
         Ok(kp) => {
             info!("🔑 Wallet: {}", kp.pubkey());
@@ -204,7 +210,7 @@ async fn main() {
                         
                         info!("📊 Round {} | Deployed: {:.4} SOL | Active: {} squares | ~{}s remaining", 
                             current_round,
-                            total_deployed as f64 / 1_000_000_000.0,
+                            lamports_to_sol(total_deployed),
                             active_squares,
                             time_remaining_secs);
 
@@ -220,6 +226,7 @@ async fn main() {
                                 "round_duration_secs": round_duration_secs,
                                 "time_remaining_secs": time_remaining_secs,
                                 "slots_remaining": slots_remaining,
+                                "progress_pct": clawdbot::progress::round_progress_pct(round_duration_secs, time_remaining_secs),
                                 "deployed_squares": round.deployed.iter().map(|&d| d).collect::<Vec<_>>(),
                                 "updated_at": chrono::Utc::now().to_rfc3339(),
                             })).await.ok();
@@ -235,8 +242,8 @@ async fn main() {
         match parser.get_treasury() {
             Ok(treasury) => {
                 info!("🏦 Treasury: {:.4} SOL | Staked: {:.4} ORE",
-                    treasury.balance as f64 / 1_000_000_000.0,
-                    treasury.total_staked as f64 / 1e11);
+                    lamports_to_sol(treasury.balance),
+                    raw_ore_to_ore(treasury.total_staked));
             }
             Err(e) => warn!("Could not fetch treasury: {}", e),
         }
@@ -244,8 +251,8 @@ async fn main() {
         // Check wallet rewards if available
         if let Some(ref wallet) = wallet_info {
             if let Ok(Some(miner)) = parser.get_miner(wallet.pubkey()) {
-                let sol = miner.rewards_sol as f64 / 1_000_000_000.0;
-                let ore = miner.rewards_ore as f64 / 1e11;
+                let sol = lamports_to_sol(miner.rewards_sol);
+                let ore = raw_ore_to_ore(miner.rewards_ore);
                 if sol > 0.001 || ore > 0.01 {
                     info!("💰 Claimable: {:.4} SOL | {:.4} ORE", sol, ore);
                 }
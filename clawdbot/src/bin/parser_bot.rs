@@ -2,6 +2,7 @@ use clawdbot::{
     blockchain_parser::{BlockchainParser, OreInstructionType},
     config::BotConfig,
     db::is_database_available,
+    units::lamports_to_sol,
 };
 use colored::*;
 use log::{error, info, warn};
@@ -43,7 +44,7 @@ fn print_board_visual(deployed: &[u64; 25]) {
         print!("║  ");
         for col in 0..5 {
             let idx = row * 5 + col;
-            let amount = deployed[idx] as f64 / 1_000_000_000.0;
+            let amount = lamports_to_sol(deployed[idx]);
             
             let cell = if amount > 0.1 {
                 format!("#{:02}:{:>6.2} ", idx, amount).green()
@@ -141,6 +142,11 @@ async fn main() {
         }
     };
 
+    #[cfg(feature = "database")]
+    if let Some(ref db) = db {
+        db.publish_config(BOT_NAME, &config).await.ok();
+    }
+
     let wallet_info = match load_keypair(&config.keypair_path) {
         Ok(kp) => {
             info!("🔑 Wallet: {}", kp.pubkey());
@@ -215,7 +221,7 @@ async fn main() {
                 match parser.get_round(board.round_id) {
                     Ok(round) => {
                         let total_deployed: u64 = round.deployed.iter().sum();
-                        info!("💰 Total Deployed: {:.4} SOL", total_deployed as f64 / 1_000_000_000.0);
+                        info!("💰 Total Deployed: {:.4} SOL", lamports_to_sol(total_deployed));
                         print_board_visual(&round.deployed);
                     }
                     Err(e) => warn!("Could not fetch round: {}", e),
@@ -18,6 +18,7 @@
 //!   RELOAD - "true" to auto-reload winnings (default: true)
 //!   FEE_SOL - Executor fee per deploy (default: 0.0001)
 
+use clawdbot::units::{lamports_to_sol, sol_to_lamports};
 use colored::*;
 use log::{error, info, warn};
 use solana_client::rpc_client::RpcClient;
@@ -29,7 +30,6 @@ use solana_sdk::{
 };
 use std::str::FromStr;
 
-const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
 
 fn load_keypair(keypair_path: &str) -> Result<Keypair, String> {
     if let Ok(keypair_b58) = std::env::var("KEYPAIR_B58") {
@@ -157,9 +157,9 @@ async fn main() {
     };
 
     // Convert to lamports
-    let deposit = (deposit_sol * LAMPORTS_PER_SOL as f64) as u64;
-    let amount = (amount_sol * LAMPORTS_PER_SOL as f64) as u64;
-    let fee = (fee_sol * LAMPORTS_PER_SOL as f64) as u64;
+    let deposit = sol_to_lamports(deposit_sol);
+    let amount = sol_to_lamports(amount_sol);
+    let fee = sol_to_lamports(fee_sol);
 
     info!("═══════════════════════════════════════════════════════════════");
     info!("📋 Automation Setup:");
@@ -194,7 +194,7 @@ async fn main() {
         }
     };
 
-    let balance_sol = balance as f64 / LAMPORTS_PER_SOL as f64;
+    let balance_sol = lamports_to_sol(balance);
     info!("💳 Current balance: {:.4} SOL", balance_sol);
 
     if balance < deposit + 10_000_000 { // Need extra for tx fees
@@ -1,4 +1,6 @@
+use crate::config::WinnerSource;
 use crate::error::{BotError, Result};
+use crate::units::lamports_to_sol;
 use base64::Engine;
 use log::{debug, info, warn};
 use ore_api::state::{Board, Miner, Round, Treasury};
@@ -9,7 +11,7 @@ use solana_sdk::{
     pubkey::Pubkey,
     signature::Signature,
 };
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
 use std::sync::Arc;
 
@@ -203,12 +205,116 @@ pub struct ParsedOreTransaction {
     pub reset_data: Option<ResetData>,
 }
 
+impl ParsedOreTransaction {
+    /// Player credited with this transaction
+    /// Deploy accounts are `[signer, authority, automation, ...]`; in executor mode the
+    /// signer is the automation executor and the authority is the miner's owner
+    pub fn authority(&self) -> &str {
+        if self.instruction_type == OreInstructionType::Deploy {
+            if let Some(authority) = self.accounts.get(1) {
+                return authority;
+            }
+        }
+        &self.signer
+    }
+
+    /// Deploy signed by an executor on behalf of another authority
+    pub fn is_executor_deploy(&self) -> bool {
+        self.authority() != self.signer
+    }
+}
+
+/// Distinct players (authorities) with a successful Deploy in `txs` at or after `since_slot`
+/// Pass the round's start slot so deploys into the previous round aren't counted
+pub fn unique_deployers(txs: &[ParsedOreTransaction], since_slot: u64) -> HashSet<String> {
+    txs.iter()
+        .filter(|tx| tx.success && tx.instruction_type == OreInstructionType::Deploy && tx.slot >= since_slot)
+        .map(|tx| tx.authority().to_string())
+        .collect()
+}
+
+/// Where a round's winning square was actually read from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WinnerOrigin {
+    /// ResetEvent in the Reset transaction's return data
+    #[default]
+    ResetEvent,
+    /// The Round account's RNG (`get_round_result`)
+    RoundAccount,
+    /// "winning square" text in the Reset transaction's logs
+    LogFallback,
+}
+
+impl WinnerOrigin {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WinnerOrigin::ResetEvent => "reset_event",
+            WinnerOrigin::RoundAccount => "round_account",
+            WinnerOrigin::LogFallback => "log_fallback",
+        }
+    }
+}
+
+impl std::fmt::Display for WinnerOrigin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Winning square picked from the Round account and Reset event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WinnerResolution {
+    /// 0-24, None if the configured source hasn't reported yet
+    pub winning_square: Option<u8>,
+    /// Which source `winning_square` came from
+    pub origin: Option<WinnerOrigin>,
+    /// (round account, reset event) when both reported different squares
+    pub disagreement: Option<(u8, u8)>,
+}
+
+/// Reconcile the two winning-square sources (both 0-24) per `source`
+/// `reset_event` carries how the Reset transaction was parsed (event or log fallback)
+/// Disagreements are logged so bad parses can be tracked down
+pub fn resolve_winning_square(
+    source: WinnerSource,
+    round_id: u64,
+    round_account: Option<u8>,
+    reset_event: Option<(u8, WinnerOrigin)>,
+) -> WinnerResolution {
+    let disagreement = match (round_account, reset_event) {
+        (Some(a), Some((r, _))) if a != r => Some((a, r)),
+        _ => None,
+    };
+
+    let from_account = round_account.map(|sq| (sq, WinnerOrigin::RoundAccount));
+    let picked = match source {
+        WinnerSource::ResetEvent => reset_event,
+        WinnerSource::RoundAccount => from_account,
+        WinnerSource::PreferResetEvent => reset_event.or(from_account),
+    };
+    let winning_square = picked.map(|(sq, _)| sq);
+    let origin = picked.map(|(_, origin)| origin);
+
+    if let Some((a, r)) = disagreement {
+        warn!(
+            "⚠️ Round {} winner mismatch: round account says square {}, reset event says {} (using {:?} -> {:?})",
+            round_id, a + 1, r + 1, source, winning_square.map(|s| s + 1)
+        );
+    }
+
+    WinnerResolution { winning_square, origin, disagreement }
+}
+
 /// Parsed Reset instruction data (round completion)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResetData {
     pub round_id: u64,
     pub winning_square: u8,
     pub motherlode: bool,
+    /// Event data or the log-text fallback
+    #[serde(default)]
+    pub origin: WinnerOrigin,
 }
 
 /// Deploy event from program logs
@@ -250,6 +356,16 @@ pub struct TrackedMiner {
     pub automation_enabled: bool,
 }
 
+/// Automation executor activity (deploys signed on behalf of other authorities)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TrackedExecutor {
+    pub address: String,
+    pub total_deployed: u64,
+    pub deploy_count: u64,
+    pub last_seen: i64,
+    pub authorities: Vec<String>,
+}
+
 /// Round stats tracked by the parser
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrackedRound {
@@ -285,6 +401,7 @@ pub struct BlockchainParser {
     rpc_client: Arc<RpcClient>,
     ore_program_id: Pubkey,
     tracked_miners: HashMap<String, TrackedMiner>,
+    tracked_executors: HashMap<String, TrackedExecutor>,
     tracked_rounds: HashMap<u64, TrackedRound>,
     recent_transactions: Vec<ParsedOreTransaction>,
     instruction_counts: HashMap<OreInstructionType, u64>,
@@ -307,6 +424,7 @@ impl BlockchainParser {
             rpc_client,
             ore_program_id,
             tracked_miners: HashMap::new(),
+            tracked_executors: HashMap::new(),
             tracked_rounds: HashMap::new(),
             recent_transactions: Vec::new(),
             instruction_counts: HashMap::new(),
@@ -352,7 +470,7 @@ impl BlockchainParser {
 
         Some(DeployData {
             amount_lamports,
-            amount_sol: amount_lamports as f64 / 1_000_000_000.0,
+            amount_sol: lamports_to_sol(amount_lamports),
             squares_mask,
             squares: squares.clone(),
             num_squares: squares.len(),
@@ -447,6 +565,38 @@ impl BlockchainParser {
         Ok(parsed)
     }
 
+    /// Fetch ORE program transactions from a single wallet's history
+    /// Unlike `fetch_recent_transactions` this does not update tracked stats
+    pub fn fetch_wallet_transactions(&self, wallet: &Pubkey, limit: usize) -> Result<Vec<ParsedOreTransaction>> {
+        let signatures = self.rpc_client
+            .get_signatures_for_address(wallet)
+            .map_err(|e| BotError::RpcTimeout(format!("Failed to get signatures: {}", e)))?;
+
+        let mut parsed = Vec::new();
+
+        for sig_info in signatures.iter().take(limit) {
+            let signature = Signature::from_str(&sig_info.signature)
+                .map_err(|e| BotError::Other(format!("Invalid signature: {}", e)))?;
+
+            match self.rpc_client.get_transaction(
+                &signature,
+                solana_transaction_status::UiTransactionEncoding::Base64,
+            ) {
+                Ok(tx) => {
+                    // Non-ORE transactions parse to None and are skipped
+                    if let Some(parsed_tx) = self.parse_transaction(&sig_info.signature, &tx, sig_info.slot, sig_info.block_time) {
+                        parsed.push(parsed_tx);
+                    }
+                }
+                Err(e) => {
+                    debug!("Failed to fetch tx {}: {}", sig_info.signature, e);
+                }
+            }
+        }
+
+        Ok(parsed)
+    }
+
     /// Parse a single transaction
     fn parse_transaction(
         &self,
@@ -550,6 +700,7 @@ impl BlockchainParser {
             round_id,
             winning_square,
             motherlode,
+            origin: WinnerOrigin::ResetEvent,
         })
     }
 
@@ -587,6 +738,7 @@ impl BlockchainParser {
                             round_id,
                             winning_square,
                             motherlode,
+                            origin: WinnerOrigin::ResetEvent,
                         });
                     }
                 }
@@ -616,6 +768,7 @@ impl BlockchainParser {
                                         round_id,
                                         winning_square: num,
                                         motherlode,
+                                        origin: WinnerOrigin::LogFallback,
                                     });
                                 }
                             }
@@ -639,11 +792,12 @@ impl BlockchainParser {
         // Update instruction counts
         *self.instruction_counts.entry(tx.instruction_type).or_insert(0) += 1;
 
-        // Update miner tracking
+        // Update miner tracking (credited to the authority, not an executor signer)
+        let authority = tx.authority();
         let miner = self.tracked_miners
-            .entry(tx.signer.clone())
+            .entry(authority.to_string())
             .or_insert(TrackedMiner {
-                address: tx.signer.clone(),
+                address: authority.to_string(),
                 ..Default::default()
             });
 
@@ -659,6 +813,21 @@ impl BlockchainParser {
                     for &square in &data.squares {
                         *miner.squares_deployed.entry(square).or_insert(0) += data.amount_lamports;
                     }
+
+                    if tx.is_executor_deploy() {
+                        let executor = self.tracked_executors
+                            .entry(tx.signer.clone())
+                            .or_insert(TrackedExecutor {
+                                address: tx.signer.clone(),
+                                ..Default::default()
+                            });
+                        executor.total_deployed += data.amount_lamports;
+                        executor.deploy_count += 1;
+                        executor.last_seen = tx.block_time.unwrap_or(0);
+                        if !executor.authorities.iter().any(|a| a == authority) {
+                            executor.authorities.push(authority.to_string());
+                        }
+                    }
                 }
             }
             OreInstructionType::ClaimSOL => {
@@ -797,16 +966,37 @@ impl BlockchainParser {
             total_transactions: self.recent_transactions.len(),
             total_miners_tracked: self.tracked_miners.len(),
             total_rounds_tracked: self.tracked_rounds.len(),
-            total_sol_deployed: self.total_sol_deployed as f64 / 1_000_000_000.0,
+            total_sol_deployed: lamports_to_sol(self.total_sol_deployed),
             instruction_counts: self.instruction_counts.clone(),
         }
     }
 
+    /// Per-instruction-type counts over the transactions processed so far,
+    /// most frequent first
+    pub fn instruction_stats(&self) -> Vec<InstructionCount> {
+        let mut counts: Vec<InstructionCount> = self.instruction_counts
+            .iter()
+            .map(|(instruction, &count)| InstructionCount {
+                instruction: *instruction,
+                name: instruction.name().to_string(),
+                emoji: instruction.emoji().to_string(),
+                count,
+            })
+            .collect();
+        counts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+        counts
+    }
+
     /// Get tracked miners
     pub fn get_tracked_miners(&self) -> &HashMap<String, TrackedMiner> {
         &self.tracked_miners
     }
 
+    /// Get tracked automation executors
+    pub fn get_tracked_executors(&self) -> &HashMap<String, TrackedExecutor> {
+        &self.tracked_executors
+    }
+
     /// Get recent transactions
     pub fn get_recent_transactions(&self) -> &[ParsedOreTransaction] {
         &self.recent_transactions
@@ -890,8 +1080,183 @@ pub struct ParserStats {
     pub instruction_counts: HashMap<OreInstructionType, u64>,
 }
 
+/// Number of processed transactions for one instruction type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstructionCount {
+    pub instruction: OreInstructionType,
+    pub name: String,
+    pub emoji: String,
+    pub count: u64,
+}
+
 impl Default for BlockchainParser {
     fn default() -> Self {
         Self::new("https://api.mainnet-beta.solana.com").expect("Failed to create default parser")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_tx(instruction_type: OreInstructionType, signer: &str, success: bool) -> ParsedOreTransaction {
+        ParsedOreTransaction {
+            signature: format!("sig-{}-{:?}", signer, instruction_type),
+            slot: 1000,
+            block_time: None,
+            instruction_type,
+            signer: signer.to_string(),
+            accounts: vec![],
+            success,
+            deploy_data: None,
+            automate_data: None,
+            deposit_data: None,
+            withdraw_data: None,
+            claim_yield_data: None,
+            reset_data: None,
+        }
+    }
+
+    #[test]
+    fn test_instruction_stats_mixed_batch() {
+        let mut parser = BlockchainParser::new("http://localhost:8899").unwrap();
+
+        let batch = vec![
+            test_tx(OreInstructionType::Deploy, "MinerAAAA", true),
+            test_tx(OreInstructionType::Deploy, "MinerBBBB", true),
+            test_tx(OreInstructionType::Deploy, "MinerCCCC", true),
+            test_tx(OreInstructionType::ClaimSOL, "MinerAAAA", true),
+            test_tx(OreInstructionType::Automate, "MinerBBBB", true),
+            // Failed transactions are not counted
+            test_tx(OreInstructionType::ClaimORE, "MinerCCCC", false),
+        ];
+        for tx in &batch {
+            parser.process_parsed_transaction(tx);
+        }
+
+        let stats = parser.instruction_stats();
+        let count_of = |kind: OreInstructionType| {
+            stats.iter().find(|c| c.instruction == kind).map(|c| c.count)
+        };
+
+        assert_eq!(stats.len(), 3);
+        assert_eq!(stats[0].name, "Deploy");
+        assert_eq!(stats[0].emoji, "🚀");
+        assert_eq!(count_of(OreInstructionType::Deploy), Some(3));
+        assert_eq!(count_of(OreInstructionType::ClaimSOL), Some(1));
+        assert_eq!(count_of(OreInstructionType::Automate), Some(1));
+        assert_eq!(count_of(OreInstructionType::ClaimORE), None);
+    }
+
+    #[test]
+    fn test_unique_deployers_ignores_duplicates() {
+        let batch = vec![
+            test_tx(OreInstructionType::Deploy, "MinerAAAA", true),
+            test_tx(OreInstructionType::Deploy, "MinerAAAA", true),
+            test_tx(OreInstructionType::Deploy, "MinerBBBB", true),
+            test_tx(OreInstructionType::Deploy, "MinerBBBB", true),
+            test_tx(OreInstructionType::Deploy, "MinerCCCC", true),
+            // Not deploys, or failed - not counted
+            test_tx(OreInstructionType::ClaimSOL, "MinerDDDD", true),
+            test_tx(OreInstructionType::Deploy, "MinerEEEE", false),
+        ];
+
+        let deployers = unique_deployers(&batch, 0);
+        assert_eq!(deployers.len(), 3);
+        assert!(deployers.contains("MinerAAAA"));
+        assert!(!deployers.contains("MinerDDDD"));
+        assert!(!deployers.contains("MinerEEEE"));
+
+        // Deploys from before the round started belong to the previous round
+        let mut previous = test_tx(OreInstructionType::Deploy, "MinerFFFF", true);
+        previous.slot = 99;
+        let mut current = test_tx(OreInstructionType::Deploy, "MinerGGGG", true);
+        current.slot = 100;
+        let deployers = unique_deployers(&[previous, current], 100);
+        assert_eq!(deployers.len(), 1);
+        assert!(deployers.contains("MinerGGGG"));
+    }
+
+    #[test]
+    fn test_executor_deploy_credited_to_authority() {
+        let mut parser = BlockchainParser::new("http://localhost:8899").unwrap();
+
+        let mut tx = test_tx(OreInstructionType::Deploy, "Executor1111", true);
+        tx.accounts = vec!["Executor1111".to_string(), "Player2222".to_string(), "Automation3333".to_string()];
+        tx.deploy_data = Some(DeployData {
+            amount_lamports: 10_000_000,
+            amount_sol: 0.01,
+            squares_mask: 0b101,
+            squares: vec![0, 2],
+            num_squares: 2,
+        });
+        assert_eq!(tx.authority(), "Player2222");
+        assert!(tx.is_executor_deploy());
+
+        parser.process_parsed_transaction(&tx);
+
+        let miners = parser.get_tracked_miners();
+        let player = miners.get("Player2222").expect("authority should be tracked");
+        assert_eq!(player.total_deployed, 10_000_000);
+        assert_eq!(player.deploy_count, 1);
+        assert!(!miners.contains_key("Executor1111"));
+
+        let executor = parser.get_tracked_executors().get("Executor1111").expect("executor should be tracked");
+        assert_eq!(executor.deploy_count, 1);
+        assert_eq!(executor.total_deployed, 10_000_000);
+        assert_eq!(executor.authorities, vec!["Player2222".to_string()]);
+    }
+
+    #[test]
+    fn test_winner_source_preference_on_conflict() {
+        // Round account says square 4 (0-24), the reset event says 7
+        let account = Some(4);
+        let reset = Some((7, WinnerOrigin::ResetEvent));
+
+        let r = resolve_winning_square(WinnerSource::RoundAccount, 100, account, reset);
+        assert_eq!(r.winning_square, Some(4));
+        assert_eq!(r.disagreement, Some((4, 7)));
+
+        let r = resolve_winning_square(WinnerSource::ResetEvent, 100, account, reset);
+        assert_eq!(r.winning_square, Some(7));
+        assert_eq!(r.disagreement, Some((4, 7)));
+
+        let r = resolve_winning_square(WinnerSource::PreferResetEvent, 100, account, reset);
+        assert_eq!(r.winning_square, Some(7));
+
+        // Falls back when the reset hasn't been seen; strict sources wait
+        let r = resolve_winning_square(WinnerSource::PreferResetEvent, 100, account, None);
+        assert_eq!(r.winning_square, Some(4));
+        assert_eq!(r.disagreement, None);
+        assert_eq!(resolve_winning_square(WinnerSource::ResetEvent, 100, account, None).winning_square, None);
+
+        assert_eq!("round_account".parse::<WinnerSource>().unwrap(), WinnerSource::RoundAccount);
+        assert_eq!("PreferResetEvent".parse::<WinnerSource>().unwrap(), WinnerSource::PreferResetEvent);
+    }
+
+    #[test]
+    fn test_winner_origin_matches_path_taken() {
+        let from_logs = Some((7, WinnerOrigin::LogFallback));
+
+        // Reset parsed from the log text: recorded as the log fallback, not the event
+        let r = resolve_winning_square(WinnerSource::PreferResetEvent, 100, Some(7), from_logs);
+        assert_eq!(r.origin, Some(WinnerOrigin::LogFallback));
+        assert_eq!(r.disagreement, None);
+
+        let r = resolve_winning_square(WinnerSource::PreferResetEvent, 100, Some(7), Some((7, WinnerOrigin::ResetEvent)));
+        assert_eq!(r.origin, Some(WinnerOrigin::ResetEvent));
+
+        // No reset seen yet: the account's RNG decided
+        let r = resolve_winning_square(WinnerSource::PreferResetEvent, 100, Some(4), None);
+        assert_eq!(r.origin, Some(WinnerOrigin::RoundAccount));
+
+        // A strict account source ignores the reset, even when they disagree
+        let r = resolve_winning_square(WinnerSource::RoundAccount, 100, Some(4), from_logs);
+        assert_eq!((r.winning_square, r.origin), (Some(4), Some(WinnerOrigin::RoundAccount)));
+        assert_eq!(r.disagreement, Some((4, 7)));
+
+        // Nothing decided, nothing recorded
+        assert_eq!(resolve_winning_square(WinnerSource::ResetEvent, 100, Some(4), None).origin, None);
+        assert_eq!(WinnerOrigin::LogFallback.as_str(), "log_fallback");
+    }
+}
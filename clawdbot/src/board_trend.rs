@@ -0,0 +1,275 @@
+//! Per-square competition trend within a round
+//!
+//! The coordinator records a board snapshot every cycle. From the snapshots of
+//! the current round we fit each square's fill rate (lamports per slot) and
+//! compare it to the board average, so squares that are filling up quickly
+//! can be avoided in favour of flat ones.
+//!
+//! `FinalWindowDeploys` watches deploy timing instead: the share of a round's
+//! deploys landing in its final second. Rounds where most SOL arrives at the
+//! last moment are dominated by faster bots and play differently.
+
+use crate::round_phase::SLOTS_PER_SECOND;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+
+/// Snapshots kept per round; older ones are dropped first
+const MAX_SNAPSHOTS_PER_ROUND: usize = 64;
+
+/// Rate vs board average above which a square counts as rising
+const RISING_RATIO: f64 = 1.5;
+/// Rate vs board average below which a square counts as flat
+const FLAT_RATIO: f64 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoardSnapshot {
+    pub slot: u64,
+    pub deployed: [u64; 25],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SquareTrend {
+    /// Filling much faster than the board average
+    Rising,
+    /// Filling at about the board average
+    Steady,
+    /// Little or no new SOL
+    Flat,
+}
+
+impl SquareTrend {
+    fn from_rate(rate: f64, board_mean: f64) -> Self {
+        if rate <= 0.0 || rate < board_mean * FLAT_RATIO {
+            SquareTrend::Flat
+        } else if rate >= board_mean * RISING_RATIO {
+            SquareTrend::Rising
+        } else {
+            SquareTrend::Steady
+        }
+    }
+}
+
+/// Board snapshots for the current round
+#[derive(Debug, Clone, Default)]
+pub struct BoardSnapshots {
+    round_id: u64,
+    snapshots: VecDeque<BoardSnapshot>,
+}
+
+impl BoardSnapshots {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a snapshot; a new round id starts over
+    pub fn record(&mut self, round_id: u64, slot: u64, deployed: [u64; 25]) {
+        if round_id != self.round_id {
+            self.round_id = round_id;
+            self.snapshots.clear();
+        }
+        if self.snapshots.back().map_or(false, |s| s.slot >= slot) {
+            return; // same or older slot, nothing new
+        }
+        if self.snapshots.len() >= MAX_SNAPSHOTS_PER_ROUND {
+            self.snapshots.pop_front();
+        }
+        self.snapshots.push_back(BoardSnapshot { slot, deployed });
+    }
+
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+
+    /// Least-squares fill rate per square in lamports per slot (0 with < 2 snapshots)
+    pub fn fill_rates(&self) -> [f64; 25] {
+        let mut rates = [0.0; 25];
+        let Some(first) = self.snapshots.front() else {
+            return rates;
+        };
+
+        let n = self.snapshots.len() as f64;
+        let xs: Vec<f64> = self.snapshots.iter().map(|s| (s.slot - first.slot) as f64).collect();
+        let mean_x = xs.iter().sum::<f64>() / n;
+        let var_x: f64 = xs.iter().map(|x| (x - mean_x).powi(2)).sum();
+        if var_x == 0.0 {
+            return rates;
+        }
+
+        for (square, rate) in rates.iter_mut().enumerate() {
+            let mean_y = self.snapshots.iter().map(|s| s.deployed[square] as f64).sum::<f64>() / n;
+            let cov: f64 = self.snapshots.iter()
+                .zip(&xs)
+                .map(|(s, x)| (x - mean_x) * (s.deployed[square] as f64 - mean_y))
+                .sum();
+            *rate = cov / var_x;
+        }
+        rates
+    }
+
+    /// Trend of each square relative to the board's average fill rate
+    pub fn trends(&self) -> [SquareTrend; 25] {
+        let rates = self.fill_rates();
+        let mean = rates.iter().sum::<f64>() / 25.0;
+        rates.map(|rate| SquareTrend::from_rate(rate, mean))
+    }
+}
+
+/// Fraction of `deploy_slots` within `window_slots` of `end_slot` (0 with no deploys)
+pub fn final_window_fraction(deploy_slots: &[u64], end_slot: u64, window_slots: u64) -> f64 {
+    if deploy_slots.is_empty() {
+        return 0.0;
+    }
+    let window_start = end_slot.saturating_sub(window_slots);
+    let late = deploy_slots.iter().filter(|&&slot| slot > window_start && slot <= end_slot).count();
+    late as f64 / deploy_slots.len() as f64
+}
+
+/// Last-second share of one round's deploys
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BotHeaviness {
+    pub round_id: u64,
+    pub deploys: usize,
+    pub final_second_fraction: f64,
+    /// `final_second_fraction` reached the configured threshold
+    pub bot_heavy: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+struct RoundDeploySlots {
+    round_id: u64,
+    start_slot: u64,
+    end_slot: u64,
+    slots: Vec<u64>,
+}
+
+/// Deploy slots of the current and previous round, to measure how much of
+/// each round was deployed in its final second
+#[derive(Debug, Clone)]
+pub struct FinalWindowDeploys {
+    window_slots: u64,
+    heavy_fraction: f64,
+    current: RoundDeploySlots,
+    previous: Option<RoundDeploySlots>,
+    /// Signatures counted since the last flip and in the round before;
+    /// fetched transaction pages overlap
+    seen: HashSet<String>,
+    seen_before: HashSet<String>,
+}
+
+impl FinalWindowDeploys {
+    /// `window_secs` is the "final second"; rounds at or above `heavy_fraction` are flagged
+    pub fn new(window_secs: f64, heavy_fraction: f64) -> Self {
+        Self {
+            window_slots: (window_secs * SLOTS_PER_SECOND).ceil().max(1.0) as u64,
+            heavy_fraction,
+            current: RoundDeploySlots::default(),
+            previous: None,
+            seen: HashSet::new(),
+            seen_before: HashSet::new(),
+        }
+    }
+
+    /// Board seen this cycle; a new round id moves the current round to previous
+    pub fn observe_round(&mut self, round_id: u64, start_slot: u64, end_slot: u64) {
+        if round_id != self.current.round_id {
+            let finished = std::mem::take(&mut self.current);
+            self.previous = (finished.round_id != 0).then_some(finished);
+            self.seen_before = std::mem::take(&mut self.seen);
+        }
+        self.current.round_id = round_id;
+        self.current.start_slot = start_slot;
+        self.current.end_slot = end_slot;
+    }
+
+    /// A deploy landed at `slot`; late transactions of the previous round still count toward it
+    pub fn record(&mut self, signature: &str, slot: u64) {
+        if self.seen_before.contains(signature) || !self.seen.insert(signature.to_string()) {
+            return;
+        }
+        if slot >= self.current.start_slot {
+            self.current.slots.push(slot);
+        } else if let Some(ref mut previous) = self.previous {
+            if slot >= previous.start_slot && slot <= previous.end_slot {
+                previous.slots.push(slot);
+            }
+        }
+    }
+
+    /// Heaviness of the last completed round
+    pub fn last_round(&self) -> Option<BotHeaviness> {
+        self.previous.as_ref().map(|round| {
+            let fraction = final_window_fraction(&round.slots, round.end_slot, self.window_slots);
+            BotHeaviness {
+                round_id: round.round_id,
+                deploys: round.slots.len(),
+                final_second_fraction: fraction,
+                bot_heavy: !round.slots.is_empty() && fraction >= self.heavy_fraction,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fast_filling_square_is_rising() {
+        let mut board = BoardSnapshots::new();
+        for step in 0..5u64 {
+            let mut deployed = [1_000_000u64; 25];
+            deployed[3] += step * 50_000_000; // piling in
+            deployed[7] = 2_000_000;          // untouched all round
+            for (i, d) in deployed.iter_mut().enumerate() {
+                if i != 3 && i != 7 {
+                    *d += step * 1_000_000;   // gentle background
+                }
+            }
+            board.record(42, 1_000 + step * 40, deployed);
+        }
+
+        let rates = board.fill_rates();
+        let trends = board.trends();
+        assert!((rates[3] - 1_250_000.0).abs() < 1.0, "rate {}", rates[3]);
+        assert_eq!(rates[7], 0.0);
+        assert_eq!(trends[3], SquareTrend::Rising);
+        assert_eq!(trends[7], SquareTrend::Flat);
+
+        // A new round starts from scratch
+        board.record(43, 1_200, [0; 25]);
+        assert_eq!(board.len(), 1);
+        assert_eq!(board.fill_rates(), [0.0; 25]);
+    }
+
+    #[test]
+    fn test_final_second_fraction_from_deploy_timings() {
+        // Round 10 ends at slot 1_150; the final second is slots 1_148..=1_150 at 2.7 slots/s
+        let mut timing = FinalWindowDeploys::new(1.0, 0.5);
+        timing.observe_round(10, 1_000, 1_150);
+        for (i, slot) in [1_010u64, 1_060, 1_120, 1_148, 1_149, 1_150, 1_150].iter().enumerate() {
+            timing.record(&format!("sig-{}", i), *slot);
+        }
+        timing.record("sig-6", 1_150); // seen again in the next page
+        assert!(timing.last_round().is_none());
+
+        // A late-indexed deploy of round 10 arrives after the flip
+        timing.observe_round(11, 1_160, 1_310);
+        timing.record("sig-7", 1_147);
+        timing.record("sig-5", 1_150);
+        timing.record("sig-8", 1_165);
+
+        let heaviness = timing.last_round().unwrap();
+        assert_eq!(heaviness.round_id, 10);
+        assert_eq!(heaviness.deploys, 8);
+        assert!((heaviness.final_second_fraction - 4.0 / 8.0).abs() < 1e-9);
+        assert!(heaviness.bot_heavy);
+
+        assert_eq!(final_window_fraction(&[], 1_150, 3), 0.0);
+        assert_eq!(final_window_fraction(&[1_000, 1_100], 1_150, 3), 0.0);
+    }
+}
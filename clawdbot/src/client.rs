@@ -1,18 +1,100 @@
 use crate::error::{BotError, Result};
 use ore_api::state::{Board, Miner, Round, Treasury, board_pda, miner_pda, round_pda, treasury_pda};
-use solana_client::rpc_client::RpcClient;
+use solana_client::{
+    client_error::{ClientErrorKind, Result as ClientResult},
+    rpc_client::RpcClient,
+};
 use solana_sdk::{
     commitment_config::CommitmentConfig,
     compute_budget::ComputeBudgetInstruction,
+    hash::Hash,
     pubkey::Pubkey,
     signature::{Keypair, Signature, Signer},
     transaction::Transaction,
 };
+use std::str::FromStr;
 use std::sync::Arc;
 use backoff::{ExponentialBackoff, future::retry};
 use std::time::Duration;
 use log::info;
 
+/// Time between signature status checks while confirming
+const CONFIRM_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// When a sent transaction counts as done
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConfirmPolicy {
+    pub commitment: CommitmentConfig,
+    /// false: return as soon as the RPC node accepts the transaction
+    pub wait: bool,
+}
+
+impl Default for ConfirmPolicy {
+    fn default() -> Self {
+        Self {
+            commitment: CommitmentConfig::confirmed(),
+            wait: true,
+        }
+    }
+}
+
+impl ConfirmPolicy {
+    /// `commitment` is "processed", "confirmed" or "finalized"
+    pub fn new(commitment: &str, wait: bool) -> Result<Self> {
+        let commitment = CommitmentConfig::from_str(commitment)
+            .map_err(|_| BotError::Config(format!("Unknown commitment level: {}", commitment)))?;
+        Ok(Self { commitment, wait })
+    }
+}
+
+/// The two RPC calls `send_with_policy` makes, so they can be faked in tests
+pub trait TransactionSender {
+    fn send(&self, transaction: &Transaction) -> ClientResult<Signature>;
+
+    fn confirm(&self, signature: &Signature, blockhash: &Hash, commitment: CommitmentConfig) -> ClientResult<()>;
+}
+
+impl TransactionSender for RpcClient {
+    fn send(&self, transaction: &Transaction) -> ClientResult<Signature> {
+        self.send_transaction(transaction)
+    }
+
+    /// Poll the signature status until it reaches `commitment`, fails, or its blockhash expires
+    fn confirm(&self, signature: &Signature, blockhash: &Hash, commitment: CommitmentConfig) -> ClientResult<()> {
+        loop {
+            match self.get_signature_status_with_commitment(signature, commitment)? {
+                Some(Ok(())) => return Ok(()),
+                Some(Err(e)) => return Err(e.into()),
+                None => {}
+            }
+            if !self.is_blockhash_valid(blockhash, CommitmentConfig::processed())? {
+                return Err(ClientErrorKind::Custom(format!(
+                    "Transaction {} expired: blockhash not found",
+                    signature
+                ))
+                .into());
+            }
+            std::thread::sleep(CONFIRM_POLL_INTERVAL);
+        }
+    }
+}
+
+/// Send `transaction`, then wait for the policy's commitment if it asks to
+/// The signature is logged as soon as the transaction is sent, so it is on
+/// record even when confirmation is skipped or times out
+pub fn send_with_policy<S: TransactionSender + ?Sized>(
+    sender: &S,
+    transaction: &Transaction,
+    policy: ConfirmPolicy,
+) -> ClientResult<Signature> {
+    let signature = sender.send(transaction)?;
+    info!("   📤 Sent {}", signature);
+    if policy.wait {
+        sender.confirm(&signature, &transaction.message.recent_blockhash, policy.commitment)?;
+    }
+    Ok(signature)
+}
+
 pub struct OreClient {
     pub rpc_client: Arc<RpcClient>,
     pub keypair: Arc<Keypair>,
@@ -310,3 +392,46 @@ impl OreClient {
         Ok(signature)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Records the commitment each confirmation was asked for
+    #[derive(Default)]
+    struct FakeSender {
+        confirmed_with: Mutex<Vec<CommitmentConfig>>,
+    }
+
+    impl TransactionSender for FakeSender {
+        fn send(&self, transaction: &Transaction) -> ClientResult<Signature> {
+            Ok(transaction.signatures[0])
+        }
+
+        fn confirm(&self, _signature: &Signature, _blockhash: &Hash, commitment: CommitmentConfig) -> ClientResult<()> {
+            self.confirmed_with.lock().unwrap().push(commitment);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_configured_commitment_passed_to_confirmation() {
+        let payer = Keypair::new();
+        let ix = solana_sdk::system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 1);
+        let tx = Transaction::new_signed_with_payer(&[ix], Some(&payer.pubkey()), &[&payer], Hash::new_unique());
+        let sender = FakeSender::default();
+
+        let policy = ConfirmPolicy::new("processed", true).unwrap();
+        assert_eq!(send_with_policy(&sender, &tx, policy).unwrap(), tx.signatures[0]);
+        assert_eq!(*sender.confirmed_with.lock().unwrap(), vec![CommitmentConfig::processed()]);
+
+        // Not waiting still returns the signature, without a confirmation call
+        let policy = ConfirmPolicy::new("finalized", false).unwrap();
+        assert_eq!(send_with_policy(&sender, &tx, policy).unwrap(), tx.signatures[0]);
+        assert_eq!(sender.confirmed_with.lock().unwrap().len(), 1);
+
+        assert_eq!(ConfirmPolicy::default().commitment, CommitmentConfig::confirmed());
+        assert!(ConfirmPolicy::new("instant", true).is_err());
+    }
+}
@@ -1,4 +1,13 @@
 use crate::error::{BotError, Result};
+use crate::config::AnalyticsConfig;
+#[cfg(feature = "database")]
+use crate::config::BotConfig;
+#[cfg(feature = "database")]
+use crate::learning_engine::{strategies_to_prune, StrategyPrunePolicy};
+#[cfg(feature = "database")]
+use crate::units::lamports_to_sol;
+#[cfg(feature = "database")]
+use crate::blockchain_parser::WinnerOrigin;
 use log::{info, warn};
 use serde::{Deserialize, Serialize};
 use std::env;
@@ -23,9 +32,13 @@ pub const SCHEMA_STATEMENTS: &[&str] = &[
         total_vaulted BIGINT DEFAULT 0,
         motherlode BOOLEAN DEFAULT FALSE,
         num_deploys INTEGER DEFAULT 0,
+        winner_source TEXT,
+        winner_disagreement BOOLEAN DEFAULT FALSE,
         created_at TIMESTAMPTZ DEFAULT NOW(),
         completed_at TIMESTAMPTZ
     )"#,
+    "ALTER TABLE rounds ADD COLUMN IF NOT EXISTS winner_source TEXT",
+    "ALTER TABLE rounds ADD COLUMN IF NOT EXISTS winner_disagreement BOOLEAN DEFAULT FALSE",
     
     // Miners table
     r#"CREATE TABLE IF NOT EXISTS miners (
@@ -136,6 +149,14 @@ pub const SCHEMA_STATEMENTS: &[&str] = &[
         updated_at TIMESTAMPTZ DEFAULT NOW()
     )"#,
     
+    // Individual deploys behind player_performance, so totals can be taken over a slot range
+    r#"CREATE TABLE IF NOT EXISTS player_deploys (
+        address TEXT NOT NULL,
+        slot BIGINT NOT NULL,
+        amount_lamports BIGINT NOT NULL,
+        created_at TIMESTAMPTZ DEFAULT NOW()
+    )"#,
+    
     // Square count statistics - which counts work best
     r#"CREATE TABLE IF NOT EXISTS square_count_stats (
         square_count SMALLINT PRIMARY KEY,
@@ -161,8 +182,10 @@ pub const SCHEMA_STATEMENTS: &[&str] = &[
         our_deployed BOOLEAN DEFAULT FALSE,
         our_won BOOLEAN DEFAULT FALSE,
         our_ore_earned REAL DEFAULT 0.0,
+        our_expected_ore REAL,
         created_at TIMESTAMPTZ DEFAULT NOW()
     )"#,
+    "ALTER TABLE round_conditions ADD COLUMN IF NOT EXISTS our_expected_ore REAL",
     
     // Comprehensive win records - THE KEY TABLE
     r#"CREATE TABLE IF NOT EXISTS win_records (
@@ -183,8 +206,10 @@ pub const SCHEMA_STATEMENTS: &[&str] = &[
         winner_share_pct REAL,
         slot BIGINT,
         block_time TIMESTAMPTZ,
+        winner_source TEXT,
         created_at TIMESTAMPTZ DEFAULT NOW()
     )"#,
+    "ALTER TABLE win_records ADD COLUMN IF NOT EXISTS winner_source TEXT",
     
     // Detected strategies - what patterns work
     r#"CREATE TABLE IF NOT EXISTS detected_strategies (
@@ -218,17 +243,40 @@ pub const SCHEMA_STATEMENTS: &[&str] = &[
         completed_at TIMESTAMPTZ
     )"#,
     
+    // Executor deploy leases: one row per executor deploying for an authority in a round
+    r#"CREATE TABLE IF NOT EXISTS deploy_leases (
+        authority TEXT NOT NULL,
+        round_id BIGINT NOT NULL,
+        holder TEXT NOT NULL,
+        acquired_at TIMESTAMPTZ DEFAULT NOW(),
+        PRIMARY KEY (authority, round_id, holder)
+    )"#,
+    
+    // Completed rounds with winner and pot - the one place "recent rounds" is defined
+    // completed_at stays NULL for backfilled rounds, whose completion time isn't known
+    r#"CREATE OR REPLACE VIEW recent_completed_rounds AS
+        SELECT round_id,
+               winning_square,
+               total_deployed AS total_pot,
+               motherlode AS is_motherlode,
+               num_deploys,
+               completed_at
+        FROM rounds
+        WHERE winning_square IS NOT NULL AND winning_square > 0"#,
+    
     // Indexes
     "CREATE INDEX IF NOT EXISTS idx_test_20_completed ON test_20_rounds(completed_at) WHERE completed_at IS NOT NULL",
     "CREATE INDEX IF NOT EXISTS idx_transactions_signer ON transactions(signer)",
     "CREATE INDEX IF NOT EXISTS idx_transactions_round ON transactions(round_id)",
     "CREATE INDEX IF NOT EXISTS idx_transactions_type ON transactions(instruction_type)",
+    "CREATE INDEX IF NOT EXISTS idx_transactions_slot ON transactions(slot DESC)",
     "CREATE INDEX IF NOT EXISTS idx_signals_unprocessed ON signals(processed, target_bot) WHERE NOT processed",
     "CREATE INDEX IF NOT EXISTS idx_rounds_completed ON rounds(completed_at) WHERE completed_at IS NOT NULL",
     "CREATE INDEX IF NOT EXISTS idx_strategy_performance_strategy ON strategy_performance(strategy_name)",
     "CREATE INDEX IF NOT EXISTS idx_whales_deployed ON whales(total_deployed DESC)",
     "CREATE INDEX IF NOT EXISTS idx_player_performance_roi ON player_performance(roi DESC)",
     "CREATE INDEX IF NOT EXISTS idx_player_performance_wins ON player_performance(wins DESC)",
+    "CREATE INDEX IF NOT EXISTS idx_player_deploys_address_slot ON player_deploys(address, slot)",
     "CREATE INDEX IF NOT EXISTS idx_round_conditions_competition ON round_conditions(competition_level)",
     "CREATE INDEX IF NOT EXISTS idx_win_records_winner ON win_records(winner_address)",
     "CREATE INDEX IF NOT EXISTS idx_win_records_round ON win_records(round_id)",
@@ -267,6 +315,72 @@ pub struct DbRound {
     pub completed_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
+/// A completed round from the `recent_completed_rounds` view
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoundSummary {
+    pub round_id: u64,
+    /// 1-25
+    pub winning_square: u8,
+    /// Total SOL deployed in the round (lamports)
+    pub total_pot: u64,
+    pub is_motherlode: bool,
+    pub num_deploys: u32,
+    /// None for backfilled rounds
+    pub completed_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Totals over the completed rounds in a time window
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompletedRoundStats {
+    pub rounds: u64,
+    /// Total SOL deployed across those rounds (lamports)
+    pub total_pot: u64,
+    pub motherlodes: u64,
+}
+
+/// Row shape of `recent_completed_rounds`
+pub type RoundSummaryRow = (i64, i16, i64, bool, i32, Option<chrono::DateTime<chrono::Utc>>);
+
+impl From<RoundSummaryRow> for RoundSummary {
+    fn from((round_id, winning_square, total_pot, is_motherlode, num_deploys, completed_at): RoundSummaryRow) -> Self {
+        Self {
+            round_id: round_id.max(0) as u64,
+            winning_square: winning_square.clamp(0, 25) as u8,
+            total_pot: total_pot.max(0) as u64,
+            is_motherlode,
+            num_deploys: num_deploys.max(0) as u32,
+            completed_at,
+        }
+    }
+}
+
+/// How often one winner source decided a round, and how often the sources disagreed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WinnerSourceStats {
+    /// `WinnerOrigin` as stored: reset_event, round_account or log_fallback
+    pub source: String,
+    pub rounds: u64,
+    /// Rounds where the Round account and Reset transaction named different squares
+    pub disagreements: u64,
+    pub disagreement_rate: f64,
+}
+
+/// (winner_source, rounds, disagreements)
+pub type WinnerSourceRow = (String, i64, i64);
+
+impl From<WinnerSourceRow> for WinnerSourceStats {
+    fn from((source, rounds, disagreements): WinnerSourceRow) -> Self {
+        let rounds = rounds.max(0) as u64;
+        let disagreements = disagreements.max(0) as u64;
+        Self {
+            source,
+            rounds,
+            disagreements,
+            disagreement_rate: if rounds > 0 { disagreements as f64 / rounds as f64 } else { 0.0 },
+        }
+    }
+}
+
 /// Miner data stored in database
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "database", derive(FromRow))]
@@ -296,6 +410,44 @@ pub struct DbTransaction {
     pub success: bool,
 }
 
+/// Row shape of `SELECT signature, slot, block_time, instruction_type, signer, round_id, amount_lamports, squares, success`
+pub type TransactionRow = (
+    String,
+    i64,
+    Option<chrono::DateTime<chrono::Utc>>,
+    String,
+    String,
+    Option<i64>,
+    Option<i64>,
+    Vec<i32>,
+    bool,
+);
+
+impl From<TransactionRow> for DbTransaction {
+    fn from((signature, slot, block_time, instruction_type, signer, round_id, amount_lamports, squares, success): TransactionRow) -> Self {
+        Self {
+            signature,
+            slot,
+            block_time,
+            instruction_type,
+            signer,
+            round_id,
+            amount_lamports,
+            squares,
+            success,
+        }
+    }
+}
+
+/// Newest transactions first; `idx_transactions_slot` serves the sort
+pub const RECENT_TRANSACTIONS_SQL: &str = r#"
+    SELECT signature, slot, block_time, instruction_type, signer,
+           round_id, amount_lamports, squares, success
+    FROM transactions
+    ORDER BY slot DESC
+    LIMIT $1
+"#;
+
 /// Signal for bot-to-bot communication
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Signal {
@@ -358,8 +510,61 @@ impl std::fmt::Display for SignalType {
     }
 }
 
+impl SignalType {
+    /// Every signal type, in declaration order
+    pub const ALL: [SignalType; 13] = [
+        SignalType::RoundStarted,
+        SignalType::RoundEndingSoon,
+        SignalType::RoundCompleted,
+        SignalType::MotherlodeAlert,
+        SignalType::DeployOpportunity,
+        SignalType::ClaimRecommended,
+        SignalType::HotSquare,
+        SignalType::ColdSquare,
+        SignalType::PriceAlert,
+        SignalType::Heartbeat,
+        SignalType::BetPlaced,
+        SignalType::Error,
+        SignalType::Custom,
+    ];
+}
+
+/// Parse the name stored in `signals.signal_type` (same as `Display`)
+impl std::str::FromStr for SignalType {
+    type Err = BotError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        SignalType::ALL
+            .iter()
+            .find(|t| t.to_string() == s)
+            .cloned()
+            .ok_or_else(|| BotError::Other(format!("Unknown signal type: {}", s)))
+    }
+}
+
+impl TryFrom<&str> for SignalType {
+    type Error = BotError;
+
+    fn try_from(s: &str) -> Result<Self> {
+        s.parse()
+    }
+}
+
+/// `context: error`, as `DatabaseUnavailable` when the database itself couldn't be reached
+#[cfg(feature = "database")]
+fn db_error(context: &str, e: sqlx::Error) -> BotError {
+    match e {
+        sqlx::Error::PoolTimedOut | sqlx::Error::PoolClosed | sqlx::Error::Io(_) | sqlx::Error::Tls(_) => {
+            BotError::DatabaseUnavailable(format!("{}: {}", context, e))
+        }
+        e => BotError::Other(format!("{}: {}", context, e)),
+    }
+}
+
 /// Shared database client using raw SQL (no heavy ORM dependencies)
 /// In production, you'd use sqlx with the database feature enabled
+/// Cloning is cheap (the connection pool is shared)
+#[derive(Clone)]
 pub struct SharedDb {
     config: DbConfig,
     #[cfg(feature = "database")]
@@ -372,12 +577,17 @@ impl SharedDb {
     pub async fn connect() -> Result<Self> {
         let config = DbConfig::from_env()
             .ok_or_else(|| BotError::Other("DATABASE_URL not set".to_string()))?;
-        
+        Self::connect_with(config).await
+    }
+
+    /// Connect to an explicit database and initialize its schema
+    #[cfg(feature = "database")]
+    pub async fn connect_with(config: DbConfig) -> Result<Self> {
         info!("🔌 Connecting to database...");
         
         let pool = sqlx::PgPool::connect(&config.url)
             .await
-            .map_err(|e| BotError::Other(format!("Database connection failed: {}", e)))?;
+            .map_err(|e| db_error("Database connection failed", e))?;
         
         info!("✅ Database connected");
         
@@ -396,7 +606,7 @@ impl SharedDb {
             sqlx::query(statement)
                 .execute(&self.pool)
                 .await
-                .map_err(|e| BotError::Other(format!("Schema statement {} failed: {}", i + 1, e)))?;
+                .map_err(|e| db_error(&format!("Schema statement {} failed", i + 1), e))?;
         }
         
         info!("✅ Database schema ready ({} tables/indexes created)", SCHEMA_STATEMENTS.len());
@@ -436,11 +646,40 @@ impl SharedDb {
         .bind(round.completed_at)
         .execute(&self.pool)
         .await
-        .map_err(|e| BotError::Other(format!("Failed to upsert round: {}", e)))?;
+        .map_err(|e| db_error("Failed to upsert round", e))?;
         
         Ok(())
     }
 
+    /// Store a backfilled round unless a row for it already exists
+    /// Returns false if the round was already there; an in-progress row is never overwritten
+    #[cfg(feature = "database")]
+    pub async fn insert_backfilled_round(&self, round: &DbRound) -> Result<bool> {
+        let result = sqlx::query(r#"
+            INSERT INTO rounds (round_id, start_slot, end_slot, winning_square, total_deployed, 
+                               deployed_squares, total_winnings, total_vaulted, motherlode, 
+                               num_deploys, completed_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            ON CONFLICT (round_id) DO NOTHING
+        "#)
+        .bind(round.round_id)
+        .bind(round.start_slot)
+        .bind(round.end_slot)
+        .bind(round.winning_square)
+        .bind(round.total_deployed)
+        .bind(&round.deployed_squares)
+        .bind(round.total_winnings)
+        .bind(round.total_vaulted)
+        .bind(round.motherlode)
+        .bind(round.num_deploys)
+        .bind(round.completed_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| db_error("Failed to insert backfilled round", e))?;
+        
+        Ok(result.rows_affected() > 0)
+    }
+
     /// Get recent rounds
     #[cfg(feature = "database")]
     pub async fn get_recent_rounds(&self, limit: i32) -> Result<Vec<i64>> {
@@ -450,11 +689,51 @@ impl SharedDb {
         .bind(limit)
         .fetch_all(&self.pool)
         .await
-        .map_err(|e| BotError::Other(format!("Failed to get rounds: {}", e)))?;
+        .map_err(|e| db_error("Failed to get rounds", e))?;
         
         Ok(round_ids.into_iter().map(|(id,)| id).collect())
     }
 
+    /// Last `limit` completed rounds, newest first
+    #[cfg(feature = "database")]
+    pub async fn get_recent_completed_rounds(&self, limit: i64) -> Result<Vec<RoundSummary>> {
+        let rows = sqlx::query_as::<_, RoundSummaryRow>(r#"
+            SELECT round_id, winning_square, total_pot, is_motherlode, num_deploys, completed_at
+            FROM recent_completed_rounds
+            ORDER BY round_id DESC
+            LIMIT $1
+        "#)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| db_error("Failed to get recent completed rounds", e))?;
+        
+        Ok(rows.into_iter().map(RoundSummary::from).collect())
+    }
+
+    /// Count, pot and motherlodes of the rounds completed in the last `hours`
+    /// Backfilled rounds have no completion time and are not counted
+    #[cfg(feature = "database")]
+    pub async fn get_completed_round_stats(&self, hours: i32) -> Result<CompletedRoundStats> {
+        let (rounds, total_pot, motherlodes): (i64, i64, i64) = sqlx::query_as(r#"
+            SELECT COUNT(*),
+                   COALESCE(SUM(total_pot), 0)::BIGINT,
+                   COUNT(*) FILTER (WHERE is_motherlode)
+            FROM recent_completed_rounds
+            WHERE completed_at > NOW() - make_interval(hours => $1)
+        "#)
+        .bind(hours)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| db_error("Failed to get completed round stats", e))?;
+        
+        Ok(CompletedRoundStats {
+            rounds: rounds.max(0) as u64,
+            total_pot: total_pot.max(0) as u64,
+            motherlodes: motherlodes.max(0) as u64,
+        })
+    }
+
     /// Store/update a miner
     #[cfg(feature = "database")]
     pub async fn upsert_miner(&self, miner: &DbMiner) -> Result<()> {
@@ -484,7 +763,7 @@ impl SharedDb {
         .bind(miner.win_rate)
         .execute(&self.pool)
         .await
-        .map_err(|e| BotError::Other(format!("Failed to upsert miner: {}", e)))?;
+        .map_err(|e| db_error("Failed to upsert miner", e))?;
         
         Ok(())
     }
@@ -509,11 +788,39 @@ impl SharedDb {
         .bind(tx.success)
         .execute(&self.pool)
         .await
-        .map_err(|e| BotError::Other(format!("Failed to insert transaction: {}", e)))?;
+        .map_err(|e| db_error("Failed to insert transaction", e))?;
         
         Ok(())
     }
 
+    /// Most recent stored transactions by slot
+    #[cfg(feature = "database")]
+    pub async fn get_recent_transactions(&self, limit: i64) -> Result<Vec<DbTransaction>> {
+        let rows = sqlx::query_as::<_, TransactionRow>(RECENT_TRANSACTIONS_SQL)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| db_error("Failed to get recent transactions", e))?;
+        
+        Ok(rows.into_iter().map(DbTransaction::from).collect())
+    }
+
+    /// Delete transactions more than `keep_slots` slots older than the newest one
+    /// Returns the number of rows removed
+    #[cfg(feature = "database")]
+    pub async fn prune_transactions(&self, keep_slots: i64) -> Result<u64> {
+        let result = sqlx::query(r#"
+            DELETE FROM transactions
+            WHERE slot < (SELECT MAX(slot) FROM transactions) - $1
+        "#)
+        .bind(keep_slots)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| db_error("Failed to prune transactions", e))?;
+        
+        Ok(result.rows_affected())
+    }
+
     /// Send a signal to other bots
     #[cfg(feature = "database")]
     pub async fn send_signal(&self, signal: &Signal) -> Result<()> {
@@ -527,14 +834,15 @@ impl SharedDb {
         .bind(&signal.payload)
         .execute(&self.pool)
         .await
-        .map_err(|e| BotError::Other(format!("Failed to send signal: {}", e)))?;
+        .map_err(|e| db_error("Failed to send signal", e))?;
         
         Ok(())
     }
 
     /// Get pending signals for a bot
+    /// Rows with an unrecognised signal type are skipped
     #[cfg(feature = "database")]
-    pub async fn get_pending_signals(&self, bot_name: &str) -> Result<Vec<(i32, String, String, Option<String>, serde_json::Value)>> {
+    pub async fn get_pending_signals(&self, bot_name: &str) -> Result<Vec<(i32, SignalType, String, Option<String>, serde_json::Value)>> {
         let signals = sqlx::query_as::<_, (i32, String, String, Option<String>, serde_json::Value)>(r#"
             SELECT id, signal_type, source_bot, target_bot, payload
             FROM signals 
@@ -544,9 +852,19 @@ impl SharedDb {
         .bind(bot_name)
         .fetch_all(&self.pool)
         .await
-        .map_err(|e| BotError::Other(format!("Failed to get signals: {}", e)))?;
+        .map_err(|e| db_error("Failed to get signals", e))?;
         
-        Ok(signals)
+        Ok(signals.into_iter()
+            .filter_map(|(id, signal_type, source, target, payload)| {
+                match signal_type.parse::<SignalType>() {
+                    Ok(t) => Some((id, t, source, target, payload)),
+                    Err(e) => {
+                        warn!("Skipping signal {}: {}", id, e);
+                        None
+                    }
+                }
+            })
+            .collect())
     }
 
     /// Mark signals as processed
@@ -556,11 +874,27 @@ impl SharedDb {
             .bind(signal_ids)
             .execute(&self.pool)
             .await
-            .map_err(|e| BotError::Other(format!("Failed to mark signals: {}", e)))?;
+            .map_err(|e| db_error("Failed to mark signals", e))?;
         
         Ok(())
     }
 
+    /// Get the most recent heartbeat time (unix seconds) for each bot
+    #[cfg(feature = "database")]
+    pub async fn get_last_heartbeats(&self) -> Result<Vec<(String, i64)>> {
+        let heartbeats = sqlx::query_as::<_, (String, i64)>(r#"
+            SELECT source_bot, EXTRACT(EPOCH FROM MAX(created_at))::BIGINT
+            FROM signals
+            WHERE signal_type = 'heartbeat'
+            GROUP BY source_bot
+        "#)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| db_error("Failed to get heartbeats", e))?;
+        
+        Ok(heartbeats)
+    }
+
     /// Store bot state (key-value)
     #[cfg(feature = "database")]
     pub async fn set_state(&self, key: &str, value: serde_json::Value) -> Result<()> {
@@ -573,7 +907,7 @@ impl SharedDb {
         .bind(value)
         .execute(&self.pool)
         .await
-        .map_err(|e| BotError::Other(format!("Failed to set state: {}", e)))?;
+        .map_err(|e| db_error("Failed to set state", e))?;
         
         Ok(())
     }
@@ -587,11 +921,17 @@ impl SharedDb {
         .bind(key)
         .fetch_optional(&self.pool)
         .await
-        .map_err(|e| BotError::Other(format!("Failed to get state: {}", e)))?;
+        .map_err(|e| db_error("Failed to get state", e))?;
         
         Ok(result)
     }
 
+    /// Store a sanitized copy of a bot's effective config under `<bot>_config`
+    #[cfg(feature = "database")]
+    pub async fn publish_config(&self, bot_name: &str, config: &BotConfig) -> Result<()> {
+        self.set_state(&format!("{}_config", bot_name), config.sanitized()).await
+    }
+
     /// Get square statistics from historical data
     #[cfg(feature = "database")]
     pub async fn get_square_stats(&self) -> Result<Vec<(i64, i64, i64)>> {
@@ -607,7 +947,7 @@ impl SharedDb {
         "#)
         .fetch_all(&self.pool)
         .await
-        .map_err(|e| BotError::Other(format!("Failed to get square stats: {}", e)))?;
+        .map_err(|e| db_error("Failed to get square stats", e))?;
         
         Ok(stats)
     }
@@ -621,7 +961,7 @@ impl SharedDb {
         .bind(limit)
         .fetch_all(&self.pool)
         .await
-        .map_err(|e| BotError::Other(format!("Failed to get top miners: {}", e)))?;
+        .map_err(|e| db_error("Failed to get top miners", e))?;
         
         Ok(miners)
     }
@@ -629,6 +969,7 @@ impl SharedDb {
     // ==================== LEARNING METHODS ====================
 
     /// Update square statistics from a completed round
+    /// `winning_square` is 0-24, like `square_id`
     #[cfg(feature = "database")]
     pub async fn update_square_stats(&self, winning_square: i16, deployed: &[i64; 25]) -> Result<()> {
         for (i, &amount) in deployed.iter().enumerate() {
@@ -674,7 +1015,7 @@ impl SharedDb {
         "#)
         .fetch_all(&self.pool)
         .await
-        .map_err(|e| BotError::Other(format!("Failed to load square stats: {}", e)))?;
+        .map_err(|e| db_error("Failed to load square stats", e))?;
         
         Ok(stats)
     }
@@ -702,7 +1043,7 @@ impl SharedDb {
         .bind(squares)
         .execute(&self.pool)
         .await
-        .map_err(|e| BotError::Other(format!("Failed to track whale: {}", e)))?;
+        .map_err(|e| db_error("Failed to track whale", e))?;
         
         Ok(())
     }
@@ -720,7 +1061,7 @@ impl SharedDb {
         .bind(min_deployed)
         .fetch_all(&self.pool)
         .await
-        .map_err(|e| BotError::Other(format!("Failed to load whales: {}", e)))?;
+        .map_err(|e| db_error("Failed to load whales", e))?;
         
         Ok(whales)
     }
@@ -750,7 +1091,7 @@ impl SharedDb {
         .bind(confidence)
         .execute(&self.pool)
         .await
-        .map_err(|e| BotError::Other(format!("Failed to record strategy: {}", e)))?;
+        .map_err(|e| db_error("Failed to record strategy", e))?;
         
         Ok(())
     }
@@ -770,7 +1111,7 @@ impl SharedDb {
         "#)
         .fetch_all(&self.pool)
         .await
-        .map_err(|e| BotError::Other(format!("Failed to get strategy performance: {}", e)))?;
+        .map_err(|e| db_error("Failed to get strategy performance", e))?;
         
         Ok(perf)
     }
@@ -778,8 +1119,19 @@ impl SharedDb {
     /// Load historical rounds for strategy engine initialization
     #[cfg(feature = "database")]
     pub async fn load_round_history(&self, limit: i32) -> Result<Vec<(i64, i16, Vec<i64>, i64, bool)>> {
-        let rounds = sqlx::query_as::<_, (i64, i16, Vec<i64>, i64, bool)>(r#"
-            SELECT round_id, COALESCE(winning_square, -1), deployed_squares, total_deployed, motherlode
+        self.load_round_history_within(limit, &HistoryWindow::default()).await
+    }
+
+    /// Load historical rounds, dropping any outside the given window
+    #[cfg(feature = "database")]
+    pub async fn load_round_history_within(
+        &self,
+        limit: i32,
+        window: &HistoryWindow,
+    ) -> Result<Vec<(i64, i16, Vec<i64>, i64, bool)>> {
+        let rounds = sqlx::query_as::<_, (i64, i16, Vec<i64>, i64, bool, Option<i64>)>(r#"
+            SELECT round_id, COALESCE(winning_square, -1), deployed_squares, total_deployed, motherlode,
+                   EXTRACT(EPOCH FROM COALESCE(completed_at, created_at))::BIGINT
             FROM rounds
             WHERE winning_square IS NOT NULL
             ORDER BY round_id DESC
@@ -788,9 +1140,15 @@ impl SharedDb {
         .bind(limit)
         .fetch_all(&self.pool)
         .await
-        .map_err(|e| BotError::Other(format!("Failed to load round history: {}", e)))?;
+        .map_err(|e| db_error("Failed to load round history", e))?;
+        
+        let newest = rounds.first().map(|r| r.0).unwrap_or(0);
+        let now = chrono::Utc::now().timestamp();
         
-        Ok(rounds)
+        Ok(rounds.into_iter()
+            .filter(|r| window.contains(r.0, newest, r.5, now))
+            .map(|(id, sq, deployed, total, ml, _)| (id, sq, deployed, total, ml))
+            .collect())
     }
 
     /// Update round with winning square (when round completes)
@@ -806,11 +1164,56 @@ impl SharedDb {
         .bind(motherlode)
         .execute(&self.pool)
         .await
-        .map_err(|e| BotError::Other(format!("Failed to complete round: {}", e)))?;
+        .map_err(|e| db_error("Failed to complete round", e))?;
         
         Ok(())
     }
 
+    /// Which source decided a completed round's winner
+    /// Win records already written for the round are tagged too; later ones pick it up on insert
+    #[cfg(feature = "database")]
+    pub async fn record_winner_source(&self, round_id: i64, origin: WinnerOrigin, disagreed: bool) -> Result<()> {
+        sqlx::query(r#"
+            UPDATE rounds
+            SET winner_source = $2, winner_disagreement = $3
+            WHERE round_id = $1
+        "#)
+        .bind(round_id)
+        .bind(origin.as_str())
+        .bind(disagreed)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| db_error("Failed to record winner source", e))?;
+
+        sqlx::query("UPDATE win_records SET winner_source = $2 WHERE round_id = $1")
+            .bind(round_id)
+            .bind(origin.as_str())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| db_error("Failed to record winner source", e))?;
+
+        Ok(())
+    }
+
+    /// Rounds decided by each winner source, with how often the sources disagreed
+    #[cfg(feature = "database")]
+    pub async fn get_winner_source_stats(&self) -> Result<Vec<WinnerSourceStats>> {
+        let rows = sqlx::query_as::<_, WinnerSourceRow>(r#"
+            SELECT winner_source,
+                   COUNT(*),
+                   COUNT(*) FILTER (WHERE winner_disagreement)
+            FROM rounds
+            WHERE winner_source IS NOT NULL
+            GROUP BY winner_source
+            ORDER BY COUNT(*) DESC
+        "#)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| db_error("Failed to get winner source stats", e))?;
+
+        Ok(rows.into_iter().map(WinnerSourceStats::from).collect())
+    }
+
     // ==================== TEST-20 TRACKING METHODS ====================
 
     /// Lock test-20 picks at round start (pick best 20 squares to bet on)
@@ -827,7 +1230,7 @@ impl SharedDb {
         .bind(confidence)
         .execute(&self.pool)
         .await
-        .map_err(|e| BotError::Other(format!("Failed to lock test-20 round: {}", e)))?;
+        .map_err(|e| db_error("Failed to lock test-20 round", e))?;
         
         Ok(())
     }
@@ -842,7 +1245,7 @@ impl SharedDb {
         .bind(round_id)
         .fetch_optional(&self.pool)
         .await
-        .map_err(|e| BotError::Other(format!("Failed to get test-20 round: {}", e)))?;
+        .map_err(|e| db_error("Failed to get test-20 round", e))?;
         
         if let Some((betting_squares,)) = result {
             let is_hit = betting_squares.contains(&(winning_square as i32));
@@ -857,7 +1260,7 @@ impl SharedDb {
             .bind(is_hit)
             .execute(&self.pool)
             .await
-            .map_err(|e| BotError::Other(format!("Failed to complete test-20 round: {}", e)))?;
+            .map_err(|e| db_error("Failed to complete test-20 round", e))?;
             
             Ok(is_hit)
         } else {
@@ -935,7 +1338,7 @@ impl SharedDb {
         "#)
         .fetch_all(&self.pool)
         .await
-        .map_err(|e| BotError::Other(format!("Failed to get square win rates: {}", e)))?;
+        .map_err(|e| db_error("Failed to get square win rates", e))?;
         
         Ok(rates)
     }
@@ -966,10 +1369,23 @@ impl SharedDb {
     }
 
     /// Clean up old data
+    /// Transactions older than `days` go, and with `keep_slots > 0` so does
+    /// anything further than that many slots behind the newest transaction
     #[cfg(feature = "database")]
-    pub async fn cleanup_old_data(&self, days: i32) -> Result<()> {
+    pub async fn cleanup_old_data(&self, days: i32, keep_slots: i64) -> Result<u64> {
         // Clean old transactions
-        sqlx::query("DELETE FROM transactions WHERE created_at < NOW() - INTERVAL '$1 days'")
+        let mut removed = sqlx::query("DELETE FROM transactions WHERE created_at < NOW() - make_interval(days => $1)")
+            .bind(days)
+            .execute(&self.pool)
+            .await
+            .map(|r| r.rows_affected())
+            .unwrap_or(0);
+        if keep_slots > 0 {
+            removed += self.prune_transactions(keep_slots).await?;
+        }
+        
+        // Per-deploy rows only back slot-range totals; player_performance keeps the lifetime ones
+        sqlx::query("DELETE FROM player_deploys WHERE created_at < NOW() - make_interval(days => $1)")
             .bind(days)
             .execute(&self.pool)
             .await
@@ -981,7 +1397,7 @@ impl SharedDb {
             .await
             .ok();
         
-        Ok(())
+        Ok(removed)
     }
 
     // ===== ALL PLAYER LEARNING METHODS =====
@@ -1012,11 +1428,38 @@ impl SharedDb {
         .bind(slot)
         .execute(&self.pool)
         .await
-        .map_err(|e| BotError::Other(format!("Failed to record player deploy: {}", e)))?;
+        .map_err(|e| db_error("Failed to record player deploy", e))?;
+        
+        sqlx::query("INSERT INTO player_deploys (address, slot, amount_lamports) VALUES ($1, $2, $3)")
+            .bind(address)
+            .bind(slot)
+            .bind(amount_lamports)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| db_error("Failed to record player deploy", e))?;
         
         Ok(())
     }
 
+    /// A player's recorded deploys at or after `from_slot`
+    /// Returns: (slot, amount_lamports)
+    #[cfg(feature = "database")]
+    pub async fn get_player_deploys_since(&self, address: &str, from_slot: i64) -> Result<Vec<(i64, i64)>> {
+        let deploys = sqlx::query_as::<_, (i64, i64)>(r#"
+            SELECT slot, amount_lamports
+            FROM player_deploys
+            WHERE address = $1 AND slot >= $2
+            ORDER BY slot
+        "#)
+        .bind(address)
+        .bind(from_slot)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| db_error("Failed to get player deploys", e))?;
+        
+        Ok(deploys)
+    }
+
     /// Record a player's win
     #[cfg(feature = "database")]
     pub async fn record_player_win(
@@ -1038,7 +1481,7 @@ impl SharedDb {
         .bind(amount_won_lamports)
         .execute(&self.pool)
         .await
-        .map_err(|e| BotError::Other(format!("Failed to record player win: {}", e)))?;
+        .map_err(|e| db_error("Failed to record player win", e))?;
         
         Ok(())
     }
@@ -1056,11 +1499,28 @@ impl SharedDb {
         "#)
         .fetch_all(&self.pool)
         .await
-        .map_err(|e| BotError::Other(format!("Failed to load players: {}", e)))?;
+        .map_err(|e| db_error("Failed to load players", e))?;
         
         Ok(players)
     }
 
+    /// Get recorded totals for a single player
+    /// Returns: (total_deployed, total_won, total_rounds, wins)
+    #[cfg(feature = "database")]
+    pub async fn get_player_totals(&self, address: &str) -> Result<Option<(i64, i64, i32, i32)>> {
+        let totals = sqlx::query_as::<_, (i64, i64, i32, i32)>(r#"
+            SELECT total_deployed, total_won, total_rounds, wins
+            FROM player_performance
+            WHERE address = $1
+        "#)
+        .bind(address)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| db_error("Failed to get player totals", e))?;
+        
+        Ok(totals)
+    }
+
     /// Get top performing players by ROI
     #[cfg(feature = "database")]
     pub async fn get_top_performers(&self, limit: i32) -> Result<Vec<(String, f32, f32, i32, f32)>> {
@@ -1080,7 +1540,7 @@ impl SharedDb {
         .bind(limit)
         .fetch_all(&self.pool)
         .await
-        .map_err(|e| BotError::Other(format!("Failed to get top performers: {}", e)))?;
+        .map_err(|e| db_error("Failed to get top performers", e))?;
         
         Ok(performers)
     }
@@ -1100,7 +1560,7 @@ impl SharedDb {
         .bind(amount_lamports)
         .execute(&self.pool)
         .await
-        .map_err(|e| BotError::Other(format!("Failed to record square count deploy: {}", e)))?;
+        .map_err(|e| db_error("Failed to record square count deploy", e))?;
         
         Ok(())
     }
@@ -1123,7 +1583,7 @@ impl SharedDb {
         .bind(amount_won_lamports)
         .execute(&self.pool)
         .await
-        .map_err(|e| BotError::Other(format!("Failed to record square count win: {}", e)))?;
+        .map_err(|e| db_error("Failed to record square count win", e))?;
         
         Ok(())
     }
@@ -1140,7 +1600,7 @@ impl SharedDb {
         "#)
         .fetch_all(&self.pool)
         .await
-        .map_err(|e| BotError::Other(format!("Failed to load square count stats: {}", e)))?;
+        .map_err(|e| db_error("Failed to load square count stats", e))?;
         
         Ok(stats)
     }
@@ -1180,7 +1640,7 @@ impl SharedDb {
         .bind(squares_with_deploys)
         .execute(&self.pool)
         .await
-        .map_err(|e| BotError::Other(format!("Failed to record round conditions: {}", e)))?;
+        .map_err(|e| db_error("Failed to record round conditions", e))?;
         
         Ok(())
     }
@@ -1205,7 +1665,134 @@ impl SharedDb {
         .bind(ore_earned)
         .execute(&self.pool)
         .await
-        .map_err(|e| BotError::Other(format!("Failed to record our round result: {}", e)))?;
+        .map_err(|e| db_error("Failed to record our round result", e))?;
+        
+        Ok(())
+    }
+
+    /// Record the ORE we expected when deciding to deploy in a round
+    #[cfg(feature = "database")]
+    pub async fn record_expected_ore(&self, round_id: i64, expected_ore: f32) -> Result<()> {
+        sqlx::query(r#"
+            INSERT INTO round_conditions (round_id, our_deployed, our_expected_ore)
+            VALUES ($1, TRUE, $2)
+            ON CONFLICT (round_id) DO UPDATE SET
+                our_deployed = TRUE,
+                our_expected_ore = $2
+        "#)
+        .bind(round_id)
+        .bind(expected_ore)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| db_error("Failed to record expected ORE", e))?;
+        
+        Ok(())
+    }
+
+    /// (round_id, expected, realized) ORE for recent rounds we played
+    #[cfg(feature = "database")]
+    pub async fn get_ore_estimate_pairs(&self, limit: i64) -> Result<Vec<(i64, f32, f32)>> {
+        let pairs = sqlx::query_as::<_, (i64, f32, f32)>(r#"
+            SELECT round_id, our_expected_ore, COALESCE(our_ore_earned, 0.0)
+            FROM round_conditions
+            WHERE our_deployed = TRUE AND our_expected_ore IS NOT NULL
+            ORDER BY round_id DESC
+            LIMIT $1
+        "#)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| db_error("Failed to get ORE estimates", e))?;
+        
+        Ok(pairs)
+    }
+
+    /// (total_deployed, num_deployers) for the most recent recorded rounds
+    #[cfg(feature = "database")]
+    pub async fn get_recent_round_competition(&self, limit: i64) -> Result<Vec<(i64, i32)>> {
+        let rows = sqlx::query_as::<_, (i64, i32)>(r#"
+            SELECT total_deployed, num_deployers
+            FROM round_conditions
+            WHERE total_deployed IS NOT NULL AND num_deployers IS NOT NULL
+            ORDER BY round_id DESC
+            LIMIT $1
+        "#)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| db_error("Failed to get round competition", e))?;
+        
+        Ok(rows)
+    }
+
+    /// Take one of the `max_in_flight` executor deploy slots for `authority` in a round
+    /// Returns false if the other executors already hold them all
+    /// Re-acquiring a lease the holder already has succeeds
+    #[cfg(feature = "database")]
+    pub async fn acquire_deploy_lease(
+        &self,
+        authority: &str,
+        round_id: i64,
+        holder: &str,
+        max_in_flight: u32,
+    ) -> Result<bool> {
+        let err = |e: sqlx::Error| db_error("Failed to acquire deploy lease", e);
+        let mut tx = self.pool.begin().await.map_err(err)?;
+        
+        // Serialize executors of the same authority for the count-then-insert
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext($1))")
+            .bind(authority)
+            .execute(&mut *tx)
+            .await
+            .map_err(err)?;
+        
+        let holders = sqlx::query_scalar::<_, String>(
+            "SELECT holder FROM deploy_leases WHERE authority = $1 AND round_id = $2"
+        )
+        .bind(authority)
+        .bind(round_id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(err)?;
+        
+        if !can_acquire_lease(&holders, holder, max_in_flight) {
+            return Ok(false);
+        }
+        
+        sqlx::query(r#"
+            INSERT INTO deploy_leases (authority, round_id, holder)
+            VALUES ($1, $2, $3)
+            ON CONFLICT DO NOTHING
+        "#)
+        .bind(authority)
+        .bind(round_id)
+        .bind(holder)
+        .execute(&mut *tx)
+        .await
+        .map_err(err)?;
+        
+        // Old rounds' leases are finished
+        sqlx::query("DELETE FROM deploy_leases WHERE authority = $1 AND round_id < $2 - 10")
+            .bind(authority)
+            .bind(round_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(err)?;
+        
+        tx.commit().await.map_err(err)?;
+        Ok(true)
+    }
+
+    /// Give back a lease after a deploy that never made it on-chain
+    #[cfg(feature = "database")]
+    pub async fn release_deploy_lease(&self, authority: &str, round_id: i64, holder: &str) -> Result<()> {
+        sqlx::query("DELETE FROM deploy_leases WHERE authority = $1 AND round_id = $2 AND holder = $3")
+            .bind(authority)
+            .bind(round_id)
+            .bind(holder)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| db_error("Failed to release deploy lease", e))?;
         
         Ok(())
     }
@@ -1227,7 +1814,7 @@ impl SharedDb {
         "#)
         .fetch_all(&self.pool)
         .await
-        .map_err(|e| BotError::Other(format!("Failed to get best conditions: {}", e)))?;
+        .map_err(|e| db_error("Failed to get best conditions", e))?;
         
         Ok(conditions)
     }
@@ -1297,8 +1884,9 @@ impl SharedDb {
                 (round_id, winner_address, winning_square, amount_bet, amount_won,
                  squares_bet, num_squares, total_round_sol, num_deployers,
                  is_motherlode, is_full_ore, ore_earned, competition_on_square,
-                 winner_share_pct, slot)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+                 winner_share_pct, slot, winner_source)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15,
+                    (SELECT winner_source FROM rounds WHERE round_id = $1))
             ON CONFLICT DO NOTHING
         "#)
         .bind(round_id)
@@ -1318,7 +1906,7 @@ impl SharedDb {
         .bind(slot)
         .execute(&self.pool)
         .await
-        .map_err(|e| BotError::Other(format!("Failed to record win: {}", e)))?;
+        .map_err(|e| db_error("Failed to record win", e))?;
         
         Ok(())
     }
@@ -1337,7 +1925,7 @@ impl SharedDb {
         .bind(limit)
         .fetch_all(&self.pool)
         .await
-        .map_err(|e| BotError::Other(format!("Failed to load wins: {}", e)))?;
+        .map_err(|e| db_error("Failed to load wins", e))?;
         
         Ok(wins.into_iter().map(|(round_id, winner, winning_sq, bet, won, squares, num_sq, total_sol, deployers, motherlode, full_ore, ore)| {
             serde_json::json!({
@@ -1357,6 +1945,24 @@ impl SharedDb {
         }).collect())
     }
 
+    /// Get every winner of a round as (address, amount_bet, amount_won, share_pct, ore_earned)
+    #[cfg(feature = "database")]
+    pub async fn get_round_winners(&self, round_id: i64) -> Result<Vec<(String, i64, i64, f32, f32)>> {
+        let winners = sqlx::query_as::<_, (String, i64, i64, f32, f32)>(r#"
+            SELECT winner_address, COALESCE(amount_bet, 0), COALESCE(amount_won, 0),
+                   COALESCE(winner_share_pct, 0), COALESCE(ore_earned, 0)
+            FROM win_records
+            WHERE round_id = $1
+            ORDER BY winner_share_pct DESC NULLS LAST
+        "#)
+        .bind(round_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| db_error("Failed to get round winners", e))?;
+        
+        Ok(winners)
+    }
+
     /// Get full ORE wins specifically - the most valuable learning data
     #[cfg(feature = "database")]
     pub async fn get_full_ore_wins(&self, limit: i32) -> Result<Vec<serde_json::Value>> {
@@ -1371,17 +1977,17 @@ impl SharedDb {
         .bind(limit)
         .fetch_all(&self.pool)
         .await
-        .map_err(|e| BotError::Other(format!("Failed to get full ORE wins: {}", e)))?;
+        .map_err(|e| db_error("Failed to get full ORE wins", e))?;
         
         Ok(wins.into_iter().map(|(round_id, winner, winning_sq, bet, squares, num_sq, total_sol, deployers)| {
             serde_json::json!({
                 "round_id": round_id,
                 "winner": winner,
                 "winning_square": winning_sq,
-                "amount_bet_sol": bet as f64 / 1_000_000_000.0,
+                "amount_bet_sol": lamports_to_sol(bet),
                 "squares_bet": squares,
                 "num_squares": num_sq,
-                "total_round_sol": total_sol as f64 / 1_000_000_000.0,
+                "total_round_sol": lamports_to_sol(total_sol),
                 "num_deployers": deployers,
             })
         }).collect())
@@ -1401,17 +2007,17 @@ impl SharedDb {
         .bind(limit)
         .fetch_all(&self.pool)
         .await
-        .map_err(|e| BotError::Other(format!("Failed to get motherlode wins: {}", e)))?;
+        .map_err(|e| db_error("Failed to get motherlode wins", e))?;
         
         Ok(wins.into_iter().map(|(round_id, winner, winning_sq, bet, won, num_sq, total_sol, ore)| {
             serde_json::json!({
                 "round_id": round_id,
                 "winner": winner,
                 "winning_square": winning_sq,
-                "bet_sol": bet as f64 / 1_000_000_000.0,
-                "won_sol": won as f64 / 1_000_000_000.0,
+                "bet_sol": lamports_to_sol(bet),
+                "won_sol": lamports_to_sol(won),
                 "num_squares": num_sq,
-                "total_round_sol": total_sol as f64 / 1_000_000_000.0,
+                "total_round_sol": lamports_to_sol(total_sol),
                 "ore_earned": ore,
             })
         }).collect())
@@ -1474,7 +2080,7 @@ impl SharedDb {
         .bind(example_players)
         .execute(&self.pool)
         .await
-        .map_err(|e| BotError::Other(format!("Failed to save strategy: {}", e)))?;
+        .map_err(|e| db_error("Failed to save strategy", e))?;
         
         Ok(())
     }
@@ -1491,7 +2097,7 @@ impl SharedDb {
         "#)
         .fetch_all(&self.pool)
         .await
-        .map_err(|e| BotError::Other(format!("Failed to load strategies: {}", e)))?;
+        .map_err(|e| db_error("Failed to load strategies", e))?;
         
         Ok(strategies.into_iter().map(|(name, desc, samples, win_rate, roi, ore, sq_count, bet, comp, pref_sq, motherlode, conf, consistent)| {
             serde_json::json!({
@@ -1512,6 +2118,34 @@ impl SharedDb {
         }).collect())
     }
 
+    /// Remove stale, low-confidence and excess detected strategies
+    /// Returns the names that were removed
+    #[cfg(feature = "database")]
+    pub async fn prune_detected_strategies(&self, policy: &StrategyPrunePolicy) -> Result<Vec<String>> {
+        let stored = sqlx::query_as::<_, (String, f32, i64)>(r#"
+            SELECT name, COALESCE(confidence, 0), EXTRACT(EPOCH FROM updated_at)::BIGINT
+            FROM detected_strategies
+        "#)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| db_error("Failed to load detected strategies", e))?;
+        
+        let stored: Vec<(String, f64, i64)> = stored.into_iter()
+            .map(|(name, conf, updated)| (name, conf as f64, updated))
+            .collect();
+        let to_prune = strategies_to_prune(&stored, chrono::Utc::now().timestamp(), policy);
+        
+        if !to_prune.is_empty() {
+            sqlx::query("DELETE FROM detected_strategies WHERE name = ANY($1)")
+                .bind(&to_prune)
+                .execute(&self.pool)
+                .await
+                .map_err(|e| db_error("Failed to prune detected strategies", e))?;
+        }
+        
+        Ok(to_prune)
+    }
+
     /// Get winning stats summary
     #[cfg(feature = "database")]
     pub async fn get_win_stats(&self) -> Result<serde_json::Value> {
@@ -1577,6 +2211,37 @@ impl SharedDb {
     }
 }
 
+/// Bounds on how far back round history is loaded (0 = no limit)
+/// Keeps strategies on the current regime after game parameters change
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HistoryWindow {
+    pub max_age_rounds: u64,
+    pub max_age_days: u64,
+}
+
+impl HistoryWindow {
+    pub fn from_config(config: &AnalyticsConfig) -> Self {
+        Self {
+            max_age_rounds: config.max_history_age_rounds,
+            max_age_days: config.max_history_days,
+        }
+    }
+
+    /// Whether a round is recent enough, relative to the newest loaded round and `now`
+    /// Rounds without a timestamp are only checked against the round bound
+    pub fn contains(&self, round_id: i64, newest_round_id: i64, completed_at: Option<i64>, now: i64) -> bool {
+        if self.max_age_rounds > 0 && newest_round_id - round_id >= self.max_age_rounds as i64 {
+            return false;
+        }
+        if let (true, Some(ts)) = (self.max_age_days > 0, completed_at) {
+            if now - ts > self.max_age_days as i64 * 86_400 {
+                return false;
+            }
+        }
+        true
+    }
+}
+
 /// Check if database is available
 pub fn is_database_available() -> bool {
     env::var("DATABASE_URL").is_ok()
@@ -1649,3 +2314,347 @@ impl Signal {
         )
     }
 }
+
+/// Find bots whose last heartbeat is older than `timeout_secs`
+/// Returns (bot name, seconds since last heartbeat), skipping `exclude` (the caller itself)
+pub fn find_dead_bots(
+    heartbeats: &[(String, i64)],
+    now: i64,
+    timeout_secs: u64,
+    exclude: &str,
+) -> Vec<(String, i64)> {
+    let mut dead: Vec<(String, i64)> = heartbeats.iter()
+        .filter(|(bot, _)| bot != exclude)
+        .map(|(bot, last)| (bot.clone(), now - last))
+        .filter(|(_, age)| *age > timeout_secs as i64)
+        .collect();
+    dead.sort_by(|a, b| b.1.cmp(&a.1));
+    dead
+}
+
+/// Whether `holder` may deploy given the executors already holding leases
+pub fn can_acquire_lease(holders: &[String], holder: &str, max_in_flight: u32) -> bool {
+    holders.iter().any(|h| h == holder) || holders.len() < max_in_flight as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "database")]
+    #[test]
+    fn test_connection_errors_are_database_unavailable() {
+        assert!(matches!(db_error("Failed to get state", sqlx::Error::PoolTimedOut), BotError::DatabaseUnavailable(_)));
+        let refused = std::io::Error::new(std::io::ErrorKind::ConnectionRefused, "refused");
+        assert!(matches!(db_error("Failed to get state", sqlx::Error::Io(refused)), BotError::DatabaseUnavailable(_)));
+        assert!(matches!(db_error("Failed to get state", sqlx::Error::RowNotFound), BotError::Other(_)));
+    }
+
+    #[test]
+    fn test_round_summary_from_view_rows() {
+        let completed = chrono::DateTime::parse_from_rfc3339("2026-01-02T03:04:05Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        let rows: Vec<RoundSummaryRow> = vec![
+            (1002, 25, 3_500_000_000, true, 41, Some(completed)),
+            (1001, 1, 800_000_000, false, 12, None),
+        ];
+
+        let summaries: Vec<RoundSummary> = rows.into_iter().map(RoundSummary::from).collect();
+
+        assert_eq!(summaries[0], RoundSummary {
+            round_id: 1002,
+            winning_square: 25,
+            total_pot: 3_500_000_000,
+            is_motherlode: true,
+            num_deploys: 41,
+            completed_at: Some(completed),
+        });
+        assert_eq!(summaries[1].winning_square, 1);
+        assert_eq!(summaries[1].total_pot, 800_000_000);
+        assert!(!summaries[1].is_motherlode);
+        assert_eq!(summaries[1].completed_at, None);
+    }
+
+    #[test]
+    fn test_extra_executor_deploy_is_blocked() {
+        let max_in_flight = 2;
+        let mut holders: Vec<String> = Vec::new();
+
+        for executor in ["exec-a", "exec-b"] {
+            assert!(can_acquire_lease(&holders, executor, max_in_flight));
+            holders.push(executor.to_string());
+        }
+
+        // Third executor for the same authority and round waits
+        assert!(!can_acquire_lease(&holders, "exec-c", max_in_flight));
+        // A holder retrying its own deploy keeps its slot
+        assert!(can_acquire_lease(&holders, "exec-a", max_in_flight));
+
+        // Once one releases, the next one gets in
+        holders.retain(|h| h != "exec-b");
+        assert!(can_acquire_lease(&holders, "exec-c", max_in_flight));
+    }
+
+    #[test]
+    fn test_signal_type_round_trip() {
+        for signal_type in SignalType::ALL {
+            let name = signal_type.to_string();
+            assert_eq!(name.parse::<SignalType>().unwrap(), signal_type);
+            assert_eq!(SignalType::try_from(name.as_str()).unwrap(), signal_type);
+            // Display must match the serde name so stored and serialized values agree
+            assert_eq!(serde_json::to_value(&signal_type).unwrap(), serde_json::json!(name));
+        }
+        assert!("not_a_signal".parse::<SignalType>().is_err());
+    }
+
+    #[test]
+    fn test_missing_heartbeat_triggers_dead_bot() {
+        let now = 10_000;
+        let heartbeats = vec![
+            ("coordinator".to_string(), now - 1_000),
+            ("miner-bot".to_string(), now - 600),
+            ("monitor-bot".to_string(), now - 20),
+        ];
+        
+        let dead = find_dead_bots(&heartbeats, now, 300, "coordinator");
+        assert_eq!(dead, vec![("miner-bot".to_string(), 600)]);
+        
+        // Everyone is fresh with a generous timeout
+        assert!(find_dead_bots(&heartbeats, now, 900, "coordinator").is_empty());
+    }
+
+    #[test]
+    fn test_history_window_excludes_old_rounds() {
+        let now = 100 * 86_400;
+        // (round_id, completed_at)
+        let rounds = vec![
+            (1000, Some(now - 3_600)),
+            (995, Some(now - 2 * 86_400)),
+            (990, Some(now - 10 * 86_400)),
+            (900, Some(now - 3_600)),
+            (999, None),
+        ];
+        let loaded = |window: HistoryWindow| -> Vec<i64> {
+            rounds.iter()
+                .filter(|(id, ts)| window.contains(*id, 1000, *ts, now))
+                .map(|(id, _)| *id)
+                .collect()
+        };
+        
+        assert_eq!(loaded(HistoryWindow::default()).len(), rounds.len());
+        assert_eq!(loaded(HistoryWindow { max_age_rounds: 50, max_age_days: 0 }), vec![1000, 995, 990, 999]);
+        assert_eq!(loaded(HistoryWindow { max_age_rounds: 0, max_age_days: 7 }), vec![1000, 995, 900, 999]);
+        assert_eq!(loaded(HistoryWindow { max_age_rounds: 50, max_age_days: 7 }), vec![1000, 995, 999]);
+    }
+
+    /// A fresh schema in the Postgres given in TEST_DATABASE_URL
+    /// Every connection's search_path points at it, so tests never see real data
+    /// Tests using it are #[ignore]d; run them with `cargo test --features database -- --ignored`
+    #[cfg(feature = "database")]
+    async fn scratch_db() -> (SharedDb, String) {
+        static NEXT: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(0);
+        let url = env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL must point at a Postgres to run database tests");
+        let schema = format!(
+            "clawdbot_test_{}_{}",
+            std::process::id(),
+            NEXT.fetch_add(1, std::sync::atomic::Ordering::SeqCst)
+        );
+
+        let set_search_path = format!("SET search_path TO {}", schema);
+        let pool = sqlx::postgres::PgPoolOptions::new()
+            .after_connect(move |conn, _| {
+                let set_search_path = set_search_path.clone();
+                Box::pin(async move {
+                    sqlx::Executor::execute(conn, set_search_path.as_str()).await?;
+                    Ok(())
+                })
+            })
+            .connect(&url)
+            .await
+            .unwrap();
+        sqlx::query(&format!("DROP SCHEMA IF EXISTS {} CASCADE", schema)).execute(&pool).await.unwrap();
+        sqlx::query(&format!("CREATE SCHEMA {}", schema)).execute(&pool).await.unwrap();
+
+        let db = SharedDb { config: DbConfig { url }, pool };
+        db.init_schema().await.unwrap();
+        (db, schema)
+    }
+
+    #[cfg(feature = "database")]
+    async fn drop_scratch_db(db: SharedDb, schema: &str) {
+        sqlx::query(&format!("DROP SCHEMA {} CASCADE", schema)).execute(&db.pool).await.unwrap();
+    }
+
+    #[cfg(feature = "database")]
+    #[tokio::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn test_recent_completed_rounds_from_seeded_db() {
+        let (db, schema) = scratch_db().await;
+        let now = chrono::Utc::now().timestamp();
+        let hours_ago = |h: i64| chrono::DateTime::from_timestamp(now - h * 3600, 0);
+        let round = |round_id: i64, winning_square: Option<i16>, pot: i64, motherlode: bool, completed_at| DbRound {
+            round_id,
+            start_slot: None,
+            end_slot: None,
+            winning_square,
+            total_deployed: pot,
+            deployed_squares: vec![0; 25],
+            total_winnings: 0,
+            total_vaulted: 0,
+            motherlode,
+            num_deploys: 12,
+            completed_at,
+        };
+        for r in [
+            round(1001, Some(25), 3_500_000_000, true, hours_ago(1)),
+            round(1002, Some(1), 800_000_000, false, hours_ago(2)),
+            round(1003, Some(7), 1_000_000_000, false, hours_ago(30)),
+            round(1004, Some(3), 500_000_000, false, None), // backfilled
+            round(1005, None, 200_000_000, false, None),    // in progress
+        ] {
+            db.upsert_round(&r).await.unwrap();
+        }
+
+        let rounds = db.get_recent_completed_rounds(10).await.unwrap();
+        assert_eq!(rounds.iter().map(|r| r.round_id).collect::<Vec<_>>(), vec![1004, 1003, 1002, 1001]);
+        assert_eq!(rounds[3], RoundSummary {
+            round_id: 1001,
+            winning_square: 25,
+            total_pot: 3_500_000_000,
+            is_motherlode: true,
+            num_deploys: 12,
+            completed_at: hours_ago(1),
+        });
+        assert_eq!(rounds[0].completed_at, None);
+
+        // 24h totals: 1001 and 1002 only
+        assert_eq!(db.get_completed_round_stats(24).await.unwrap(), CompletedRoundStats {
+            rounds: 2,
+            total_pot: 4_300_000_000,
+            motherlodes: 1,
+        });
+
+        drop_scratch_db(db, &schema).await;
+    }
+
+    /// Executors racing for the same authority's round are serialized by the advisory lock
+    #[cfg(feature = "database")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn test_concurrent_leases_admit_exactly_max_in_flight() {
+        let (db, schema) = scratch_db().await;
+        // Advisory locks are database-wide; keep the key to this run
+        let authority = format!("{}-authority", schema);
+        let max_in_flight = 3;
+
+        let attempts: Vec<_> = (0..=max_in_flight)
+            .map(|i| {
+                let db = db.clone();
+                let authority = authority.clone();
+                tokio::spawn(async move {
+                    db.acquire_deploy_lease(&authority, 500, &format!("executor-{}", i), max_in_flight).await
+                })
+            })
+            .collect();
+        let mut acquired = 0;
+        for attempt in attempts {
+            if attempt.await.unwrap().unwrap() {
+                acquired += 1;
+            }
+        }
+        assert_eq!(acquired, max_in_flight);
+
+        let (leases,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM deploy_leases WHERE authority = $1 AND round_id = 500")
+            .bind(&authority)
+            .fetch_one(&db.pool)
+            .await
+            .unwrap();
+        assert_eq!(leases, max_in_flight as i64);
+
+        drop_scratch_db(db, &schema).await;
+    }
+
+    /// Heartbeats go through `signals`, and the coordinator's dead-bot check reads them back from there
+    #[cfg(feature = "database")]
+    #[tokio::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn test_dead_bot_found_from_stored_heartbeats() {
+        let (db, schema) = scratch_db().await;
+        let heartbeat = |bot: &str| Signal::new(SignalType::Heartbeat, bot, serde_json::json!({}));
+
+        // The miner loops every 10s but only heartbeats every 30s
+        let mut budget = crate::state_store::SignalBudget::new(0, 30);
+        let now = chrono::Utc::now().timestamp();
+        for t in (now - 60..=now).step_by(10) {
+            if budget.heartbeat_due(t) {
+                db.send_signal(&heartbeat("miner-bot")).await.unwrap();
+            }
+        }
+        db.send_signal(&heartbeat("monitor-bot")).await.unwrap();
+        let (sent,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM signals WHERE source_bot = 'miner-bot'")
+            .fetch_one(&db.pool)
+            .await
+            .unwrap();
+        assert_eq!(sent, 3);
+
+        let heartbeats = db.get_last_heartbeats().await.unwrap();
+        assert!(find_dead_bots(&heartbeats, now, 300, "coordinator").is_empty());
+
+        // Then the miner goes quiet for 10 minutes
+        sqlx::query("UPDATE signals SET created_at = NOW() - INTERVAL '10 minutes' WHERE source_bot = 'miner-bot'")
+            .execute(&db.pool)
+            .await
+            .unwrap();
+        let heartbeats = db.get_last_heartbeats().await.unwrap();
+        let dead = find_dead_bots(&heartbeats, now, 300, "coordinator");
+        assert_eq!(dead.len(), 1);
+        assert_eq!(dead[0].0, "miner-bot");
+        assert!(dead[0].1 >= 590, "{:?}", dead);
+
+        drop_scratch_db(db, &schema).await;
+    }
+
+    /// Runs in a scratch schema (see `scratch_db`), so pruning can't touch other rows
+    #[cfg(feature = "database")]
+    #[tokio::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn test_transaction_retention_and_slot_index() {
+        let (db, schema) = scratch_db().await;
+        let base = 1_000_000;
+        for i in 0..5 {
+            db.insert_transaction(&DbTransaction {
+                signature: format!("retention-test-{}-{}", base, i),
+                slot: base + i * 500,
+                block_time: None,
+                instruction_type: "Deploy".to_string(),
+                signer: "RetentionTest".to_string(),
+                round_id: None,
+                amount_lamports: Some(1_000),
+                squares: vec![1],
+                success: true,
+            })
+            .await
+            .unwrap();
+        }
+
+        // Keep 1000 slots behind the newest (base + 2000): base and base + 500 go
+        db.prune_transactions(1_000).await.unwrap();
+        let slots: Vec<i64> = db.get_recent_transactions(10).await.unwrap().iter().map(|t| t.slot).collect();
+        assert_eq!(slots, vec![base + 2_000, base + 1_500, base + 1_000]);
+
+        // The slot-ordered query is answered from the new index
+        let mut conn = db.pool.acquire().await.unwrap();
+        sqlx::query("SET enable_seqscan = off").execute(&mut *conn).await.unwrap();
+        let plan: Vec<(String,)> = sqlx::query_as(&format!("EXPLAIN {}", RECENT_TRANSACTIONS_SQL.replace("$1", "10")))
+            .fetch_all(&mut *conn)
+            .await
+            .unwrap();
+        sqlx::query("RESET enable_seqscan").execute(&mut *conn).await.unwrap();
+        let plan: String = plan.into_iter().map(|(line,)| line).collect::<Vec<_>>().join("\n");
+        assert!(plan.contains("idx_transactions_slot"), "{}", plan);
+        drop(conn);
+
+        drop_scratch_db(db, &schema).await;
+    }
+}
@@ -1,5 +1,9 @@
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use solana_client::rpc_request::{RpcError, RpcResponseErrorData};
 use thiserror::Error;
 
+use crate::blockchain_parser::ORE_PROGRAM_ID;
+
 #[derive(Error, Debug)]
 pub enum BotError {
     #[error("Solana client error: {0}")]
@@ -53,11 +57,299 @@ pub enum BotError {
     #[error("Transaction failed: {0}")]
     TransactionFailed(String),
 
+    #[error("Deploy failed: {0}")]
+    Deploy(#[from] DeployError),
+
     #[error("Rate limit exceeded")]
     RateLimitExceeded,
 
+    /// The database could not be reached (pool exhausted or connection lost)
+    #[error("Database unavailable: {0}")]
+    DatabaseUnavailable(String),
+
     #[error("Other error: {0}")]
     Other(String),
 }
 
 pub type Result<T> = std::result::Result<T, BotError>;
+
+/// Why a deploy transaction was rejected, read from the simulation/transaction logs
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum DeployError {
+    #[error("round already ended")]
+    RoundEnded,
+
+    #[error("insufficient funds")]
+    InsufficientFunds,
+
+    #[error("account not found")]
+    AccountNotFound,
+
+    #[error("blockhash expired")]
+    BlockhashExpired,
+
+    /// The board says the game is paused or stuck
+    #[error("game not accepting deploys: {0}")]
+    GameHalted(String),
+
+    /// Custom program error code we don't have a name for
+    #[error("program error 0x{0:x}")]
+    Program(u32),
+
+    #[error("{0}")]
+    Other(String),
+}
+
+/// What the bot should do about a failed deploy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeployAction {
+    /// Transient, send again
+    Retry,
+    /// Try again with less SOL
+    ReduceAmount,
+    /// Give up on this round
+    Abort,
+    /// Needs the operator (e.g. a missing account); stop deploying until resumed
+    Halt,
+}
+
+impl DeployError {
+    /// Classify from the error message and program logs.
+    ///
+    /// A custom error raised by the ORE or System program is looked up in
+    /// [`PROGRAM_ERROR_CODES`]. Text matching is only applied to the runtime
+    /// message and to log lines from the System and ComputeBudget programs,
+    /// whose wording is stable.
+    pub fn from_logs(message: &str, logs: &[String]) -> Self {
+        if let Some((program, code)) = failed_program(logs) {
+            if let Some(error) = program_error(program, code) {
+                return error;
+            }
+            if program == ORE_PROGRAM_ID {
+                return DeployError::Program(code);
+            }
+        }
+
+        let text = std::iter::once(message)
+            .chain(runtime_log_lines(logs))
+            .collect::<Vec<_>>()
+            .join("\n")
+            .to_lowercase();
+
+        if ["insufficient lamports", "insufficient funds", "insufficientfundsforfee"]
+            .iter()
+            .any(|p| text.contains(p))
+        {
+            DeployError::InsufficientFunds
+        } else if [
+            "accountnotfound",
+            "account not found",
+            "found no record of a prior credit",
+            "requires an initialized account",
+        ]
+        .iter()
+        .any(|p| text.contains(p))
+        {
+            DeployError::AccountNotFound
+        } else if text.contains("blockhash not found") || text.contains("blockhashnotfound") {
+            DeployError::BlockhashExpired
+        } else if let Some(code) = custom_error_code(&text) {
+            DeployError::Program(code)
+        } else {
+            DeployError::Other(message.to_string())
+        }
+    }
+
+    /// Classify a send/confirm failure, using the preflight simulation logs when present
+    pub fn from_client_error(error: &ClientError) -> Self {
+        let logs = match error.kind() {
+            ClientErrorKind::RpcError(RpcError::RpcResponseError {
+                data: RpcResponseErrorData::SendTransactionPreflightFailure(sim),
+                ..
+            }) => sim.logs.clone().unwrap_or_default(),
+            _ => Vec::new(),
+        };
+        let message = match error.get_transaction_error() {
+            Some(tx_error) => format!("{:?}: {}", tx_error, error),
+            None => error.to_string(),
+        };
+        Self::from_logs(&message, &logs)
+    }
+
+    pub fn action(&self) -> DeployAction {
+        match self {
+            DeployError::InsufficientFunds => DeployAction::ReduceAmount,
+            DeployError::BlockhashExpired | DeployError::Other(_) => DeployAction::Retry,
+            DeployError::RoundEnded | DeployError::GameHalted(_) | DeployError::Program(_) => DeployAction::Abort,
+            DeployError::AccountNotFound => DeployAction::Halt,
+        }
+    }
+}
+
+const SYSTEM_PROGRAM_ID: &str = "11111111111111111111111111111111";
+const COMPUTE_BUDGET_PROGRAM_ID: &str = "ComputeBudget111111111111111111111111111111";
+
+/// Custom program error codes we act on, by program
+const PROGRAM_ERROR_CODES: &[(&str, u32, DeployError)] = &[
+    // Deploy after the round's end slot
+    (ORE_PROGRAM_ID, 0x3, DeployError::RoundEnded),
+    // SystemError::ResultWithNegativeLamports - a transfer the payer can't cover
+    (SYSTEM_PROGRAM_ID, 0x1, DeployError::InsufficientFunds),
+];
+
+fn program_error(program: &str, code: u32) -> Option<DeployError> {
+    PROGRAM_ERROR_CODES
+        .iter()
+        .find(|(p, c, _)| *p == program && *c == code)
+        .map(|(_, _, e)| e.clone())
+}
+
+/// Program id and code from the first "Program <id> failed: custom program error: 0x.." line
+fn failed_program(logs: &[String]) -> Option<(&str, u32)> {
+    logs.iter().find_map(|line| {
+        let rest = line.strip_prefix("Program ")?;
+        let (program, rest) = rest.split_once(' ')?;
+        let rest = rest.strip_prefix("failed: ")?;
+        Some((program, custom_error_code(rest)?))
+    })
+}
+
+/// Log lines emitted while the System or ComputeBudget program is executing,
+/// plus lines outside any program (runtime messages)
+fn runtime_log_lines(logs: &[String]) -> impl Iterator<Item = &str> {
+    let mut stack: Vec<&str> = Vec::new();
+    logs.iter().filter_map(move |line| {
+        let mut words = line.split_whitespace();
+        if words.next() == Some("Program") {
+            if let (Some(program), Some(event)) = (words.next(), words.next()) {
+                match event {
+                    "invoke" => stack.push(program),
+                    "success" | "failed:" => {
+                        stack.pop();
+                    }
+                    _ => {}
+                }
+            }
+        }
+        match stack.last() {
+            None => Some(line.as_str()),
+            Some(&p) if p == SYSTEM_PROGRAM_ID || p == COMPUTE_BUDGET_PROGRAM_ID => Some(line.as_str()),
+            Some(_) => None,
+        }
+    })
+}
+
+/// Code from "custom program error: 0x1f"
+fn custom_error_code(text: &str) -> Option<u32> {
+    let rest = &text[text.find("custom program error: 0x")? + "custom program error: 0x".len()..];
+    let hex: String = rest.chars().take_while(|c| c.is_ascii_hexdigit()).collect();
+    u32::from_str_radix(&hex, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn logs(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn test_deploy_error_from_program_logs() {
+        let cases = [
+            (
+                logs(&[
+                    "Program oreV3EG1i9BEgiAJ8b177Z2S2rMarzak4NMv1kULvWv invoke [1]",
+                    "Program log: Round has ended",
+                    "Program oreV3EG1i9BEgiAJ8b177Z2S2rMarzak4NMv1kULvWv failed: custom program error: 0x3",
+                ]),
+                DeployError::RoundEnded,
+            ),
+            (
+                logs(&[
+                    "Program 11111111111111111111111111111111 invoke [2]",
+                    "Transfer: insufficient lamports 1200000, need 40000000",
+                    "Program 11111111111111111111111111111111 failed: custom program error: 0x1",
+                ]),
+                DeployError::InsufficientFunds,
+            ),
+            (
+                logs(&["Program log: Instruction: Deploy", "Program log: AccountNotFound"]),
+                DeployError::AccountNotFound,
+            ),
+            (
+                logs(&[
+                    "Program oreV3EG1i9BEgiAJ8b177Z2S2rMarzak4NMv1kULvWv invoke [1]",
+                    "Program oreV3EG1i9BEgiAJ8b177Z2S2rMarzak4NMv1kULvWv failed: custom program error: 0x1f",
+                ]),
+                DeployError::Program(0x1f),
+            ),
+        ];
+        for (lines, expected) in cases {
+            assert_eq!(DeployError::from_logs("Transaction simulation failed", &lines), expected, "{:?}", lines);
+        }
+
+        // No logs: fall back to the error message
+        assert_eq!(
+            DeployError::from_logs("Attempt to debit an account but found no record of a prior credit.", &[]),
+            DeployError::AccountNotFound
+        );
+        assert_eq!(DeployError::from_logs("Blockhash not found", &[]), DeployError::BlockhashExpired);
+        assert_eq!(
+            DeployError::from_logs("connection reset", &[]),
+            DeployError::Other("connection reset".to_string())
+        );
+
+        assert_eq!(DeployError::RoundEnded.action(), DeployAction::Abort);
+        assert_eq!(DeployError::InsufficientFunds.action(), DeployAction::ReduceAmount);
+        assert_eq!(DeployError::BlockhashExpired.action(), DeployAction::Retry);
+        assert_eq!(DeployError::AccountNotFound.action(), DeployAction::Halt);
+    }
+
+    #[test]
+    fn test_system_error_code_without_log_text() {
+        // The System transfer failure is read from its code even when the log wording differs
+        let lines = logs(&[
+            "Program oreV3EG1i9BEgiAJ8b177Z2S2rMarzak4NMv1kULvWv invoke [1]",
+            "Program 11111111111111111111111111111111 invoke [2]",
+            "Program 11111111111111111111111111111111 failed: custom program error: 0x1",
+            "Program oreV3EG1i9BEgiAJ8b177Z2S2rMarzak4NMv1kULvWv failed: custom program error: 0x1",
+        ]);
+        assert_eq!(DeployError::from_logs("Transaction simulation failed", &lines), DeployError::InsufficientFunds);
+
+        // Other System codes are not guessed at
+        let lines = logs(&[
+            "Program 11111111111111111111111111111111 invoke [1]",
+            "Program 11111111111111111111111111111111 failed: custom program error: 0x0",
+        ]);
+        assert_eq!(DeployError::from_logs("Transaction simulation failed", &lines), DeployError::Program(0x0));
+    }
+
+    #[test]
+    fn test_ore_errors_use_code_table_not_log_text() {
+        // ORE's own log text is ignored; only the code decides
+        let lines = logs(&[
+            "Program oreV3EG1i9BEgiAJ8b177Z2S2rMarzak4NMv1kULvWv invoke [1]",
+            "Program log: Round has ended, insufficient funds",
+            "Program oreV3EG1i9BEgiAJ8b177Z2S2rMarzak4NMv1kULvWv failed: custom program error: 0x5",
+        ]);
+        assert_eq!(DeployError::from_logs("Transaction simulation failed", &lines), DeployError::Program(0x5));
+
+        // A System transfer failing inside the ORE instruction is still read from its log text
+        let lines = logs(&[
+            "Program oreV3EG1i9BEgiAJ8b177Z2S2rMarzak4NMv1kULvWv invoke [1]",
+            "Program 11111111111111111111111111111111 invoke [2]",
+            "Transfer: insufficient lamports 1200000, need 40000000",
+            "Program 11111111111111111111111111111111 failed: custom program error: 0x1",
+            "Program oreV3EG1i9BEgiAJ8b177Z2S2rMarzak4NMv1kULvWv failed: custom program error: 0x1",
+        ]);
+        assert_eq!(DeployError::from_logs("Transaction simulation failed", &lines), DeployError::InsufficientFunds);
+
+        // Same code from another program is not read as an ORE error
+        let lines = logs(&[
+            "Program Other111111111111111111111111111111111111 invoke [1]",
+            "Program Other111111111111111111111111111111111111 failed: custom program error: 0x3",
+        ]);
+        assert_eq!(DeployError::from_logs("Transaction simulation failed", &lines), DeployError::Program(0x3));
+    }
+}
@@ -1,3 +1,4 @@
+use crate::units::{lamports_to_sol, raw_ore_to_ore};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -23,9 +24,6 @@ use std::collections::HashMap;
 /// NOT tracked: Non-ORE program activity, other Solana programs
 /// ═══════════════════════════════════════════════════════════════════════════════
 
-pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
-pub const ORE_DECIMALS: f64 = 1e11;
-
 /// ORE Round Win Record
 /// Captures every winning event from an ORE program Reset transaction
 /// The winner is the wallet whose Deploy landed on the winning square
@@ -52,6 +50,201 @@ pub struct WinRecord {
     pub timestamp: Option<i64>,
 }
 
+impl WinRecord {
+    /// Build a WinRecord from a row returned by `SharedDb::load_wins`
+    /// Fields not persisted in win_records are left at their defaults
+    pub fn from_db_json(value: &serde_json::Value) -> Option<Self> {
+        Some(Self {
+            round_id: value["round_id"].as_u64()?,
+            winner_address: value["winner"].as_str()?.to_string(),
+            winning_square: value["winning_square"].as_u64()? as u8,
+            amount_bet: value["amount_bet"].as_u64().unwrap_or(0),
+            amount_won: value["amount_won"].as_u64().unwrap_or(0),
+            squares_bet: value["squares_bet"].as_array()
+                .map(|sq| sq.iter().filter_map(|s| s.as_u64().map(|n| n as u8)).collect())
+                .unwrap_or_default(),
+            num_squares: value["num_squares"].as_u64().unwrap_or(0) as u8,
+            total_round_sol: value["total_round_sol"].as_u64().unwrap_or(0),
+            num_deployers: value["num_deployers"].as_u64().unwrap_or(0) as u32,
+            is_motherlode: value["is_motherlode"].as_bool().unwrap_or(false),
+            is_full_ore: value["is_full_ore"].as_bool().unwrap_or(false),
+            ore_earned: value["ore_earned"].as_f64().unwrap_or(0.0),
+            competition_on_square: 0,
+            winner_share_pct: 0.0,
+            slot: 0,
+            timestamp: None,
+        })
+    }
+}
+
+/// A winner's slice of the winning square
+/// Tracked deploys only carry the player's total bet, so the stake on the
+/// winning square is the total split evenly across the squares they bet
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WinnerAllocation {
+    /// Lamports the winner had on the winning square
+    pub stake_on_square: u64,
+    /// Winner's fraction of the winning square (0.0 - 1.0)
+    pub share: f64,
+    /// Winner's cut of the SOL deployed on losing squares
+    pub amount_won: u64,
+}
+
+impl WinnerAllocation {
+    pub fn compute(
+        amount_bet: u64,
+        num_squares: usize,
+        competition_on_square: u64,
+        total_round_sol: u64,
+    ) -> Self {
+        let stake_on_square = amount_bet / num_squares.max(1) as u64;
+        let share = if competition_on_square > 0 {
+            (stake_on_square as f64 / competition_on_square as f64).min(1.0)
+        } else {
+            1.0
+        };
+        let losing_pot = total_round_sol.saturating_sub(competition_on_square);
+        let amount_won = (losing_pot as f64 * share) as u64;
+
+        Self {
+            stake_on_square,
+            share,
+            amount_won,
+        }
+    }
+}
+
+/// How far the decision-time `expected_ore` is from what rounds paid out
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct OreEstimateError {
+    pub samples: usize,
+    pub mean_expected: f64,
+    pub mean_realized: f64,
+    /// Mean |expected - realized|
+    pub mean_abs_error: f64,
+    /// Mean (expected - realized); positive means the model overestimates
+    pub bias: f64,
+    pub rmse: f64,
+}
+
+impl OreEstimateError {
+    /// From (expected, realized) pairs, one per played round
+    pub fn from_pairs(pairs: &[(f64, f64)]) -> Self {
+        if pairs.is_empty() {
+            return Self::default();
+        }
+        let n = pairs.len() as f64;
+        let mean = |f: &dyn Fn(&(f64, f64)) -> f64| pairs.iter().map(f).sum::<f64>() / n;
+
+        Self {
+            samples: pairs.len(),
+            mean_expected: mean(&|p| p.0),
+            mean_realized: mean(&|p| p.1),
+            mean_abs_error: mean(&|p| (p.0 - p.1).abs()),
+            bias: mean(&|p| p.0 - p.1),
+            rmse: mean(&|p| (p.0 - p.1).powi(2)).sqrt(),
+        }
+    }
+}
+
+/// Upper bounds (SOL) of the round pot bins; bigger pots land in the last, open bin
+pub const POT_BINS_SOL: [f64; 7] = [0.5, 1.0, 2.0, 5.0, 10.0, 25.0, 50.0];
+
+/// Upper bounds of the deployer-count bins
+pub const DEPLOYER_BINS: [f64; 6] = [5.0, 10.0, 25.0, 50.0, 100.0, 250.0];
+
+/// One histogram bin covering [lower, upper); `upper` is None for the open last bin
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HistogramBin {
+    pub lower: f64,
+    pub upper: Option<f64>,
+    pub count: u64,
+}
+
+fn histogram(values: &[f64], bounds: &[f64]) -> Vec<HistogramBin> {
+    let mut bins: Vec<HistogramBin> = std::iter::once(0.0)
+        .chain(bounds.iter().copied())
+        .zip(bounds.iter().copied().map(Some).chain(std::iter::once(None)))
+        .map(|(lower, upper)| HistogramBin { lower, upper, count: 0 })
+        .collect();
+    for &v in values {
+        let bin = bounds.iter().position(|&b| v < b).unwrap_or(bounds.len());
+        bins[bin].count += 1;
+    }
+    bins
+}
+
+fn median(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let mid = sorted.len() / 2;
+    if sorted.len() % 2 == 0 {
+        (sorted[mid - 1] + sorted[mid]) / 2.0
+    } else {
+        sorted[mid]
+    }
+}
+
+/// Competition regime over recent rounds: how big the pots were and how many
+/// wallets played, from `round_conditions`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CompetitionDistribution {
+    pub rounds: usize,
+    pub total_deployed_sol: Vec<HistogramBin>,
+    pub num_deployers: Vec<HistogramBin>,
+    pub median_total_deployed_sol: f64,
+    pub median_num_deployers: f64,
+}
+
+impl CompetitionDistribution {
+    /// From (total_deployed lamports, num_deployers), one per round
+    pub fn from_rounds(rounds: &[(i64, i32)]) -> Self {
+        let pots: Vec<f64> = rounds.iter().map(|&(total, _)| lamports_to_sol(total.max(0) as u64)).collect();
+        let deployers: Vec<f64> = rounds.iter().map(|&(_, n)| n.max(0) as f64).collect();
+
+        Self {
+            rounds: rounds.len(),
+            total_deployed_sol: histogram(&pots, &POT_BINS_SOL),
+            num_deployers: histogram(&deployers, &DEPLOYER_BINS),
+            median_total_deployed_sol: median(&pots),
+            median_num_deployers: median(&deployers),
+        }
+    }
+}
+
+/// ORE a round credited to our miner account: the change in `Miner.rewards_ore`
+/// across the round, adding back what we claimed in between
+pub fn realized_ore(rewards_before: u64, rewards_after: u64, claimed_since: u64) -> f64 {
+    raw_ore_to_ore((rewards_after + claimed_since).saturating_sub(rewards_before))
+}
+
+/// Tracks `Miner.checkpoint_id` across reads. A round's ORE only reaches
+/// `rewards_ore` when the miner is checkpointed for it (usually by our next
+/// deploy), so a read right after the round ends still sees the old value
+#[derive(Debug, Default)]
+pub struct CheckpointTracker {
+    /// (checkpoint_id, rewards_ore, claimed ORE total) at the last read
+    last: Option<(u64, u64, u64)>,
+}
+
+impl CheckpointTracker {
+    /// Feed a miner read; returns the newly checkpointed round and the ORE it credited
+    pub fn observe(&mut self, checkpoint_id: u64, rewards_ore: u64, claimed_ore_total: u64) -> Option<(u64, f64)> {
+        let credited = match self.last {
+            Some((last_id, last_rewards, last_claimed)) if checkpoint_id > last_id => Some((
+                checkpoint_id,
+                realized_ore(last_rewards, rewards_ore, claimed_ore_total.saturating_sub(last_claimed)),
+            )),
+            _ => None,
+        };
+        self.last = Some((checkpoint_id, rewards_ore, claimed_ore_total));
+        credited
+    }
+}
+
 /// ORE Player Profile - Built from on-chain ORE program history
 /// Each profile represents a wallet address that has interacted with ORE program
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -111,6 +304,42 @@ pub struct DetectedStrategy {
     pub examples: Vec<String>,      // Player addresses using this
 }
 
+/// Limits applied when pruning persisted detected strategies
+#[derive(Debug, Clone, Copy)]
+pub struct StrategyPrunePolicy {
+    /// Strategies not refreshed within this many seconds are stale
+    pub max_age_secs: i64,
+    /// Strategies below this confidence are dropped
+    pub min_confidence: f64,
+    /// At most this many strategies are kept (highest confidence first)
+    pub max_count: usize,
+}
+
+/// Pick which stored strategies to drop
+/// `stored` is (name, confidence, updated_at as unix seconds)
+pub fn strategies_to_prune(
+    stored: &[(String, f64, i64)],
+    now: i64,
+    policy: &StrategyPrunePolicy,
+) -> Vec<String> {
+    let mut pruned = Vec::new();
+    let mut kept: Vec<&(String, f64, i64)> = Vec::new();
+
+    for entry in stored {
+        let (name, confidence, updated_at) = entry;
+        if now - updated_at > policy.max_age_secs || *confidence < policy.min_confidence {
+            pruned.push(name.clone());
+        } else {
+            kept.push(entry);
+        }
+    }
+
+    kept.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    pruned.extend(kept.iter().skip(policy.max_count).map(|(name, _, _)| name.clone()));
+
+    pruned
+}
+
 /// Main Learning Engine
 pub struct LearningEngine {
     // All wins we've observed
@@ -163,6 +392,15 @@ impl LearningEngine {
         }
     }
 
+    /// Run a fresh detection pass over a batch of historical wins
+    pub fn detect_from_wins(&mut self, wins: Vec<WinRecord>) -> &[DetectedStrategy] {
+        for win in wins {
+            self.record_win(win);
+        }
+        self.analyze_and_detect_strategies();
+        &self.detected_strategies
+    }
+
     /// Record a player's deploy (even if they don't win)
     pub fn record_deploy(
         &mut self,
@@ -218,7 +456,7 @@ impl LearningEngine {
         player.avg_round_competition = 
             (((player.avg_round_competition as f64 * (n - 1.0)) + round_total_sol as f64) / n) as u64;
         player.prefers_low_competition = 
-            (player.avg_round_competition as f64 / LAMPORTS_PER_SOL as f64) < 5.0;
+            lamports_to_sol(player.avg_round_competition) < 5.0;
         
         // Track motherlode behavior
         if is_motherlode {
@@ -274,7 +512,7 @@ impl LearningEngine {
         }
         
         // Update ORE per SOL
-        let total_sol = player.total_deployed as f64 / LAMPORTS_PER_SOL as f64;
+        let total_sol = lamports_to_sol(player.total_deployed);
         if total_sol > 0.0 {
             player.ore_per_sol = player.total_ore_earned / total_sol;
         }
@@ -385,7 +623,7 @@ impl LearningEngine {
         
         if motherlode_wins.len() >= 5 {
             let avg_bet: f64 = motherlode_wins.iter()
-                .map(|w| w.amount_bet as f64 / LAMPORTS_PER_SOL as f64)
+                .map(|w| lamports_to_sol(w.amount_bet))
                 .sum::<f64>() / motherlode_wins.len() as f64;
             
             let avg_squares: f64 = motherlode_wins.iter()
@@ -421,7 +659,7 @@ impl LearningEngine {
     fn detect_low_competition_strategy(&mut self) {
         // Wins where total round SOL < 2
         let low_comp_wins: Vec<_> = self.win_history.iter()
-            .filter(|w| (w.total_round_sol as f64 / LAMPORTS_PER_SOL as f64) < 2.0)
+            .filter(|w| lamports_to_sol(w.total_round_sol) < 2.0)
             .collect();
         
         if low_comp_wins.len() >= self.min_samples_for_strategy as usize {
@@ -467,11 +705,11 @@ impl LearningEngine {
                 .sum::<f64>() / full_ore_wins.len() as f64;
             
             let avg_competition: f64 = full_ore_wins.iter()
-                .map(|w| w.total_round_sol as f64 / LAMPORTS_PER_SOL as f64)
+                .map(|w| lamports_to_sol(w.total_round_sol))
                 .sum::<f64>() / full_ore_wins.len() as f64;
             
             let avg_bet: f64 = full_ore_wins.iter()
-                .map(|w| w.amount_bet as f64 / LAMPORTS_PER_SOL as f64)
+                .map(|w| lamports_to_sol(w.amount_bet))
                 .sum::<f64>() / full_ore_wins.len() as f64;
             
             // Find most common square count
@@ -534,7 +772,7 @@ impl LearningEngine {
                 avg_roi: player.roi,
                 avg_ore_per_round: player.total_ore_earned / player.wins.max(1) as f64,
                 square_count: player.preferred_square_count,
-                bet_size_sol: player.avg_bet_size as f64 / LAMPORTS_PER_SOL as f64,
+                bet_size_sol: lamports_to_sol(player.avg_bet_size),
                 target_competition: if player.prefers_low_competition { "Low" } else { "Any" }.to_string(),
                 preferred_squares: player.favorite_squares.clone(),
                 play_motherlode: player.plays_motherlode,
@@ -640,4 +878,148 @@ mod tests {
         assert_eq!(engine.full_ore_wins_tracked, 1);
         assert!(engine.players.contains_key("ABC123"));
     }
+
+    #[test]
+    fn test_detect_from_db_wins() {
+        let wins: Vec<WinRecord> = (0..25)
+            .map(|i| serde_json::json!({
+                "round_id": 2000 + i,
+                "winner": format!("Winner{:04}", i),
+                "winning_square": 7,
+                "amount_bet": 10_000_000,
+                "amount_won": 30_000_000,
+                "squares_bet": [7, 8],
+                "num_squares": 2,
+                "total_round_sol": 400_000_000,
+                "num_deployers": 6,
+                "is_motherlode": false,
+                "is_full_ore": true,
+                "ore_earned": 1.0,
+            }))
+            .filter_map(|v| WinRecord::from_db_json(&v))
+            .collect();
+        assert_eq!(wins.len(), 25);
+
+        let mut engine = LearningEngine::new();
+        let strategies = engine.detect_from_wins(wins);
+
+        assert!(strategies.iter().any(|s| s.name == "Low Square Focus"));
+        assert!(strategies.iter().any(|s| s.name == "Full ORE Winner"));
+    }
+
+    #[test]
+    fn test_prune_stale_and_weak_strategies() {
+        let now = 1_000_000;
+        let hour = 3600;
+        let stored = vec![
+            ("Fresh Strong".to_string(), 0.9, now - hour),
+            ("Fresh Ok".to_string(), 0.6, now - 2 * hour),
+            ("Fresh Weakest".to_string(), 0.4, now - hour),
+            ("Stale".to_string(), 0.95, now - 100 * hour),
+            ("Low Confidence".to_string(), 0.1, now),
+        ];
+        let policy = StrategyPrunePolicy {
+            max_age_secs: 48 * hour,
+            min_confidence: 0.2,
+            max_count: 2,
+        };
+
+        let mut pruned = strategies_to_prune(&stored, now, &policy);
+        pruned.sort();
+
+        assert_eq!(pruned, vec!["Fresh Weakest", "Low Confidence", "Stale"]);
+    }
+
+    #[test]
+    fn test_winner_allocation_multi_square() {
+        // 0.05 SOL spread over 5 squares = 0.01 SOL on the winning square
+        let allocation = WinnerAllocation::compute(
+            50_000_000,
+            5,
+            40_000_000,  // 0.04 SOL on the winning square in total
+            500_000_000, // 0.5 SOL in the round
+        );
+
+        assert_eq!(allocation.stake_on_square, 10_000_000);
+        assert!((allocation.share - 0.25).abs() < 1e-9);
+        assert_eq!(allocation.amount_won, 115_000_000);
+    }
+
+    #[test]
+    fn test_winner_allocation_single_square() {
+        let allocation = WinnerAllocation::compute(10_000_000, 1, 10_000_000, 100_000_000);
+
+        assert_eq!(allocation.stake_on_square, 10_000_000);
+        assert!((allocation.share - 1.0).abs() < 1e-9);
+        assert_eq!(allocation.amount_won, 90_000_000);
+    }
+
+    #[test]
+    fn test_ore_estimate_error_metrics() {
+        // Model says 0.2 every round; we won one round in four for 0.4 ORE
+        let pairs = [(0.2, 0.0), (0.2, 0.4), (0.2, 0.0), (0.2, 0.0)];
+        let err = OreEstimateError::from_pairs(&pairs);
+
+        assert_eq!(err.samples, 4);
+        assert!((err.mean_expected - 0.2).abs() < 1e-9);
+        assert!((err.mean_realized - 0.1).abs() < 1e-9);
+        assert!((err.mean_abs_error - 0.2).abs() < 1e-9);
+        assert!((err.bias - 0.1).abs() < 1e-9, "overestimates by 0.1 on average");
+        assert!((err.rmse - 0.2).abs() < 1e-9);
+
+        assert_eq!(OreEstimateError::from_pairs(&[]).samples, 0);
+
+        // 0.25 ORE credited, with 1 ORE claimed during the round
+        let ore = crate::units::RAW_PER_ORE;
+        assert!((realized_ore(2 * ore, 2 * ore + ore / 4, 0) - 0.25).abs() < 1e-9);
+        assert!((realized_ore(2 * ore, ore + ore / 4, ore) - 0.25).abs() < 1e-9);
+        assert_eq!(realized_ore(2 * ore, 2 * ore, 0), 0.0);
+    }
+
+    #[test]
+    fn test_round_credited_at_checkpoint() {
+        let ore = crate::units::RAW_PER_ORE;
+        let mut tracker = CheckpointTracker::default();
+
+        // Round 10 ended but isn't checkpointed yet: nothing to credit
+        assert_eq!(tracker.observe(9, 2 * ore, 0), None);
+        assert_eq!(tracker.observe(9, 2 * ore, 0), None);
+
+        // We claim 1 ORE, then our round 11 deploy checkpoints round 10
+        assert_eq!(tracker.observe(9, ore, ore), None);
+        let (round_id, realized) = tracker.observe(10, ore + ore / 2, ore).unwrap();
+        assert_eq!(round_id, 10);
+        assert!((realized - 0.5).abs() < 1e-9);
+
+        // Same checkpoint on the next read: already credited
+        assert_eq!(tracker.observe(10, ore + ore / 2, ore), None);
+    }
+
+    #[test]
+    fn test_competition_distribution_histograms() {
+        // (total_deployed lamports, num_deployers) as stored in round_conditions
+        let rounds = [
+            (300_000_000, 3),       // 0.3 SOL
+            (1_500_000_000, 12),    // 1.5 SOL
+            (1_800_000_000, 9),     // 1.8 SOL
+            (4_000_000_000, 40),    // 4 SOL
+            (60_000_000_000, 300),  // 60 SOL
+        ];
+        let dist = CompetitionDistribution::from_rounds(&rounds);
+
+        assert_eq!(dist.rounds, 5);
+        let pot_counts: Vec<u64> = dist.total_deployed_sol.iter().map(|b| b.count).collect();
+        assert_eq!(pot_counts, vec![1, 0, 2, 1, 0, 0, 0, 1]);
+        let deployer_counts: Vec<u64> = dist.num_deployers.iter().map(|b| b.count).collect();
+        assert_eq!(deployer_counts, vec![1, 1, 1, 1, 0, 0, 1]);
+
+        assert_eq!(dist.total_deployed_sol[2], HistogramBin { lower: 1.0, upper: Some(2.0), count: 2 });
+        assert_eq!(dist.num_deployers[6], HistogramBin { lower: 250.0, upper: None, count: 1 });
+        assert!((dist.median_total_deployed_sol - 1.8).abs() < 1e-9);
+        assert_eq!(dist.median_num_deployers, 12.0);
+
+        let empty = CompetitionDistribution::from_rounds(&[]);
+        assert_eq!(empty.rounds, 0);
+        assert!(empty.total_deployed_sol.iter().all(|b| b.count == 0));
+    }
 }
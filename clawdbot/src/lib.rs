@@ -5,15 +5,27 @@ pub mod error;
 pub mod strategy;
 pub mod strategies;
 pub mod analytics;
+pub mod backfill;
+pub mod backtest;
 pub mod monitor;
 pub mod utils;
 pub mod ore_round;
 pub mod blockchain_parser;
+pub mod board_trend;
 pub mod db;
 pub mod ore_strategy;
 pub mod learning_engine;
 pub mod ai_advisor;
 pub mod ore_stats;
+pub mod progress;
+pub mod request_metrics;
+pub mod round_lifecycle;
+pub mod round_phase;
+pub mod state_store;
+pub mod treasury_alerts;
+pub mod units;
+pub mod webhook;
+pub mod writer;
 
 pub use bot::*;
 pub use client::*;
@@ -3,6 +3,7 @@ use crate::{
     client::OreClient,
     config::MonitorConfig,
     error::Result,
+    units::lamports_to_sol,
 };
 use colored::*;
 use log::{info, warn};
@@ -100,8 +101,8 @@ impl MonitorBot {
             return Ok(());
         }
 
-        let balance_sol = balance as f64 / 1_000_000_000.0;
-        let last_balance_sol = *last_balance as f64 / 1_000_000_000.0;
+        let balance_sol = lamports_to_sol(balance);
+        let last_balance_sol = lamports_to_sol(*last_balance);
 
         if balance != *last_balance {
             let diff = balance_sol - last_balance_sol;
@@ -1,5 +1,7 @@
 use crate::blockchain_parser::BlockchainParser;
 use crate::error::{BotError, Result};
+use crate::ore_strategy::bet_for_target_share;
+use crate::units::{lamports_to_sol, raw_ore_to_ore};
 use log::{debug, info, warn};
 use ore_api::state::{Board, Miner, Round, Treasury};
 use serde::{Deserialize, Serialize};
@@ -27,7 +29,6 @@ use tokio::sync::RwLock;
 
 pub const ORE_PROGRAM_ID: &str = "oreV3EG1i9BEgiAJ8b177Z2S2rMarzak4NMv1kULvWv";
 pub const ORE_MINT: &str = "oreoU2P8bN6jkk3jbaiVxYnG1dCXcYxwhwyK9jSybcp";
-pub const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
 
 /// Live round data for the 5x5 grid
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -54,9 +55,9 @@ pub struct LiveRoundData {
     pub top_miner: Option<String>,
     pub top_miner_reward: Option<f64>,
     
-    /// Motherlode
+    /// Motherlode pool: raw ORE (11 decimals) and ORE
     pub motherlode_lamports: u64,
-    pub motherlode_sol: f64,
+    pub motherlode_ore: f64,
 }
 
 /// Data for a single square in the 5x5 grid
@@ -90,8 +91,9 @@ pub struct RoundHistory {
 pub struct ProtocolStats {
     pub treasury_balance_lamports: u64,
     pub treasury_balance_sol: f64,
+    /// Raw ORE (11 decimals)
     pub motherlode_lamports: u64,
-    pub motherlode_sol: f64,
+    pub motherlode_ore: f64,
     pub total_staked_ore: u64,
     pub total_refined_ore: u64,
     pub total_unclaimed_ore: u64,
@@ -259,7 +261,7 @@ impl OreStatsService {
                 square_num: (i + 1) as u8,
                 index: i as u8,
                 deployed_lamports: deployed,
-                deployed_sol: deployed as f64 / LAMPORTS_PER_SOL,
+                deployed_sol: lamports_to_sol(deployed),
                 miner_count: miners,
                 is_winning: false, // Will be set later for completed rounds
                 percentage_of_total: 0.0, // Calculate after sum
@@ -305,14 +307,14 @@ impl OreStatsService {
             is_intermission,
             squares,
             total_deployed_lamports: total_deployed,
-            total_deployed_sol: total_deployed as f64 / LAMPORTS_PER_SOL,
+            total_deployed_sol: lamports_to_sol(total_deployed),
             total_miners,
             total_vaulted_lamports: round.total_vaulted,
-            total_vaulted_sol: round.total_vaulted as f64 / LAMPORTS_PER_SOL,
+            total_vaulted_sol: lamports_to_sol(round.total_vaulted),
             top_miner,
-            top_miner_reward: Some(round.top_miner_reward as f64 / 1e11), // ORE has 11 decimals
+            top_miner_reward: Some(raw_ore_to_ore(round.top_miner_reward)),
             motherlode_lamports: round.motherlode,
-            motherlode_sol: round.motherlode as f64 / LAMPORTS_PER_SOL,
+            motherlode_ore: raw_ore_to_ore(round.motherlode),
         })
     }
 
@@ -322,9 +324,9 @@ impl OreStatsService {
         
         Ok(ProtocolStats {
             treasury_balance_lamports: treasury.balance,
-            treasury_balance_sol: treasury.balance as f64 / LAMPORTS_PER_SOL,
+            treasury_balance_sol: lamports_to_sol(treasury.balance),
             motherlode_lamports: treasury.motherlode,
-            motherlode_sol: treasury.motherlode as f64 / LAMPORTS_PER_SOL,
+            motherlode_ore: raw_ore_to_ore(treasury.motherlode),
             total_staked_ore: treasury.total_staked,
             total_refined_ore: treasury.total_refined,
             total_unclaimed_ore: treasury.total_unclaimed,
@@ -371,13 +373,13 @@ impl OreStatsService {
                     
                     let round_history = RoundHistory {
                         round_id,
-                        total_deployed_sol: round.total_deployed as f64 / LAMPORTS_PER_SOL,
-                        total_vaulted_sol: round.total_vaulted as f64 / LAMPORTS_PER_SOL,
+                        total_deployed_sol: lamports_to_sol(round.total_deployed),
+                        total_vaulted_sol: lamports_to_sol(round.total_vaulted),
                         total_miners: round.total_miners,
                         winning_square,
                         is_motherlode,
                         top_miner: round.top_miner.to_string(),
-                        top_miner_reward_ore: round.top_miner_reward as f64 / 1e11,
+                        top_miner_reward_ore: raw_ore_to_ore(round.top_miner_reward),
                         timestamp: None, // Would need block time lookup
                     };
                     
@@ -462,7 +464,8 @@ impl OreStatsService {
     }
     
     /// Get stats formatted for bot decision making
-    pub fn get_bot_recommendations(&self) -> Result<BotRecommendations> {
+    /// `target_share` is the winner share each recommended square's bet size aims for
+    pub fn get_bot_recommendations(&self, target_share: f64) -> Result<BotRecommendations> {
         let live = self.get_live_round()?;
         let analysis = self.analyze_squares(100)?;
         
@@ -499,17 +502,20 @@ impl OreStatsService {
         
         scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
         let recommended: Vec<u8> = scored.iter().take(5).map(|(sq, _)| *sq).collect();
+        let square_bets = recommended_bets(&live.squares, &recommended, target_share);
         
         Ok(BotRecommendations {
             round_id: live.round_id,
             time_remaining_secs: live.time_remaining_secs,
             is_intermission: live.is_intermission,
             recommended_squares: recommended,
+            target_share,
+            square_bets,
             hot_squares,
             underweight_squares: underweight,
             total_deployed: live.total_deployed_sol,
             total_miners: live.total_miners,
-            motherlode: live.motherlode_sol,
+            motherlode_ore: live.motherlode_ore,
         })
     }
 }
@@ -521,11 +527,40 @@ pub struct BotRecommendations {
     pub time_remaining_secs: u64,
     pub is_intermission: bool,
     pub recommended_squares: Vec<u8>,
+    pub target_share: f64,
+    /// Bet for `target_share` on each recommended square, same order
+    pub square_bets: Vec<SquareBet>,
     pub hot_squares: Vec<u8>,
     pub underweight_squares: Vec<u8>,
     pub total_deployed: f64,
     pub total_miners: u64,
-    pub motherlode: f64,
+    pub motherlode_ore: f64,
+}
+
+/// Bet size to reach the target winner share on one square
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SquareBet {
+    pub square_num: u8,
+    pub deployed_lamports: u64,
+    pub bet_lamports: u64,
+    pub bet_sol: f64,
+}
+
+/// `square_bets` for the recommended squares (1-25), from the live board
+fn recommended_bets(squares: &[SquareData], recommended: &[u8], target_share: f64) -> Vec<SquareBet> {
+    recommended
+        .iter()
+        .filter_map(|&num| squares.iter().find(|sq| sq.square_num == num))
+        .map(|sq| {
+            let bet_lamports = bet_for_target_share(sq.deployed_lamports, target_share);
+            SquareBet {
+                square_num: sq.square_num,
+                deployed_lamports: sq.deployed_lamports,
+                bet_lamports,
+                bet_sol: lamports_to_sol(bet_lamports),
+            }
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -548,4 +583,27 @@ mod tests {
         assert_eq!(sq.index, 12);
         assert_eq!(sq.deployed_sol, 1.0);
     }
+
+    #[test]
+    fn test_recommended_bets_reach_target_share() {
+        let squares: Vec<SquareData> = (0..25u8)
+            .map(|i| SquareData {
+                square_num: i + 1,
+                index: i,
+                deployed_lamports: (i as u64 + 1) * 100_000_000,
+                deployed_sol: (i as f64 + 1.0) * 0.1,
+                miner_count: 1,
+                is_winning: false,
+                percentage_of_total: 4.0,
+            })
+            .collect();
+
+        let bets = recommended_bets(&squares, &[3, 10], 0.3);
+        assert_eq!(bets.iter().map(|b| b.square_num).collect::<Vec<_>>(), vec![3, 10]);
+        for bet in &bets {
+            let share = bet.bet_lamports as f64 / (bet.deployed_lamports + bet.bet_lamports) as f64;
+            assert!((share - 0.3).abs() < 1e-6);
+        }
+        assert_eq!(bets[0].deployed_lamports, 300_000_000);
+    }
 }
@@ -1,5 +1,12 @@
+use crate::board_trend::SquareTrend;
+use crate::error::{BotError, DeployAction};
+use crate::learning_engine::WinnerAllocation;
+use crate::units::{lamports_to_sol, raw_ore_to_ore, sol_to_lamports, LAMPORTS_PER_SOL};
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::sync::Mutex;
 
 /// ORE Mining Strategy Engine
 /// Learns optimal play from ALL on-chain players (not just whales)
@@ -10,7 +17,10 @@ use std::collections::HashMap;
 /// 4. Extract maximum ORE rewards
 
 pub const BOARD_SIZE: usize = 25;
-pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+/// Smallest amount worth putting on a single square
+pub const MIN_DEPLOY_PER_SQUARE_LAMPORTS: u64 = 10_000;
+/// Left in the wallet for the transaction fee when a deploy is cut to fit the balance
+pub const DEPLOY_FEE_RESERVE_LAMPORTS: u64 = 100_000;
 
 /// Player performance data learned from on-chain activity
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -51,7 +61,7 @@ pub enum CompetitionLevel {
 
 impl CompetitionLevel {
     pub fn from_deployed(lamports: u64) -> Self {
-        let sol = lamports as f64 / LAMPORTS_PER_SOL as f64;
+        let sol = lamports_to_sol(lamports);
         if sol < 0.5 {
             Self::VeryLow
         } else if sol < 2.0 {
@@ -93,12 +103,425 @@ pub struct OptimalConfig {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeployDecision {
     pub should_deploy: bool,
+    /// 1-25 as shown in the game UI; `board_indexes`/`square_mask` convert for the chain
     pub squares: Vec<usize>,
     pub total_amount_lamports: u64,
     pub per_square_lamports: u64,
     pub expected_ore: f64,
     pub reasoning: String,
-    pub skip_reason: Option<String>,
+    pub skip_reason: Option<SkipReason>,
+}
+
+impl DeployDecision {
+    /// `squares` as 0-24 board indexes
+    pub fn board_indexes(&self) -> Vec<usize> {
+        self.squares.iter().filter(|s| (1..=25).contains(*s)).map(|s| s - 1).collect()
+    }
+
+    /// Deploy mask for `ore_api::sdk::deploy`
+    pub fn square_mask(&self) -> [bool; 25] {
+        let mut mask = [false; 25];
+        for i in self.board_indexes() {
+            mask[i] = true;
+        }
+        mask
+    }
+
+    pub fn skip(reason: SkipReason) -> Self {
+        Self {
+            should_deploy: false,
+            squares: vec![],
+            total_amount_lamports: 0,
+            per_square_lamports: 0,
+            expected_ore: 0.0,
+            reasoning: String::new(),
+            skip_reason: Some(reason),
+        }
+    }
+
+    /// Same squares with the total cut to what `deployable_lamports` (see
+    /// `OreStrategyEngine::deployable_lamports`) covers after fees
+    /// None if that isn't smaller than the current bet or is below the per-square minimum
+    pub fn reduced_to_fit(&self, deployable_lamports: u64) -> Option<Self> {
+        if self.squares.is_empty() {
+            return None;
+        }
+        let num_squares = self.squares.len() as u64;
+        let per_square = deployable_lamports.saturating_sub(DEPLOY_FEE_RESERVE_LAMPORTS) / num_squares;
+        if per_square < MIN_DEPLOY_PER_SQUARE_LAMPORTS || per_square >= self.per_square_lamports {
+            return None;
+        }
+        let total = per_square * num_squares;
+        Some(Self {
+            total_amount_lamports: total,
+            per_square_lamports: per_square,
+            expected_ore: self.expected_ore * per_square as f64 / self.per_square_lamports as f64,
+            reasoning: format!("{} | reduced to {:.4} SOL to fit the wallet", self.reasoning, lamports_to_sol(total)),
+            ..self.clone()
+        })
+    }
+}
+
+/// Send `decision` and react to a rejection by its [`DeployAction`], as long as
+/// `in_window` says the round still takes deploys: a transient failure is sent
+/// once more as is, insufficient funds once more cut down to the deployable
+/// balance `deployable` reports (wallet minus min_wallet_sol and the claim
+/// reserve). Abort and Halt come back to the caller untouched
+/// Returns the signature and the decision that actually went out
+pub async fn deploy_with_retry<S, Fut, B, W>(
+    decision: &DeployDecision,
+    mut send: S,
+    deployable: B,
+    in_window: W,
+) -> crate::error::Result<(String, DeployDecision)>
+where
+    S: FnMut(DeployDecision) -> Fut,
+    Fut: Future<Output = crate::error::Result<String>>,
+    B: FnOnce() -> crate::error::Result<u64>,
+    W: FnOnce() -> bool,
+{
+    match send(decision.clone()).await {
+        Err(BotError::Deploy(reason)) if reason.action() == DeployAction::Retry => {
+            if !in_window() {
+                return Err(BotError::Deploy(reason));
+            }
+            let signature = send(decision.clone()).await?;
+            Ok((signature, decision.clone()))
+        }
+        Err(BotError::Deploy(reason)) if reason.action() == DeployAction::ReduceAmount => {
+            let reduced = decision
+                .reduced_to_fit(deployable()?)
+                .filter(|_| in_window())
+                .ok_or(BotError::Deploy(reason))?;
+            let signature = send(reduced.clone()).await?;
+            Ok((signature, reduced))
+        }
+        result => result.map(|signature| (signature, decision.clone())),
+    }
+}
+
+/// Why a round was skipped
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum SkipReason {
+    /// Nothing left above the minimum balance and claim reserve
+    LowBalance { wallet_sol: f64, min_wallet_sol: f64, reserve_sol: f64 },
+    HighCompetition,
+    VeryHighCompetition,
+    /// Hourly round cap reached; resumes as the window slides
+    Cooldown { rounds_last_hour: u32, max_per_hour: u32, resumes_in_secs: u64 },
+}
+
+impl std::fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SkipReason::LowBalance { wallet_sol, min_wallet_sol, reserve_sol } => write!(
+                f,
+                "Wallet balance {:.4} SOL below minimum {:.4} SOL + {:.4} SOL claim reserve",
+                wallet_sol, min_wallet_sol, reserve_sol
+            ),
+            SkipReason::HighCompetition => f.write_str("High competition, low confidence - skipping"),
+            SkipReason::VeryHighCompetition => f.write_str("Very high competition - skip for better ORE splits"),
+            SkipReason::Cooldown { rounds_last_hour, max_per_hour, resumes_in_secs } => write!(
+                f,
+                "Hourly cap reached ({}/{} rounds) - resuming in {}s",
+                rounds_last_hour, max_per_hour, resumes_in_secs
+            ),
+        }
+    }
+}
+
+/// Caps played rounds to `max_per_hour` over a sliding one-hour window
+/// so a fixed budget is spread over time instead of spent in minutes
+pub struct RoundThrottle {
+    max_per_hour: u32,
+    /// Unix seconds of each deploy in the window, oldest first
+    deploys: VecDeque<i64>,
+}
+
+impl RoundThrottle {
+    const WINDOW_SECS: i64 = 3600;
+
+    pub fn new(max_per_hour: u32) -> Self {
+        Self {
+            max_per_hour,
+            deploys: VecDeque::new(),
+        }
+    }
+
+    /// Cooldown while the cap is reached at `now` (unix seconds)
+    pub fn check(&mut self, now: i64) -> Option<SkipReason> {
+        while self.deploys.front().is_some_and(|&t| now - t >= Self::WINDOW_SECS) {
+            self.deploys.pop_front();
+        }
+        if (self.deploys.len() as u32) < self.max_per_hour {
+            return None;
+        }
+        let oldest = self.deploys.front().copied().unwrap_or(now);
+        Some(SkipReason::Cooldown {
+            rounds_last_hour: self.deploys.len() as u32,
+            max_per_hour: self.max_per_hour,
+            resumes_in_secs: (oldest + Self::WINDOW_SECS - now).max(0) as u64,
+        })
+    }
+
+    pub fn record(&mut self, now: i64) {
+        self.deploys.push_back(now);
+    }
+}
+
+/// Wallet balance lower than our own spending explains
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BalanceDrop {
+    pub expected_lamports: u64,
+    pub actual_lamports: u64,
+    pub shortfall_lamports: u64,
+}
+
+impl std::fmt::Display for BalanceDrop {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Wallet holds {:.4} SOL, expected {:.4} SOL after our deploys ({:.4} SOL unexplained)",
+            lamports_to_sol(self.actual_lamports),
+            lamports_to_sol(self.expected_lamports),
+            lamports_to_sol(self.shortfall_lamports)
+        )
+    }
+}
+
+/// Compares each cycle's wallet balance with the previous one minus what we
+/// deployed since. Winnings and claims only raise the balance, so a shortfall
+/// beyond `tolerance_lamports` (fees, rent) means something else spent from
+/// the wallet.
+pub struct BalanceGuard {
+    tolerance_lamports: u64,
+    /// Balance we expect on the next check; None until the first reading
+    expected: Option<u64>,
+}
+
+impl BalanceGuard {
+    pub fn new(tolerance_lamports: u64) -> Self {
+        Self {
+            tolerance_lamports,
+            expected: None,
+        }
+    }
+
+    /// Check this cycle's balance; the reading becomes the new baseline either way
+    pub fn check(&mut self, actual_lamports: u64) -> Result<(), BalanceDrop> {
+        let expected = self.expected.replace(actual_lamports);
+        match expected {
+            Some(expected) if actual_lamports.saturating_add(self.tolerance_lamports) < expected => Err(BalanceDrop {
+                expected_lamports: expected,
+                actual_lamports,
+                shortfall_lamports: expected - actual_lamports,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// A confirmed deploy of `lamports` leaves the wallet
+    pub fn record_spend(&mut self, lamports: u64) {
+        if let Some(ref mut expected) = self.expected {
+            *expected = expected.saturating_sub(lamports);
+        }
+    }
+}
+
+/// Random bet-size band so the deploy amount isn't the same every round
+pub struct BetJitter {
+    /// Fraction either side of the planned amount (0.1 = ±10%)
+    pub band: f64,
+    rng: Mutex<StdRng>,
+}
+
+impl BetJitter {
+    /// `seed` makes the sequence repeatable (tests); None seeds from entropy
+    pub fn new(band: f64, seed: Option<u64>) -> Self {
+        Self {
+            band: band.clamp(0.0, 1.0),
+            rng: Mutex::new(match seed {
+                Some(seed) => StdRng::seed_from_u64(seed),
+                None => StdRng::from_entropy(),
+            }),
+        }
+    }
+
+    /// Scale `total` by a random factor in [1 - band, 1 + band], then clamp to
+    /// `max_total` (the round budget) and the per-square minimum
+    pub fn apply(&self, total: u64, max_total: u64, num_squares: usize) -> u64 {
+        let factor = if self.band > 0.0 {
+            self.rng.lock().unwrap().gen_range(1.0 - self.band..=1.0 + self.band)
+        } else {
+            1.0
+        };
+        let floor = MIN_DEPLOY_PER_SQUARE_LAMPORTS * num_squares.max(1) as u64;
+        ((total as f64 * factor) as u64).min(max_total).max(floor.min(max_total))
+    }
+
+    /// Random total at most `budget`: the band is centered at budget * (1 - band),
+    /// so draws spread over [budget * (1 - band)^2, budget * (1 - band^2)] and
+    /// don't pile up on the cap
+    pub fn below(&self, budget: u64, num_squares: usize) -> u64 {
+        let center = (budget as f64 * (1.0 - self.band)) as u64;
+        self.apply(center, budget, num_squares)
+    }
+}
+
+/// Highest winner share `bet_for_target_share` aims for; 100% needs an empty square
+pub const MAX_TARGET_SHARE: f64 = 0.99;
+
+/// Smallest bet giving us `target_share` of a square that already holds
+/// `square_deployed` lamports: share = bet / (square_deployed + bet)
+/// An empty square is ours outright at the minimum deploy; targets of 0 or less need no bet
+pub fn bet_for_target_share(square_deployed: u64, target_share: f64) -> u64 {
+    if target_share <= 0.0 || target_share.is_nan() {
+        return 0;
+    }
+    if square_deployed == 0 {
+        return MIN_DEPLOY_PER_SQUARE_LAMPORTS;
+    }
+    let share = target_share.min(MAX_TARGET_SHARE);
+    let bet = (square_deployed as f64 * share / (1.0 - share)).ceil() as u64;
+    bet.max(MIN_DEPLOY_PER_SQUARE_LAMPORTS)
+}
+
+/// Rewards to claim this cycle (0 = leave that asset alone)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClaimPlan {
+    pub sol_lamports: u64,
+    pub ore_grams: u64,
+}
+
+impl ClaimPlan {
+    /// Claim each asset once its rewards reach the threshold
+    /// A threshold of 0 or less disables claiming that asset
+    pub fn decide(rewards_sol_lamports: u64, rewards_ore_grams: u64, min_sol: f64, min_ore: f64) -> Self {
+        let reached = |amount: f64, min: f64| min > 0.0 && amount >= min;
+        Self {
+            sol_lamports: if reached(lamports_to_sol(rewards_sol_lamports), min_sol) { rewards_sol_lamports } else { 0 },
+            ore_grams: if reached(raw_ore_to_ore(rewards_ore_grams), min_ore) { rewards_ore_grams } else { 0 },
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sol_lamports == 0 && self.ore_grams == 0
+    }
+}
+
+/// Linearly blend the consensus picks with our own, returning up to `count` squares
+/// Consensus squares are scored by their normalized weight (missing weights
+/// count as 1.0), our own picks equally; `weight` 1.0 = consensus only,
+/// 0.0 = our own picks only
+pub fn blend_square_picks(
+    consensus: &[usize],
+    consensus_weights: &[f64],
+    own: &[usize],
+    weight: f64,
+    count: usize,
+) -> Vec<usize> {
+    let weight = weight.clamp(0.0, 1.0);
+    let raw: Vec<f64> = (0..consensus.len())
+        .map(|i| consensus_weights.get(i).copied().unwrap_or(1.0).max(0.0))
+        .collect();
+    let raw_sum: f64 = raw.iter().sum();
+
+    let mut scored: Vec<(usize, f64)> = Vec::new();
+    let mut add = |square: usize, score: f64| match scored.iter_mut().find(|(s, _)| *s == square) {
+        Some(entry) => entry.1 += score,
+        None => scored.push((square, score)),
+    };
+    for &square in own {
+        add(square, (1.0 - weight) / own.len() as f64);
+    }
+    for (i, &square) in consensus.iter().enumerate() {
+        let share = if raw_sum > 0.0 { raw[i] / raw_sum } else { 1.0 / consensus.len() as f64 };
+        add(square, weight * share);
+    }
+
+    // Stable sort keeps the original order between equal scores
+    scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    scored.into_iter()
+        .filter(|(_, score)| *score > 0.0)
+        .take(count)
+        .map(|(square, _)| square)
+        .collect()
+}
+
+/// Expected SOL profit (lamports) of splitting `bet_lamports` evenly over
+/// `squares` (1-25) on this board, each square winning with probability 1/25
+pub fn expected_value_lamports(squares: &[usize], deployed: &[u64; 25], bet_lamports: u64) -> f64 {
+    let squares: Vec<usize> = squares.iter().copied().filter(|s| (1..=25).contains(s)).collect();
+    if squares.is_empty() || bet_lamports == 0 {
+        return 0.0;
+    }
+
+    let stake = bet_lamports / squares.len() as u64;
+    let total_after = deployed.iter().sum::<u64>() + bet_lamports;
+    let expected_return: f64 = squares.iter()
+        .map(|&s| {
+            let allocation = WinnerAllocation::compute(
+                bet_lamports,
+                squares.len(),
+                deployed[s - 1] + stake,
+                total_after,
+            );
+            (allocation.stake_on_square + allocation.amount_won) as f64 / 25.0
+        })
+        .sum();
+
+    expected_return - bet_lamports as f64
+}
+
+/// The `count` least-deployed squares (1-25), lowest square first on ties
+pub fn low_competition_squares(deployed: &[u64; 25], count: usize) -> Vec<usize> {
+    let mut squares: Vec<usize> = (1..=25).collect();
+    squares.sort_by_key(|&s| deployed[s - 1]);
+    squares.truncate(count);
+    squares
+}
+
+/// `squares` (1-25) with each one that is filling fast this round swapped for
+/// the least-deployed calm square not already picked; kept when none is left
+pub fn avoid_rising_squares(squares: &[usize], deployed: &[u64; 25], trend: &[SquareTrend; 25]) -> Vec<usize> {
+    let mut calm: Vec<usize> = (1..=25)
+        .filter(|s| trend[s - 1] != SquareTrend::Rising && !squares.contains(s))
+        .collect();
+    calm.sort_by_key(|&s| deployed[s - 1]);
+    let mut calm = calm.into_iter();
+
+    squares.iter()
+        .map(|&s| match trend.get(s.wrapping_sub(1)) {
+            Some(SquareTrend::Rising) => calm.next().unwrap_or(s),
+            _ => s,
+        })
+        .collect()
+}
+
+/// Why the coordinator's own wallet sits this round out, None to deploy
+/// While catching up the board it sees is a round it only partly watched, so it never deploys
+pub fn coordinator_skip_reason(
+    catching_up: bool,
+    consensus_confidence: f64,
+    consensus_squares: &[usize],
+    balance_sol: f64,
+    min_wallet_sol: f64,
+    competition: CompetitionLevel,
+) -> Option<String> {
+    if catching_up {
+        Some("Catching up - waiting for the next full round".to_string())
+    } else if balance_sol < min_wallet_sol {
+        Some(format!("Low balance ({:.4} SOL)", balance_sol))
+    } else if matches!(competition, CompetitionLevel::VeryHigh) {
+        Some("Very high competition".to_string())
+    } else if consensus_confidence <= 0.4 {
+        Some(format!("Low confidence ({:.0}%)", consensus_confidence * 100.0))
+    } else if consensus_squares.is_empty() {
+        Some("No squares recommended".to_string())
+    } else {
+        None
+    }
 }
 
 /// Main ORE Strategy Engine
@@ -112,10 +535,29 @@ pub struct OreStrategyEngine {
     // Round history for pattern detection
     round_history: Vec<RoundConditions>,
     
+    // Recent winning squares (1-25), oldest first - used for strategy form checks
+    recent_winners: VecDeque<u8>,
+    
     // Configuration limits
     pub min_wallet_sol: f64,
     pub max_bet_per_round_sol: f64,
     pub target_rounds_per_session: u32,
+    /// Kept on top of min_wallet_sol so claim transactions can always pay fees
+    pub reserve_for_claims_lamports: u64,
+    
+    // Recent form requirement for detected strategies
+    pub recent_form_rounds: usize,
+    pub min_recent_hit_rate: f64,
+    
+    // Optional bet-size randomization (off by default)
+    pub bet_jitter: Option<BetJitter>,
+    
+    // How much to trust the coordinator's squares vs our own (1.0 = consensus only)
+    pub consensus_blend_weight: f64,
+    
+    // Follow the consensus only if its EV beats a low-competition spread by
+    // this fraction of the bet (None = always follow)
+    pub consensus_ev_margin: Option<f64>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -141,9 +583,16 @@ impl OreStrategyEngine {
             player_stats: HashMap::new(),
             square_count_performance,
             round_history: Vec::new(),
+            recent_winners: VecDeque::new(),
             min_wallet_sol: 0.05,        // Keep at least 0.05 SOL
             max_bet_per_round_sol: 0.04, // Max 0.04 SOL per round total
             target_rounds_per_session: 100, // Try to play 100 rounds
+            reserve_for_claims_lamports: 5_000_000, // 0.005 SOL for claims
+            recent_form_rounds: 20,      // Judge form over the last 20 rounds
+            min_recent_hit_rate: 0.1,    // Must have hit at least 10% of them
+            bet_jitter: None,
+            consensus_blend_weight: 1.0, // Coordinator is the decider
+            consensus_ev_margin: None,
         }
     }
 
@@ -154,6 +603,20 @@ impl OreStrategyEngine {
         }
     }
 
+    /// Load recent winning squares (1-25, oldest first) from database
+    pub fn load_recent_winners(&mut self, winners: Vec<u8>) {
+        for square in winners {
+            self.push_recent_winner(square);
+        }
+    }
+
+    fn push_recent_winner(&mut self, square: u8) {
+        self.recent_winners.push_back(square);
+        if self.recent_winners.len() > 1000 {
+            self.recent_winners.pop_front();
+        }
+    }
+
     /// Load square count performance from database
     pub fn load_square_count_stats(&mut self, stats: Vec<SquareCountStats>) {
         for stat in stats {
@@ -220,7 +683,7 @@ impl OreStrategyEngine {
             };
             
             // Calculate ORE per SOL
-            let total_sol = player.total_deployed as f64 / LAMPORTS_PER_SOL as f64;
+            let total_sol = lamports_to_sol(player.total_deployed);
             if total_sol > 0.0 {
                 // This would need actual ORE tracking, simplified here
                 player.ore_per_sol = ore_earned / total_sol;
@@ -242,6 +705,7 @@ impl OreStrategyEngine {
     }
 
     /// Record a completed round for learning (update square stats based on winning square)
+    /// `winning_square` is 1-25, like `load_recent_winners`
     pub fn record_round(&mut self, deployed: &[u64; 25], winning_square: u8) {
         // Count how many squares were deployed to
         let squares_with_deploys: Vec<u8> = deployed.iter()
@@ -280,6 +744,8 @@ impl OreStrategyEngine {
         if self.round_history.len() > 1000 {
             self.round_history.remove(0);
         }
+        
+        self.push_recent_winner(winning_square);
     }
 
     /// Analyze current round conditions
@@ -495,29 +961,25 @@ impl OreStrategyEngine {
         current_round_deployed: &[u64; 25],
         num_deployers: u32,
         consensus_squares: &[usize],
+        consensus_weights: &[f64],
         consensus_confidence: f64,
+        competition_trend: Option<&[SquareTrend; 25]>,
     ) -> DeployDecision {
-        let wallet_sol = wallet_balance_lamports as f64 / LAMPORTS_PER_SOL as f64;
+        let wallet_sol = lamports_to_sol(wallet_balance_lamports);
         let conditions = self.analyze_round(current_round_deployed, num_deployers);
 
-        // Check if we have enough balance
-        if wallet_sol < self.min_wallet_sol {
-            return DeployDecision {
-                should_deploy: false,
-                squares: vec![],
-                total_amount_lamports: 0,
-                per_square_lamports: 0,
-                expected_ore: 0.0,
-                reasoning: String::new(),
-                skip_reason: Some(format!(
-                    "Wallet balance {:.4} SOL below minimum {:.4} SOL",
-                    wallet_sol, self.min_wallet_sol
-                )),
-            };
+        // Check if we have enough balance (minimum plus the claim reserve)
+        let deployable_lamports = self.deployable_lamports(wallet_balance_lamports);
+        if deployable_lamports == 0 {
+            return DeployDecision::skip(SkipReason::LowBalance {
+                wallet_sol,
+                min_wallet_sol: self.min_wallet_sol,
+                reserve_sol: lamports_to_sol(self.reserve_for_claims_lamports),
+            });
         }
 
-        // Calculate available budget (leave min_wallet_sol)
-        let available_sol = wallet_sol - self.min_wallet_sol;
+        // Calculate available budget (leave min_wallet_sol and the claim reserve)
+        let available_sol = lamports_to_sol(deployable_lamports);
         let max_this_round = available_sol.min(self.max_bet_per_round_sol);
 
         // Decide based on competition level
@@ -530,11 +992,11 @@ impl OreStrategyEngine {
                 if consensus_confidence > 0.6 {
                     (true, 0.5, None)
                 } else {
-                    (false, 0.0, Some("High competition, low confidence - skipping".to_string()))
+                    (false, 0.0, Some(SkipReason::HighCompetition))
                 }
             }
             CompetitionLevel::VeryHigh => {
-                (false, 0.0, Some("Very high competition - skip for better ORE splits".to_string()))
+                (false, 0.0, Some(SkipReason::VeryHighCompetition))
             }
         };
 
@@ -553,28 +1015,73 @@ impl OreStrategyEngine {
         // Get optimal square count
         let (optimal_count, _, square_reasoning) = self.get_optimal_square_count();
         
-        // Use consensus squares if available, otherwise pick based on empty squares
+        // Our own pick: empty squares (less competition), else the first N
         // All squares are 1-25 range
-        let squares: Vec<usize> = if !consensus_squares.is_empty() && consensus_confidence > 0.4 {
-            consensus_squares.iter()
-                .take(optimal_count as usize)
-                .copied()
-                .collect()
-        } else if !conditions.empty_squares.is_empty() {
-            // Prefer empty squares (less competition) - already 1-25
+        let own_squares: Vec<usize> = if !conditions.empty_squares.is_empty() {
             conditions.empty_squares.iter()
                 .take(optimal_count as usize)
                 .copied()
                 .collect()
         } else {
-            // Random fallback - use 1-25 range
             (1..=optimal_count as usize).collect()
         };
 
+        let budget_lamports = sol_to_lamports(max_this_round);
+
+        // Blend in the consensus when it's confident enough
+        let mut ev_note = String::new();
+        let squares: Vec<usize> = if !consensus_squares.is_empty() && consensus_confidence > 0.4 {
+            let blended = blend_square_picks(
+                consensus_squares,
+                consensus_weights,
+                &own_squares,
+                self.consensus_blend_weight,
+                optimal_count as usize,
+            );
+            match self.consensus_ev_margin {
+                Some(margin) => {
+                    // Only worth following if it beats simply spreading over quiet squares
+                    let baseline = low_competition_squares(current_round_deployed, optimal_count as usize);
+                    let consensus_ev = expected_value_lamports(&blended, current_round_deployed, budget_lamports);
+                    let baseline_ev = expected_value_lamports(&baseline, current_round_deployed, budget_lamports);
+                    if consensus_ev >= baseline_ev + margin * budget_lamports as f64 {
+                        blended
+                    } else {
+                        ev_note = format!(
+                            ", consensus EV {:+.4} SOL not {:.0}% above baseline {:+.4} SOL",
+                            consensus_ev / LAMPORTS_PER_SOL as f64,
+                            margin * 100.0,
+                            baseline_ev / LAMPORTS_PER_SOL as f64
+                        );
+                        baseline
+                    }
+                }
+                None => blended,
+            }
+        } else {
+            own_squares
+        };
+
+        // Prefer squares whose competition is growing slowly
+        let squares = match competition_trend {
+            Some(trend) => {
+                let calmer = avoid_rising_squares(&squares, current_round_deployed, trend);
+                let swapped = calmer.iter().filter(|s| !squares.contains(s)).count();
+                if swapped > 0 {
+                    ev_note.push_str(&format!(", swapped {} rising square(s)", swapped));
+                }
+                calmer
+            }
+            None => squares,
+        };
+
         let num_squares = squares.len();
         
-        // Total amount is max_this_round, divided across squares
-        let total_amount_lamports = (max_this_round * LAMPORTS_PER_SOL as f64) as u64;
+        // Total amount is max_this_round (optionally randomized below it), divided across squares
+        let total_amount_lamports = match self.bet_jitter {
+            Some(ref jitter) => jitter.below(budget_lamports, num_squares),
+            None => budget_lamports,
+        };
         let per_square_lamports = total_amount_lamports / num_squares as u64;
 
         // Expected ORE calculation
@@ -588,21 +1095,29 @@ impl OreStrategyEngine {
             per_square_lamports,
             expected_ore,
             reasoning: format!(
-                "Competition: {:?} ({}x ORE), {} squares ({}), {:.4} SOL total",
+                "Competition: {:?} ({}x ORE), {} squares ({}), {:.4} SOL total{}",
                 conditions.competition_level,
                 ore_multiplier,
                 num_squares,
                 square_reasoning,
-                total_amount_lamports as f64 / LAMPORTS_PER_SOL as f64
+                lamports_to_sol(total_amount_lamports),
+                ev_note
             ),
             skip_reason: None,
         }
     }
 
+    /// Balance that may go into deploys: everything above min_wallet_sol and the claim reserve
+    pub fn deployable_lamports(&self, wallet_balance_lamports: u64) -> u64 {
+        let min_wallet_lamports = sol_to_lamports(self.min_wallet_sol);
+        wallet_balance_lamports
+            .saturating_sub(min_wallet_lamports)
+            .saturating_sub(self.reserve_for_claims_lamports)
+    }
+
     /// Calculate how many rounds we can play with current balance
     pub fn estimate_rounds_remaining(&self, wallet_balance_lamports: u64) -> u32 {
-        let wallet_sol = wallet_balance_lamports as f64 / LAMPORTS_PER_SOL as f64;
-        let playable_sol = (wallet_sol - self.min_wallet_sol).max(0.0);
+        let playable_sol = lamports_to_sol(self.deployable_lamports(wallet_balance_lamports));
         
         if self.max_bet_per_round_sol > 0.0 {
             (playable_sol / self.max_bet_per_round_sol) as u32
@@ -686,24 +1201,69 @@ impl OreStrategyEngine {
             strategy["confidence"].as_f64().unwrap_or(0.0) * 100.0);
     }
     
+    /// Realized hit rate of a strategy's preferred squares over the last
+    /// `recent_form_rounds` rounds. None if there is nothing to judge by.
+    pub fn recent_hit_rate(&self, strategy: &serde_json::Value) -> Option<f64> {
+        let preferred: Vec<u8> = strategy["preferred_squares"].as_array()?
+            .iter()
+            .filter_map(|s| s.as_u64().map(|n| n as u8))
+            .collect();
+        
+        if preferred.is_empty() || self.recent_winners.is_empty() || self.recent_form_rounds == 0 {
+            return None;
+        }
+        
+        let window = self.recent_form_rounds.min(self.recent_winners.len());
+        let hits = self.recent_winners.iter()
+            .rev()
+            .take(window)
+            .filter(|w| preferred.contains(w))
+            .count();
+        
+        Some(hits as f64 / window as f64)
+    }
+    
     /// Apply the best detected strategy from a list
+    /// Strategies are tried in confidence order; one that has gone cold
+    /// recently is skipped in favor of the next-best
     pub fn apply_best_strategy(&mut self, strategies: &[serde_json::Value]) {
-        // Find the strategy with highest confidence
-        let best = strategies.iter()
-            .max_by(|a, b| {
-                let conf_a = a["confidence"].as_f64().unwrap_or(0.0);
-                let conf_b = b["confidence"].as_f64().unwrap_or(0.0);
-                conf_a.partial_cmp(&conf_b).unwrap_or(std::cmp::Ordering::Equal)
-            });
+        let mut ranked: Vec<&serde_json::Value> = strategies.iter().collect();
+        ranked.sort_by(|a, b| {
+            let conf_a = a["confidence"].as_f64().unwrap_or(0.0);
+            let conf_b = b["confidence"].as_f64().unwrap_or(0.0);
+            conf_b.partial_cmp(&conf_a).unwrap_or(std::cmp::Ordering::Equal)
+        });
         
-        if let Some(strategy) = best {
-            if strategy["confidence"].as_f64().unwrap_or(0.0) > 0.5 {
-                self.apply_detected_strategy(strategy);
-            } else {
-                log::info!("🔍 Best strategy confidence too low ({:.0}%), will explore instead",
-                    strategy["confidence"].as_f64().unwrap_or(0.0) * 100.0);
+        let Some(best) = ranked.first() else {
+            return;
+        };
+        
+        if best["confidence"].as_f64().unwrap_or(0.0) <= 0.5 {
+            log::info!("🔍 Best strategy confidence too low ({:.0}%), will explore instead",
+                best["confidence"].as_f64().unwrap_or(0.0) * 100.0);
+            return;
+        }
+        
+        for strategy in ranked {
+            if strategy["confidence"].as_f64().unwrap_or(0.0) <= 0.5 {
+                break;
             }
+            
+            if let Some(hit_rate) = self.recent_hit_rate(strategy) {
+                if hit_rate < self.min_recent_hit_rate {
+                    log::info!("🥶 Skipping {}: recent hit rate {:.0}% below {:.0}% floor",
+                        strategy["name"].as_str().unwrap_or("Unknown"),
+                        hit_rate * 100.0,
+                        self.min_recent_hit_rate * 100.0);
+                    continue;
+                }
+            }
+            
+            self.apply_detected_strategy(strategy);
+            return;
         }
+        
+        log::info!("🔍 No detected strategy in recent form, keeping engine defaults");
     }
 }
 
@@ -736,7 +1296,9 @@ mod tests {
             &deployed,
             0,
             &[5, 10, 15],
+            &[],
             0.7,
+            None,
         );
 
         assert!(decision.should_deploy);
@@ -758,10 +1320,448 @@ mod tests {
             &deployed,
             50,
             &[5],
+            &[],
             0.3, // Low confidence
+            None,
         );
 
         assert!(!decision.should_deploy);
         assert!(decision.skip_reason.is_some());
     }
+
+    #[test]
+    fn test_cold_strategy_skipped_for_warmer_one() {
+        let mut engine = OreStrategyEngine::new();
+        // Square 10 has won the last 20 rounds
+        engine.load_recent_winners(vec![10; 20]);
+
+        let cold = serde_json::json!({
+            "name": "Cold Corners",
+            "square_count": 2,
+            "bet_size_sol": 0.01,
+            "preferred_squares": [1, 5],
+            "confidence": 0.9,
+        });
+        let warm = serde_json::json!({
+            "name": "Warm Middle",
+            "square_count": 3,
+            "bet_size_sol": 0.02,
+            "preferred_squares": [9, 10, 11],
+            "confidence": 0.7,
+        });
+
+        assert_eq!(engine.recent_hit_rate(&cold), Some(0.0));
+        assert_eq!(engine.recent_hit_rate(&warm), Some(1.0));
+
+        engine.apply_best_strategy(&[cold, warm]);
+
+        assert!((engine.max_bet_per_round_sol - 0.02).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_bet_jitter_is_seeded_and_within_band() {
+        let total = 40_000_000;
+        let a = BetJitter::new(0.1, Some(7));
+        let b = BetJitter::new(0.1, Some(7));
+
+        let from_a: Vec<u64> = (0..50).map(|_| a.apply(total, u64::MAX, 5)).collect();
+        let from_b: Vec<u64> = (0..50).map(|_| b.apply(total, u64::MAX, 5)).collect();
+        assert_eq!(from_a, from_b, "same seed, same amounts");
+        assert!(from_a.iter().any(|&x| x != total), "amounts actually vary");
+        assert!(from_a.iter().all(|&x| (36_000_000..=44_000_000).contains(&x)));
+
+        // Never above the round budget or below the per-square minimum
+        assert!((0..50).all(|_| a.apply(total, total, 5) <= total));
+        assert_eq!(a.apply(10_000, u64::MAX, 5), 5 * MIN_DEPLOY_PER_SQUARE_LAMPORTS);
+
+        // Off by default
+        assert!(OreStrategyEngine::new().bet_jitter.is_none());
+    }
+
+    #[test]
+    fn test_bet_jitter_below_budget_has_no_dominant_amount() {
+        let budget = 40_000_000;
+        let jitter = BetJitter::new(0.1, Some(11));
+        let draws: Vec<u64> = (0..500).map(|_| jitter.below(budget, 5)).collect();
+
+        assert!(draws.iter().all(|&x| (32_399_000..=39_601_000).contains(&x)));
+        let mut counts: HashMap<u64, usize> = HashMap::new();
+        for &x in &draws {
+            *counts.entry(x).or_default() += 1;
+        }
+        let most_common = counts.values().copied().max().unwrap();
+        assert!(most_common <= 5, "one amount drawn {} times out of 500", most_common);
+
+        // No band: the budget itself
+        assert_eq!(BetJitter::new(0.0, Some(11)).below(budget, 5), budget);
+    }
+
+    #[test]
+    fn test_claim_reserve_is_never_deployed() {
+        let mut engine = OreStrategyEngine::new();
+        engine.min_wallet_sol = 0.05;
+        engine.max_bet_per_round_sol = 0.04;
+        engine.reserve_for_claims_lamports = 10_000_000; // 0.01 SOL
+
+        // 0.1 SOL wallet: 0.05 min + 0.01 reserve leaves 0.04 to deploy
+        assert_eq!(engine.deployable_lamports(100_000_000), 40_000_000);
+        assert_eq!(engine.estimate_rounds_remaining(100_000_000), 1);
+        assert_eq!(engine.estimate_rounds_remaining(130_000_000), 1);
+
+        let deployed = [0u64; 25];
+        let decision = engine.make_deploy_decision(80_000_000, &deployed, 0, &[5, 10, 15], &[], 0.7, None);
+        assert!(decision.should_deploy);
+        assert!(decision.total_amount_lamports <= 20_000_000, "only 0.02 SOL above min + reserve");
+
+        // Above the minimum but inside the reserve: stop deploying
+        let decision = engine.make_deploy_decision(55_000_000, &deployed, 0, &[5, 10, 15], &[], 0.7, None);
+        assert!(!decision.should_deploy);
+        assert!(decision.skip_reason.unwrap().to_string().contains("claim reserve"));
+        assert_eq!(engine.estimate_rounds_remaining(55_000_000), 0);
+    }
+
+    #[test]
+    fn test_claim_triggers_only_above_threshold() {
+        // 0.05 SOL / 10 ORE thresholds
+        let below = ClaimPlan::decide(40_000_000, 9 * 100_000_000_000, 0.05, 10.0);
+        assert!(below.is_empty());
+
+        let sol_only = ClaimPlan::decide(60_000_000, 9 * 100_000_000_000, 0.05, 10.0);
+        assert_eq!(sol_only, ClaimPlan { sol_lamports: 60_000_000, ore_grams: 0 });
+
+        let both = ClaimPlan::decide(50_000_000, 12 * 100_000_000_000, 0.05, 10.0);
+        assert_eq!(both, ClaimPlan { sol_lamports: 50_000_000, ore_grams: 12 * 100_000_000_000 });
+
+        // Zero threshold disables, and nothing to claim means no claim
+        assert!(ClaimPlan::decide(1_000_000_000, 0, 0.0, 10.0).is_empty());
+        assert!(ClaimPlan::decide(0, 0, 0.000001, 0.000001).is_empty());
+    }
+
+    #[test]
+    fn test_consensus_blend_weight() {
+        let consensus = [3, 7, 12];
+        let weights = [0.5, 0.3, 0.2];
+        let own = [1, 2, 3];
+
+        // 0 = only our picks, 1 = only consensus
+        assert_eq!(blend_square_picks(&consensus, &weights, &own, 0.0, 3), vec![1, 2, 3]);
+        assert_eq!(blend_square_picks(&consensus, &weights, &own, 1.0, 3), vec![3, 7, 12]);
+
+        // In between, the shared square ranks first and both sides contribute
+        assert_eq!(blend_square_picks(&consensus, &weights, &own, 0.6, 3), vec![3, 7, 1]);
+
+        // Leaning on consensus keeps its strong picks, leaning on us keeps ours
+        let mostly_consensus = blend_square_picks(&consensus, &weights, &own, 0.8, 3);
+        assert_eq!(mostly_consensus, vec![3, 7, 12]);
+        let mostly_own = blend_square_picks(&consensus, &weights, &own, 0.2, 3);
+        assert_eq!(mostly_own, vec![3, 1, 2]);
+
+        // Through the engine: empty board, so our own picks are the empty squares 1..
+        let mut engine = OreStrategyEngine::new();
+        let deployed = [0u64; 25];
+        let own_only = engine.make_deploy_decision(100_000_000, &deployed, 0, &[], &[], 0.0, None);
+        engine.consensus_blend_weight = 0.0;
+        let decision = engine.make_deploy_decision(100_000_000, &deployed, 0, &[20, 21], &[], 0.9, None);
+        assert_eq!(decision.squares, own_only.squares);
+        engine.consensus_blend_weight = 1.0;
+        let decision = engine.make_deploy_decision(100_000_000, &deployed, 0, &[20, 21], &[], 0.9, None);
+        assert!(!decision.squares.is_empty());
+        assert!(decision.squares.iter().all(|s| [20, 21].contains(s)));
+    }
+
+    #[test]
+    fn test_weak_consensus_loses_to_baseline() {
+        // The consensus picks the two crowded squares, the rest of the board is quiet
+        let mut deployed = [100_000_000u64; 25];
+        deployed[2] = 2_000_000_000;
+        deployed[6] = 2_000_000_000;
+        let consensus = [3, 7];
+
+        let baseline = low_competition_squares(&deployed, 5);
+        assert_eq!(baseline, vec![1, 2, 4, 5, 6]);
+        let consensus_ev = expected_value_lamports(&consensus, &deployed, 40_000_000);
+        let baseline_ev = expected_value_lamports(&baseline, &deployed, 40_000_000);
+        assert!(consensus_ev < baseline_ev, "consensus {} vs baseline {}", consensus_ev, baseline_ev);
+
+        // Gate off: the coordinator decides
+        let mut engine = OreStrategyEngine::new();
+        let following = engine.make_deploy_decision(1_000_000_000, &deployed, 30, &consensus, &[], 0.9, None);
+        assert_eq!(following.squares, vec![3, 7]);
+
+        // Gate on: the weak consensus is dropped for the low-competition spread
+        engine.consensus_ev_margin = Some(0.05);
+        let gated = engine.make_deploy_decision(1_000_000_000, &deployed, 30, &consensus, &[], 0.9, None);
+        let (count, _, _) = engine.get_optimal_square_count();
+        assert!(gated.should_deploy);
+        assert_eq!(gated.squares, low_competition_squares(&deployed, count as usize));
+        assert!(gated.reasoning.contains("baseline"));
+    }
+
+    #[test]
+    fn test_rising_trend_moves_deploy_off_square() {
+        // Busy board with no empty squares: our own pick is squares 1..=3
+        let mut deployed = [100_000_000u64; 25];
+        deployed[24] = 50_000_000;
+        let mut engine = OreStrategyEngine::new();
+        engine.load_square_count_stats(vec![SquareCountStats {
+            count: 3,
+            times_used: 10,
+            times_won: 10,
+            avg_ore_earned: 5.0,
+            win_rate: 1.0,
+            ..Default::default()
+        }]);
+
+        let steady = [SquareTrend::Steady; 25];
+        let calm = engine.make_deploy_decision(1_000_000_000, &deployed, 30, &[], &[], 0.0, Some(&steady));
+        assert_eq!(calm.squares, vec![1, 2, 3]);
+
+        // Square 1 is filling fast this round: the least-deployed calm square takes its place
+        let mut trend = steady;
+        trend[0] = SquareTrend::Rising;
+        let decision = engine.make_deploy_decision(1_000_000_000, &deployed, 30, &[], &[], 0.0, Some(&trend));
+        assert_eq!(decision.squares, vec![25, 2, 3]);
+        assert!(decision.reasoning.contains("rising"));
+
+        // Nowhere calmer to go: the pick stands
+        assert_eq!(avoid_rising_squares(&[1, 2], &deployed, &[SquareTrend::Rising; 25]), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_coordinator_holds_deploys_while_catching_up() {
+        let ready = |catching_up| coordinator_skip_reason(catching_up, 0.8, &[3, 7], 1.0, 0.1, CompetitionLevel::Low);
+
+        assert_eq!(ready(false), None);
+        assert!(ready(true).unwrap().contains("Catching up"));
+
+        // Catch-up wins over every other reason, and the others still apply once synced
+        assert!(coordinator_skip_reason(true, 0.1, &[], 0.0, 0.1, CompetitionLevel::VeryHigh).unwrap().contains("Catching up"));
+        assert!(coordinator_skip_reason(false, 0.8, &[3], 0.05, 0.1, CompetitionLevel::Low).unwrap().contains("Low balance"));
+        assert!(coordinator_skip_reason(false, 0.3, &[3], 1.0, 0.1, CompetitionLevel::Low).unwrap().contains("Low confidence"));
+    }
+
+    #[test]
+    fn test_ev_gated_square_is_the_one_deployed() {
+        // Square 25 is the only quiet one; the consensus squares are crowded
+        let mut deployed = [100_000_000u64; 25];
+        deployed[24] = 0;
+        deployed[2] = 2_000_000_000;
+        deployed[6] = 2_000_000_000;
+
+        let mut engine = OreStrategyEngine::new();
+        engine.consensus_ev_margin = Some(0.05);
+        let decision = engine.make_deploy_decision(1_000_000_000, &deployed, 30, &[3, 7], &[], 0.9, None);
+        assert!(decision.reasoning.contains("baseline"));
+        assert_eq!(decision.squares[0], 25);
+
+        // What the miner hands to the deploy instruction
+        let mask = decision.square_mask();
+        assert!(mask[24], "square 25 is deployed at board index 24");
+        assert!(!mask[2] && !mask[6]);
+        assert_eq!(mask.iter().filter(|&&m| m).count(), decision.squares.len());
+        for &square in &decision.squares {
+            assert!(mask[square - 1]);
+        }
+        assert_eq!(decision.board_indexes()[0], 24);
+    }
+
+    #[test]
+    fn test_deploys_throttled_to_hourly_cap() {
+        let mut throttle = RoundThrottle::new(3);
+        let mut played = Vec::new();
+
+        // A round every minute for two hours
+        for now in (0..7200).step_by(60) {
+            if throttle.check(now).is_none() {
+                throttle.record(now);
+                played.push(now);
+            }
+        }
+
+        // Three per hour, resuming as the first deploys leave the window
+        assert_eq!(played, vec![0, 60, 120, 3600, 3660, 3720]);
+
+        let mut throttle = RoundThrottle::new(2);
+        throttle.record(1_000);
+        throttle.record(1_500);
+        assert_eq!(
+            throttle.check(2_000),
+            Some(SkipReason::Cooldown { rounds_last_hour: 2, max_per_hour: 2, resumes_in_secs: 2_600 })
+        );
+        assert_eq!(throttle.check(4_600), None);
+    }
+
+    #[test]
+    fn test_bet_for_target_share() {
+        let share_after = |deployed: u64, bet: u64| bet as f64 / (deployed + bet) as f64;
+
+        for &(deployed, target) in &[(1_000_000_000u64, 0.3), (250_000_000, 0.5), (37_123_457, 0.1), (5_000_000_000, 0.75)] {
+            let bet = bet_for_target_share(deployed, target);
+            let share = share_after(deployed, bet);
+            assert!((share - target).abs() < 1e-6, "{} on {}: {}", bet, deployed, share);
+            assert!(share >= target, "never short of the target");
+        }
+        // 30% of a 1 SOL square: 0.3 / 0.7 SOL
+        assert_eq!(bet_for_target_share(1_000_000_000, 0.3), 428_571_429);
+
+        assert_eq!(bet_for_target_share(0, 0.3), MIN_DEPLOY_PER_SQUARE_LAMPORTS);
+        assert_eq!(bet_for_target_share(1_000, 0.3), MIN_DEPLOY_PER_SQUARE_LAMPORTS);
+        assert_eq!(bet_for_target_share(1_000_000_000, 0.0), 0);
+        assert_eq!(bet_for_target_share(1_000_000_000, 1.0), bet_for_target_share(1_000_000_000, MAX_TARGET_SHARE));
+    }
+
+    #[tokio::test]
+    async fn test_insufficient_funds_retries_once_with_reduced_amount() {
+        use crate::error::DeployError;
+        use std::cell::RefCell;
+
+        let decision = DeployDecision {
+            should_deploy: true,
+            squares: vec![2, 7, 11, 19],
+            total_amount_lamports: 40_000_000,
+            per_square_lamports: 10_000_000,
+            expected_ore: 0.2,
+            reasoning: "test".to_string(),
+            skip_reason: None,
+        };
+
+        // Only 0.021 SOL is deployable: the retry is cut to (0.021 - fee reserve) / 4 per square
+        let sent = RefCell::new(Vec::new());
+        let send = |d: DeployDecision| {
+            sent.borrow_mut().push(d.total_amount_lamports);
+            let ok = d.total_amount_lamports <= 21_000_000 - DEPLOY_FEE_RESERVE_LAMPORTS;
+            async move { if ok { Ok("sig".to_string()) } else { Err(DeployError::InsufficientFunds.into()) } }
+        };
+        let (signature, reduced) = deploy_with_retry(&decision, send, || Ok(21_000_000), || true).await.unwrap();
+        assert_eq!(signature, "sig");
+        assert_eq!(reduced.per_square_lamports, 5_225_000);
+        assert_eq!(reduced.squares, decision.squares);
+        assert_eq!(*sent.borrow(), vec![40_000_000, 20_900_000]);
+
+        // Still short on the retry: exactly one retry, then the error
+        let sent = RefCell::new(0);
+        let always_short = |_: DeployDecision| {
+            *sent.borrow_mut() += 1;
+            async { Err::<String, _>(DeployError::InsufficientFunds.into()) }
+        };
+        let err = deploy_with_retry(&decision, always_short, || Ok(21_000_000), || true).await.unwrap_err();
+        assert!(matches!(err, BotError::Deploy(DeployError::InsufficientFunds)));
+        assert_eq!(*sent.borrow(), 2);
+
+        // No retry once the window closed, or for other failures
+        let sent = RefCell::new(0);
+        let short = |_: DeployDecision| {
+            *sent.borrow_mut() += 1;
+            async { Err::<String, _>(DeployError::InsufficientFunds.into()) }
+        };
+        assert!(deploy_with_retry(&decision, short, || Ok(21_000_000), || false).await.is_err());
+        let ended = |_: DeployDecision| {
+            *sent.borrow_mut() += 1;
+            async { Err::<String, _>(DeployError::RoundEnded.into()) }
+        };
+        assert!(deploy_with_retry(&decision, ended, || Ok(21_000_000), || true).await.is_err());
+        assert_eq!(*sent.borrow(), 2);
+
+        // Too little left for the per-square minimum
+        assert!(decision.reduced_to_fit(DEPLOY_FEE_RESERVE_LAMPORTS + 30_000).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_deploy_failure_handled_by_action() {
+        use crate::error::DeployError;
+        use std::cell::RefCell;
+
+        let decision = DeployDecision {
+            should_deploy: true,
+            squares: vec![2, 7],
+            total_amount_lamports: 20_000_000,
+            per_square_lamports: 10_000_000,
+            expected_ore: 0.1,
+            reasoning: "test".to_string(),
+            skip_reason: None,
+        };
+
+        // Blockhash expired: sent again unchanged
+        let sent = RefCell::new(Vec::new());
+        let flaky = |d: DeployDecision| {
+            sent.borrow_mut().push(d.total_amount_lamports);
+            let first = sent.borrow().len() == 1;
+            async move { if first { Err(DeployError::BlockhashExpired.into()) } else { Ok("sig".to_string()) } }
+        };
+        let (signature, resent) = deploy_with_retry(&decision, flaky, || Ok(0), || true).await.unwrap();
+        assert_eq!(signature, "sig");
+        assert_eq!(resent.total_amount_lamports, decision.total_amount_lamports);
+        assert_eq!(*sent.borrow(), vec![20_000_000, 20_000_000]);
+
+        // Not once the round closed
+        let sent = RefCell::new(0);
+        let expired = |_: DeployDecision| {
+            *sent.borrow_mut() += 1;
+            async { Err::<String, _>(DeployError::BlockhashExpired.into()) }
+        };
+        assert!(deploy_with_retry(&decision, expired, || Ok(0), || false).await.is_err());
+        assert_eq!(*sent.borrow(), 1);
+
+        // Missing account: one attempt, handed back for the miner to halt on
+        let sent = RefCell::new(0);
+        let missing = |_: DeployDecision| {
+            *sent.borrow_mut() += 1;
+            async { Err::<String, _>(DeployError::AccountNotFound.into()) }
+        };
+        let err = deploy_with_retry(&decision, missing, || Ok(0), || true).await.unwrap_err();
+        assert!(matches!(err, BotError::Deploy(ref reason) if reason.action() == DeployAction::Halt));
+        assert_eq!(*sent.borrow(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_reduced_retry_keeps_wallet_reserve() {
+        use crate::error::DeployError;
+        use std::cell::RefCell;
+
+        let decision = DeployDecision {
+            should_deploy: true,
+            squares: vec![2, 7, 11, 19],
+            total_amount_lamports: 40_000_000,
+            per_square_lamports: 10_000_000,
+            expected_ore: 0.2,
+            reasoning: "test".to_string(),
+            skip_reason: None,
+        };
+        let engine = OreStrategyEngine::new();
+
+        // 0.021 SOL would cover a smaller bet, but it is all min_wallet_sol and claim reserve
+        let wallet = 21_000_000;
+        assert!(decision.reduced_to_fit(wallet).is_some());
+        assert_eq!(engine.deployable_lamports(wallet), 0);
+
+        let sent = RefCell::new(0);
+        let short = |_: DeployDecision| {
+            *sent.borrow_mut() += 1;
+            async { Err::<String, _>(DeployError::InsufficientFunds.into()) }
+        };
+        let err = deploy_with_retry(&decision, short, || Ok(engine.deployable_lamports(wallet)), || true)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, BotError::Deploy(DeployError::InsufficientFunds)));
+        assert_eq!(*sent.borrow(), 1);
+    }
+
+    #[test]
+    fn test_unexplained_balance_drop_pauses() {
+        let mut guard = BalanceGuard::new(sol_to_lamports(0.01));
+        assert_eq!(guard.check(sol_to_lamports(2.0)), Ok(()));
+
+        // Our own deploy plus fees, then a win: nothing to report
+        guard.record_spend(sol_to_lamports(0.5));
+        assert_eq!(guard.check(sol_to_lamports(1.495)), Ok(()));
+        assert_eq!(guard.check(sol_to_lamports(1.8)), Ok(()));
+
+        // 0.3 SOL gone with no deploy sent
+        let drop = guard.check(sol_to_lamports(1.5)).unwrap_err();
+        assert_eq!(drop.expected_lamports, sol_to_lamports(1.8));
+        assert_eq!(drop.shortfall_lamports, sol_to_lamports(0.3));
+
+        // The lower balance is the new baseline
+        assert_eq!(guard.check(sol_to_lamports(1.5)), Ok(()));
+    }
 }
@@ -0,0 +1,43 @@
+//! Round progress helpers
+//!
+//! This file has no dependencies so the dashboard (wasm) can include it
+//! directly with `#[path]` and compute progress exactly like the bots do.
+
+/// Percentage of the round that has elapsed, clamped to 0..=100
+///
+/// `remaining` can exceed `duration` when the slot clock and block times
+/// disagree, which is treated as the round having just started.
+pub fn round_progress_pct(duration: u64, remaining: u64) -> u32 {
+    if duration == 0 {
+        return 0;
+    }
+
+    let elapsed = duration.saturating_sub(remaining);
+    let pct = elapsed as f64 / duration as f64 * 100.0;
+    pct.clamp(0.0, 100.0) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_normal() {
+        assert_eq!(round_progress_pct(60, 60), 0);
+        assert_eq!(round_progress_pct(60, 30), 50);
+        assert_eq!(round_progress_pct(60, 15), 75);
+        assert_eq!(round_progress_pct(60, 0), 100);
+    }
+
+    #[test]
+    fn test_progress_remaining_exceeds_duration() {
+        assert_eq!(round_progress_pct(60, 75), 0);
+        assert_eq!(round_progress_pct(60, u64::MAX), 0);
+    }
+
+    #[test]
+    fn test_progress_zero_duration() {
+        assert_eq!(round_progress_pct(0, 0), 0);
+        assert_eq!(round_progress_pct(0, 10), 0);
+    }
+}
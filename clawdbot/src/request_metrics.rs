@@ -0,0 +1,136 @@
+//! Request metrics for the HTTP services
+//!
+//! The API and web servers time every request in a small middleware and feed
+//! the result here: a fixed-bucket latency histogram per method and route,
+//! served as JSON from their metrics endpoints. Routes the dashboard polls
+//! every second would flood the logs, so successful fast requests to those
+//! are only logged every `HOT_LOG_EVERY`th time.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Upper bounds (ms) of the latency buckets; slower requests land in the last, open bucket
+pub const LATENCY_BUCKETS_MS: [f64; 10] = [1.0, 2.0, 5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0];
+
+/// Log one in this many successful requests to a hot route
+pub const HOT_LOG_EVERY: u64 = 20;
+
+/// Requests slower than this are always logged
+pub const SLOW_REQUEST_MS: f64 = 500.0;
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LatencyHistogram {
+    /// One count per `LATENCY_BUCKETS_MS` bound, plus the overflow bucket
+    pub buckets: Vec<u64>,
+    pub count: u64,
+    pub sum_ms: f64,
+    pub max_ms: f64,
+}
+
+impl LatencyHistogram {
+    pub fn record(&mut self, latency_ms: f64) {
+        if self.buckets.is_empty() {
+            self.buckets = vec![0; LATENCY_BUCKETS_MS.len() + 1];
+        }
+        let bucket = LATENCY_BUCKETS_MS
+            .iter()
+            .position(|&bound| latency_ms <= bound)
+            .unwrap_or(LATENCY_BUCKETS_MS.len());
+        self.buckets[bucket] += 1;
+        self.count += 1;
+        self.sum_ms += latency_ms;
+        self.max_ms = self.max_ms.max(latency_ms);
+    }
+
+    pub fn mean_ms(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.sum_ms / self.count as f64
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RouteMetrics {
+    pub method: String,
+    pub route: String,
+    pub latency: LatencyHistogram,
+    /// Responses with status 400 or above
+    pub errors: u64,
+}
+
+/// Per method+route request histograms, shared by every request task
+#[derive(Debug, Clone, Default)]
+pub struct RequestMetrics {
+    routes: Arc<Mutex<HashMap<(String, String), RouteMetrics>>>,
+    hot_routes: Arc<Vec<String>>,
+}
+
+impl RequestMetrics {
+    /// `hot_routes` are matched route patterns (e.g. `/api/state`) whose logs are sampled
+    pub fn new(hot_routes: &[&str]) -> Self {
+        Self {
+            routes: Arc::default(),
+            hot_routes: Arc::new(hot_routes.iter().map(|r| r.to_string()).collect()),
+        }
+    }
+
+    /// Record one request; returns whether it should be logged
+    pub fn record(&self, method: &str, route: &str, status: u16, latency_ms: f64) -> bool {
+        let mut routes = self.routes.lock().unwrap();
+        let entry = routes
+            .entry((method.to_string(), route.to_string()))
+            .or_insert_with(|| RouteMetrics {
+                method: method.to_string(),
+                route: route.to_string(),
+                ..Default::default()
+            });
+        entry.latency.record(latency_ms);
+        if status >= 400 {
+            entry.errors += 1;
+        }
+
+        let hot = self.hot_routes.iter().any(|r| r == route);
+        !hot || status >= 400 || latency_ms >= SLOW_REQUEST_MS || entry.latency.count % HOT_LOG_EVERY == 1
+    }
+
+    /// Every route seen so far, busiest first
+    pub fn snapshot(&self) -> Vec<RouteMetrics> {
+        let mut routes: Vec<RouteMetrics> = self.routes.lock().unwrap().values().cloned().collect();
+        routes.sort_by(|a, b| b.latency.count.cmp(&a.latency.count).then_with(|| a.route.cmp(&b.route)));
+        routes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_and_hot_route_sampling() {
+        let metrics = RequestMetrics::new(&["/api/state"]);
+
+        // Cold routes are always logged
+        assert!(metrics.record("GET", "/api/bots", 200, 3.0));
+
+        // Hot routes: first request, then every HOT_LOG_EVERY-th, plus errors and slow ones
+        let logged = (0..HOT_LOG_EVERY * 2)
+            .filter(|_| metrics.record("GET", "/api/state", 200, 0.5))
+            .count();
+        assert_eq!(logged, 2);
+        assert!(metrics.record("GET", "/api/state", 503, 0.5));
+        assert!(metrics.record("GET", "/api/state", 200, 800.0));
+
+        let snapshot = metrics.snapshot();
+        let state = &snapshot[0];
+        assert_eq!(state.route, "/api/state");
+        assert_eq!(state.latency.count, HOT_LOG_EVERY * 2 + 2);
+        assert_eq!(state.errors, 1);
+        assert_eq!(state.latency.buckets[0], HOT_LOG_EVERY * 2 + 1); // <= 1ms
+        assert_eq!(state.latency.buckets[LATENCY_BUCKETS_MS.len()], 0);
+        assert_eq!(state.latency.buckets[9], 1); // 800ms lands in the <= 1000ms bucket
+        assert_eq!(state.latency.max_ms, 800.0);
+    }
+}
@@ -0,0 +1,508 @@
+//! Round flip handling for the coordinator
+//!
+//! When the board's round id moves on, the coordinator resolves the completed
+//! round's winner, feeds it to the strategy engines, credits the tracked
+//! deploys that hit the winning square and announces the new round. That step
+//! lives here, behind `RoundSource` and `StateStore`, so one iteration can be
+//! driven against fakes; the coordinator keeps the database writes and runs
+//! them from the returned `RoundSummary`.
+
+use crate::blockchain_parser::{resolve_winning_square, BlockchainParser, ResetData, WinnerOrigin, WinnerResolution};
+use crate::config::WinnerSource;
+use crate::db::Signal;
+use crate::error::Result;
+use crate::learning_engine::{LearningEngine, WinRecord, WinnerAllocation};
+use crate::ore_strategy::OreStrategyEngine;
+use crate::state_store::{completed_round, StateStore};
+use crate::strategies::{RoundHistory, StrategyEngine};
+use crate::units::lamports_to_sol;
+use colored::*;
+use log::{info, warn};
+use ore_api::state::Round;
+use std::collections::HashMap;
+
+/// Rounds with less SOL than this paid a full ORE to the winner
+const FULL_ORE_MAX_POT_SOL: f64 = 2.0;
+
+/// Winner keys and deferred rounds older than this many rounds are dropped on a flip
+const WINNER_RETENTION_ROUNDS: u64 = 10;
+
+/// The chain reads the round flip needs
+pub trait RoundSource {
+    /// Winning square (0-24) and motherlode flag, None while the round is open
+    fn get_round_result(&self, round_id: u64) -> Result<Option<(u8, bool)>>;
+
+    fn get_round(&self, round_id: u64) -> Result<Round>;
+}
+
+impl RoundSource for BlockchainParser {
+    fn get_round_result(&self, round_id: u64) -> Result<Option<(u8, bool)>> {
+        BlockchainParser::get_round_result(self, round_id)
+    }
+
+    fn get_round(&self, round_id: u64) -> Result<Round> {
+        BlockchainParser::get_round(self, round_id)
+    }
+}
+
+/// What the coordinator learned about a completed round
+#[derive(Debug, Clone)]
+pub struct RoundSummary {
+    pub round_id: u64,
+    /// 1-25
+    pub winning_square: u8,
+    pub motherlode: bool,
+    /// Where the winner came from, None if it could not be determined
+    pub origin: Option<WinnerOrigin>,
+    /// Round account and Reset event reported different squares
+    pub disagreement: bool,
+    pub history: RoundHistory,
+    /// One record per tracked deployer that hit the winning square
+    pub wins: Vec<WinRecord>,
+    pub total_deployed: u64,
+    pub num_deployers: u32,
+    pub is_full_ore: bool,
+}
+
+/// Result of a detected round flip
+#[derive(Debug, Clone)]
+pub struct RoundFlip {
+    pub completed_round_id: u64,
+    /// None if the completed Round account could not be read, or if its
+    /// winner isn't known yet (recorded later by `RoundLifecycle::on_reset`)
+    pub summary: Option<RoundSummary>,
+}
+
+/// A flipped round still waiting for the configured winner source
+struct DeferredRound {
+    round: Round,
+    /// Tracked deploys at the flip, squares 1-25
+    deploys: HashMap<String, (u64, Vec<u8>)>,
+}
+
+/// Round-to-round state of the coordinator loop
+pub struct RoundLifecycle {
+    source_bot: String,
+    winner_source: WinnerSource,
+    pub last_round_id: u64,
+    /// Tracked deploys this round: authority -> (lamports, squares 1-25)
+    pub round_deploys: HashMap<String, (u64, Vec<u8>)>,
+    /// `round_deploys` of the round that just completed, kept for Reset win detection
+    pub previous_round_deploys: HashMap<String, (u64, Vec<u8>)>,
+    /// Winning squares (0-24) read from Round accounts, by round
+    pub round_account_winners: HashMap<u64, u8>,
+    /// Winning squares (0-24) parsed from Reset transactions, by round
+    pub reset_event_winners: HashMap<u64, (u8, WinnerOrigin)>,
+    deferred_rounds: HashMap<u64, DeferredRound>,
+}
+
+impl RoundLifecycle {
+    pub fn new(source_bot: &str, winner_source: WinnerSource) -> Self {
+        Self {
+            source_bot: source_bot.to_string(),
+            winner_source,
+            last_round_id: 0,
+            round_deploys: HashMap::new(),
+            previous_round_deploys: HashMap::new(),
+            round_account_winners: HashMap::new(),
+            reset_event_winners: HashMap::new(),
+            deferred_rounds: HashMap::new(),
+        }
+    }
+
+    /// Run one board observation; returns the flip if `current_round` completed the last one
+    pub async fn on_board<C: RoundSource, S: StateStore>(
+        &mut self,
+        chain: &C,
+        store: &S,
+        current_round: u64,
+        strategy_engine: &mut StrategyEngine,
+        ore_strategy: &mut OreStrategyEngine,
+        learning_engine: &mut LearningEngine,
+    ) -> Option<RoundFlip> {
+        let completed_round_id = completed_round(self.last_round_id, current_round)?;
+        info!("{}", format!("🆕 NEW ROUND DETECTED: {} → {}", completed_round_id, current_round).green().bold());
+
+        // The Reset transaction arrives with this cycle's transactions, so the
+        // completed round's deploys are kept until it has been processed
+        self.previous_round_deploys = self.round_deploys.clone();
+        self.round_account_winners.retain(|round, _| *round + WINNER_RETENTION_ROUNDS >= completed_round_id);
+        self.reset_event_winners.retain(|round, _| *round + WINNER_RETENTION_ROUNDS >= completed_round_id);
+        self.deferred_rounds.retain(|round, _| *round + WINNER_RETENTION_ROUNDS >= completed_round_id);
+        info!("📋 Saved {} deploys from round {} for win detection",
+            self.previous_round_deploys.len(), completed_round_id);
+
+        let summary = self.complete_round(
+            chain,
+            completed_round_id,
+            strategy_engine,
+            ore_strategy,
+            learning_engine,
+        );
+
+        let signal = Signal::round_started(&self.source_bot, current_round);
+        if let Err(e) = store.send_signal(&signal).await {
+            warn!("Failed to send round_started signal: {}", e);
+        } else {
+            info!("📤 Sent ROUND_STARTED signal");
+        }
+
+        self.last_round_id = current_round;
+        Some(RoundFlip { completed_round_id, summary })
+    }
+
+    /// Feed a Reset event; completes the round if its flip was waiting on it
+    pub fn on_reset(
+        &mut self,
+        reset: &ResetData,
+        strategy_engine: &mut StrategyEngine,
+        ore_strategy: &mut OreStrategyEngine,
+        learning_engine: &mut LearningEngine,
+    ) -> Option<RoundSummary> {
+        self.reset_event_winners.insert(reset.round_id, (reset.winning_square, reset.origin));
+        if !self.deferred_rounds.contains_key(&reset.round_id) {
+            return None;
+        }
+        let resolved = resolve_winning_square(
+            self.winner_source,
+            reset.round_id,
+            self.round_account_winners.get(&reset.round_id).copied(),
+            Some((reset.winning_square, reset.origin)),
+        );
+        let winning_square = resolved.winning_square?;
+        let deferred = self.deferred_rounds.remove(&reset.round_id)?;
+        info!("⏩ Round {} winner arrived with its Reset event", reset.round_id);
+        Some(record_completed_round(
+            reset.round_id,
+            winning_square,
+            reset.motherlode,
+            resolved,
+            &deferred.round.deployed,
+            &deferred.deploys,
+            strategy_engine,
+            ore_strategy,
+            learning_engine,
+        ))
+    }
+
+    fn complete_round<C: RoundSource>(
+        &mut self,
+        chain: &C,
+        round_id: u64,
+        strategy_engine: &mut StrategyEngine,
+        ore_strategy: &mut OreStrategyEngine,
+        learning_engine: &mut LearningEngine,
+    ) -> Option<RoundSummary> {
+        let winning_result = chain.get_round_result(round_id);
+        let account_square = winning_result.as_ref().ok().and_then(|r| r.map(|(sq, _)| sq));
+        if let Some(sq) = account_square {
+            self.round_account_winners.insert(round_id, sq);
+        }
+        let resolved = resolve_winning_square(
+            self.winner_source,
+            round_id,
+            account_square,
+            self.reset_event_winners.get(&round_id).copied(),
+        );
+        let completed = chain.get_round(round_id).ok()?;
+
+        let Some(winning_square) = resolved.winning_square else {
+            // Usually the Reset event hasn't been seen yet; `on_reset` records it later
+            warn!("⚠️ Could not determine winning square for round {} yet ({:?}), waiting for its Reset",
+                round_id, self.winner_source);
+            self.deferred_rounds.insert(round_id, DeferredRound {
+                round: completed,
+                deploys: self.previous_round_deploys.clone(),
+            });
+            return None;
+        };
+        let motherlode = matches!(winning_result, Ok(Some((_, true))));
+
+        Some(record_completed_round(
+            round_id,
+            winning_square,
+            motherlode,
+            resolved,
+            &completed.deployed,
+            &self.previous_round_deploys,
+            strategy_engine,
+            ore_strategy,
+            learning_engine,
+        ))
+    }
+}
+
+/// Feed a completed round with a resolved winner (0-24) to the engines and credit its winners
+#[allow(clippy::too_many_arguments)]
+fn record_completed_round(
+    round_id: u64,
+    winning_square: u8,
+    motherlode: bool,
+    resolved: WinnerResolution,
+    deployed: &[u64; 25],
+    deploys: &HashMap<String, (u64, Vec<u8>)>,
+    strategy_engine: &mut StrategyEngine,
+    ore_strategy: &mut OreStrategyEngine,
+    learning_engine: &mut LearningEngine,
+) -> RoundSummary {
+    // Strategy history keeps ore_api's 0-24; win records, tracked deploys and the DB use 1-25
+    let winning_sq_idx = winning_square;
+    let winning_square = winning_sq_idx + 1;
+    info!("🎯 Round {} RESULT: Winning square {} {}",
+        round_id, winning_square, if motherlode { "🎰 MOTHERLODE!" } else { "" });
+
+    let total_deployed: u64 = deployed.iter().sum();
+    let history = RoundHistory {
+        round_id,
+        winning_square: winning_sq_idx,
+        deployed: *deployed,
+        total_pot: total_deployed,
+        motherlode,
+        timestamp: None,
+    };
+    strategy_engine.add_round(history.clone());
+    ore_strategy.record_round(&history.deployed, winning_square);
+    info!("📚 Added round {} to strategy history (winning square: {})", round_id, winning_square);
+
+    // Credit tracked deploys that hit the winning square
+    let competition_on_sq = deployed.get(winning_sq_idx as usize).copied().unwrap_or(0);
+    let is_full_ore = lamports_to_sol(total_deployed) < FULL_ORE_MAX_POT_SOL;
+    let ore_earned = if is_full_ore { 1.0 } else { 0.5 };
+    let num_deployers = deploys.len() as u32;
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64;
+
+    let mut wins = Vec::new();
+    for (address, (deploy_amount, squares)) in deploys {
+        if !squares.contains(&winning_square) {
+            continue;
+        }
+        let num_squares = squares.len() as u8;
+        let allocation = WinnerAllocation::compute(
+            *deploy_amount,
+            squares.len(),
+            competition_on_sq,
+            total_deployed,
+        );
+        let win = WinRecord {
+            round_id,
+            winner_address: address.clone(),
+            winning_square,
+            squares_bet: squares.clone(),
+            amount_bet: *deploy_amount,
+            amount_won: allocation.amount_won,
+            num_squares,
+            total_round_sol: total_deployed,
+            num_deployers,
+            is_motherlode: motherlode,
+            is_full_ore,
+            ore_earned,
+            competition_on_square: competition_on_sq,
+            winner_share_pct: allocation.share,
+            slot: 0,
+            timestamp: Some(timestamp),
+        };
+        learning_engine.record_win(win.clone());
+        ore_strategy.record_win(address, allocation.amount_won, ore_earned, num_squares);
+        wins.push(win);
+    }
+
+    if !wins.is_empty() {
+        info!("🏆 Detected {} winners on square {} (full ORE: {})",
+            wins.len(), winning_square, is_full_ore);
+    }
+
+    RoundSummary {
+        round_id,
+        winning_square,
+        motherlode,
+        origin: resolved.origin,
+        disagreement: resolved.disagreement.is_some(),
+        history,
+        wins,
+        total_deployed,
+        num_deployers,
+        is_full_ore,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::SignalType;
+    use crate::error::BotError;
+    use crate::state_store::{publish_cycle_heartbeat, read_snapshot_key, MemoryStateStore, StateBackend};
+    use crate::units::sol_to_lamports;
+
+    /// Completed rounds served from memory instead of RPC
+    struct FakeRounds {
+        rounds: HashMap<u64, (Round, Option<(u8, bool)>)>,
+    }
+
+    impl RoundSource for FakeRounds {
+        fn get_round_result(&self, round_id: u64) -> Result<Option<(u8, bool)>> {
+            Ok(self.rounds.get(&round_id).and_then(|(_, result)| *result))
+        }
+
+        fn get_round(&self, round_id: u64) -> Result<Round> {
+            self.rounds
+                .get(&round_id)
+                .map(|(round, _)| *round)
+                .ok_or_else(|| BotError::Other(format!("round {} not found", round_id)))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_round_flip_records_wins_and_signals() {
+        // Round 100: 0.1 SOL on each square, square 8 (index 7) wins
+        let mut round: Round = bytemuck::Zeroable::zeroed();
+        round.deployed = [sol_to_lamports(0.1); 25];
+        let chain = FakeRounds {
+            rounds: HashMap::from([(100, (round, Some((7, false)))), (101, (round, Some((24, true))))]),
+        };
+        let store = MemoryStateStore::new();
+        let mut strategy_engine = StrategyEngine::new();
+        let mut ore_strategy = OreStrategyEngine::new();
+        let mut learning_engine = LearningEngine::new();
+
+        let mut lifecycle = RoundLifecycle::new("coordinator", WinnerSource::RoundAccount);
+        lifecycle.last_round_id = 100;
+        lifecycle.round_deploys.insert("winner".to_string(), (sol_to_lamports(0.1), vec![3, 8]));
+        lifecycle.round_deploys.insert("loser".to_string(), (sol_to_lamports(0.05), vec![1]));
+
+        // Same round again: nothing to do
+        assert!(lifecycle
+            .on_board(&chain, &store, 100, &mut strategy_engine, &mut ore_strategy, &mut learning_engine)
+            .await
+            .is_none());
+
+        let flip = lifecycle
+            .on_board(&chain, &store, 101, &mut strategy_engine, &mut ore_strategy, &mut learning_engine)
+            .await
+            .expect("round flip");
+        assert_eq!(flip.completed_round_id, 100);
+        assert_eq!(lifecycle.last_round_id, 101);
+        assert_eq!(lifecycle.previous_round_deploys.len(), 2);
+
+        let summary = flip.summary.expect("completed round");
+        assert_eq!(summary.winning_square, 8);
+        assert_eq!(summary.origin, Some(WinnerOrigin::RoundAccount));
+        assert_eq!(summary.num_deployers, 2);
+        assert!(!summary.is_full_ore);
+
+        // Only the deployer on square 8 won, with half their bet on it
+        assert_eq!(summary.wins.len(), 1);
+        let win = &summary.wins[0];
+        let expected = WinnerAllocation::compute(sol_to_lamports(0.1), 2, sol_to_lamports(0.1), sol_to_lamports(2.5));
+        assert_eq!(win.winner_address, "winner");
+        assert_eq!(win.amount_won, expected.amount_won);
+        assert_eq!(win.competition_on_square, sol_to_lamports(0.1));
+
+        let signals = store.recent_signals();
+        assert_eq!(signals.len(), 1);
+        assert_eq!(signals[0].signal_type, SignalType::RoundStarted);
+        assert_eq!(signals[0].payload["round_id"], 101);
+
+        assert_eq!(strategy_engine.history_count(), 1);
+        assert_eq!(summary.history.winning_square, 7);
+        let stats = strategy_engine.square_stats();
+        assert_eq!(stats[7].wins, 1);
+        assert_eq!(stats[7].total_deployed_when_won, sol_to_lamports(0.1));
+        assert_eq!(stats.iter().map(|s| s.wins).sum::<u32>(), 1);
+        assert!(stats.iter().all(|s| s.total_rounds == 1));
+
+        // Square 25 lands on the last index
+        let summary = lifecycle
+            .on_board(&chain, &store, 102, &mut strategy_engine, &mut ore_strategy, &mut learning_engine)
+            .await
+            .and_then(|flip| flip.summary)
+            .expect("completed round");
+        assert_eq!(summary.winning_square, 25);
+        assert!(summary.motherlode);
+        assert_eq!(strategy_engine.square_stats()[24].wins, 1);
+    }
+
+    #[tokio::test]
+    async fn test_round_waits_for_reset_event_winner() {
+        let mut round: Round = bytemuck::Zeroable::zeroed();
+        round.deployed = [sol_to_lamports(0.1); 25];
+        // The Round account already knows square 3, but the configured source is the Reset event
+        let chain = FakeRounds {
+            rounds: HashMap::from([(100, (round, Some((2, false))))]),
+        };
+        let store = MemoryStateStore::new();
+        let mut strategy_engine = StrategyEngine::new();
+        let mut ore_strategy = OreStrategyEngine::new();
+        let mut learning_engine = LearningEngine::new();
+
+        let mut lifecycle = RoundLifecycle::new("coordinator", WinnerSource::ResetEvent);
+        lifecycle.last_round_id = 100;
+        lifecycle.round_deploys.insert("winner".to_string(), (sol_to_lamports(0.1), vec![5]));
+
+        let flip = lifecycle
+            .on_board(&chain, &store, 101, &mut strategy_engine, &mut ore_strategy, &mut learning_engine)
+            .await
+            .expect("round flip");
+        assert!(flip.summary.is_none());
+        assert_eq!(strategy_engine.history_count(), 0);
+
+        // Deploys are cleared at the end of the cycle; the deferred round kept its own copy
+        lifecycle.round_deploys.clear();
+        lifecycle.previous_round_deploys.clear();
+
+        let reset = ResetData { round_id: 100, winning_square: 4, motherlode: false, origin: WinnerOrigin::ResetEvent };
+        let summary = lifecycle
+            .on_reset(&reset, &mut strategy_engine, &mut ore_strategy, &mut learning_engine)
+            .expect("deferred round recorded");
+        assert_eq!(summary.winning_square, 5);
+        assert_eq!(summary.origin, Some(WinnerOrigin::ResetEvent));
+        assert!(summary.disagreement);
+        assert_eq!(summary.wins.len(), 1);
+        assert_eq!(strategy_engine.history_count(), 1);
+
+        // Only recorded once
+        assert!(lifecycle
+            .on_reset(&reset, &mut strategy_engine, &mut ore_strategy, &mut learning_engine)
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_coordinator_cycle_without_database() {
+        let mut round: Round = bytemuck::Zeroable::zeroed();
+        round.deployed = [sol_to_lamports(0.1); 25];
+        let chain = FakeRounds {
+            rounds: HashMap::from([(100, (round, Some((7, false)))), (101, (round, None))]),
+        };
+        let path = std::env::temp_dir().join(format!("clawdbot-cycle-{}.json", std::process::id()));
+        let store = StateBackend::Memory(MemoryStateStore::with_snapshot_file(&path));
+        let mut strategy_engine = StrategyEngine::new();
+        let mut ore_strategy = OreStrategyEngine::new();
+        let mut learning_engine = LearningEngine::new();
+        let mut lifecycle = RoundLifecycle::new("coordinator", WinnerSource::RoundAccount);
+        lifecycle.last_round_id = 100;
+
+        // One cycle: the board moved to round 101, so round 100 completes
+        let flip = lifecycle
+            .on_board(&chain, &store, 101, &mut strategy_engine, &mut ore_strategy, &mut learning_engine)
+            .await
+            .expect("round flip");
+        assert!(flip.summary.is_some());
+
+        let current = chain.get_round(101).unwrap();
+        let consensus = strategy_engine.get_consensus_recommendation_n(&current.deployed, 5);
+        store.set_state("consensus_recommendation", serde_json::json!({
+            "squares": consensus.squares,
+            "confidence": consensus.confidence,
+        })).await.unwrap();
+        publish_cycle_heartbeat(&store, "coordinator", lifecycle.last_round_id, true).await.unwrap();
+        store.flush().await.unwrap();
+
+        // The dashboard reads the cycle's output from the snapshot file
+        assert_eq!(read_snapshot_key(&path, "current_round").unwrap(), Some(serde_json::json!(101)));
+        let published = read_snapshot_key(&path, "consensus_recommendation").unwrap().unwrap();
+        assert_eq!(published["squares"], serde_json::json!(consensus.squares));
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -0,0 +1,391 @@
+//! Round phases
+//!
+//! Where the current round is relative to its end slot, computed once per
+//! cycle from slots remaining and the deploy mode's timing thresholds so the
+//! bots branch on a phase instead of re-comparing seconds everywhere. Also
+//! spots boards that say the game isn't taking deploys at all (paused or
+//! stuck after admin instructions) so the miner can pause instead of failing,
+//! and holds back the coordinator's deploy signals while it catches up after
+//! a restart or a long gap.
+
+use crate::bot::BotStatus;
+use crate::db::SignalType;
+use crate::error::DeployError;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+/// Solana targets ~400ms/slot but often runs faster (~350-380ms)
+pub const SLOTS_PER_SECOND: f64 = 2.7;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundPhase {
+    /// Too early to decide; the board will still change a lot
+    Early,
+    /// Decide now and wait for the signing window
+    Decision,
+    /// Sign and send the deploy
+    Signing,
+    /// A deploy sent now would likely land after the round closes
+    TooLate,
+    /// The end slot has passed
+    Ended,
+}
+
+/// Seconds-remaining thresholds for one deploy mode
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PhaseThresholds {
+    pub decision_secs: f64,
+    pub sign_secs: f64,
+    pub too_late_secs: f64,
+}
+
+impl PhaseThresholds {
+    /// We sign deploys ourselves (slower, 3-5s buffer needed)
+    pub const MANUAL: Self = Self {
+        decision_secs: 5.0,
+        sign_secs: 3.0,
+        too_late_secs: 1.5,
+    };
+
+    /// Automation is pre-funded, so we can push much closer to 0
+    pub const EXECUTOR: Self = Self {
+        decision_secs: 2.0, // Start analyzing
+        sign_secs: 0.8,     // Execute here - max intel, still safe
+        too_late_secs: 0.4, // ~1 slot, too risky
+    };
+
+    pub fn for_mode(mode: &str) -> Self {
+        if mode == "executor" {
+            Self::EXECUTOR
+        } else {
+            Self::MANUAL
+        }
+    }
+}
+
+/// Estimated seconds until the end slot
+pub fn secs_remaining(slots_remaining: u64) -> f64 {
+    slots_remaining as f64 / SLOTS_PER_SECOND
+}
+
+impl RoundPhase {
+    pub fn from_slots(slots_remaining: u64, thresholds: &PhaseThresholds) -> Self {
+        if slots_remaining == 0 {
+            return RoundPhase::Ended;
+        }
+
+        let secs = secs_remaining(slots_remaining);
+        if secs <= thresholds.too_late_secs {
+            RoundPhase::TooLate
+        } else if secs <= thresholds.sign_secs {
+            RoundPhase::Signing
+        } else if secs <= thresholds.decision_secs {
+            RoundPhase::Decision
+        } else {
+            RoundPhase::Early
+        }
+    }
+
+    pub fn from_board(current_slot: u64, end_slot: u64, thresholds: &PhaseThresholds) -> Self {
+        Self::from_slots(end_slot.saturating_sub(current_slot), thresholds)
+    }
+
+    /// Decision or signing window
+    pub fn is_closing(&self) -> bool {
+        matches!(self, RoundPhase::Decision | RoundPhase::Signing)
+    }
+
+    /// Whether a poller `poll_interval_secs` apart has to warn now: the next
+    /// poll could land after the decision window opens, or past it entirely
+    pub fn ending_before_next_poll(slots_remaining: u64, poll_interval_secs: f64, thresholds: &PhaseThresholds) -> bool {
+        let secs = secs_remaining(slots_remaining);
+        slots_remaining > 0
+            && secs > thresholds.too_late_secs
+            && secs <= poll_interval_secs + thresholds.decision_secs
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RoundPhase::Early => "early",
+            RoundPhase::Decision => "decision",
+            RoundPhase::Signing => "signing",
+            RoundPhase::TooLate => "too_late",
+            RoundPhase::Ended => "ended",
+        }
+    }
+}
+
+impl std::fmt::Display for RoundPhase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Slots past the end slot without a reset before the game counts as stalled (~1 min)
+pub const RESET_GRACE_SLOTS: u64 = 150;
+
+/// Why the board says the game isn't accepting deploys
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum GameHalt {
+    /// The round ended long ago and no reset has opened the next one
+    ResetOverdue { round_id: u64, slots_past_end: u64 },
+    /// Start slot after end slot - not a state the program leaves during play
+    InvalidBoard { round_id: u64, start_slot: u64, end_slot: u64 },
+}
+
+impl std::fmt::Display for GameHalt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GameHalt::ResetOverdue { round_id, slots_past_end } => write!(
+                f,
+                "round {} ended {} slots ago with no reset (program paused?)",
+                round_id, slots_past_end
+            ),
+            GameHalt::InvalidBoard { round_id, start_slot, end_slot } => write!(
+                f,
+                "round {} has start slot {} after end slot {}",
+                round_id, start_slot, end_slot
+            ),
+        }
+    }
+}
+
+impl From<GameHalt> for DeployError {
+    fn from(halt: GameHalt) -> Self {
+        DeployError::GameHalted(halt.to_string())
+    }
+}
+
+/// Ok if the board looks like a game taking deploys
+/// `end_slot == u64::MAX` is a round waiting for its first deploy, which is fine
+pub fn check_accepting_deploys(round_id: u64, start_slot: u64, end_slot: u64, current_slot: u64) -> Result<(), GameHalt> {
+    if end_slot == u64::MAX {
+        return Ok(());
+    }
+    if start_slot > end_slot {
+        return Err(GameHalt::InvalidBoard { round_id, start_slot, end_slot });
+    }
+    let slots_past_end = current_slot.saturating_sub(end_slot);
+    if slots_past_end > RESET_GRACE_SLOTS {
+        return Err(GameHalt::ResetOverdue { round_id, slots_past_end });
+    }
+    Ok(())
+}
+
+/// Pauses a bot while the game is halted and resumes it when the board recovers
+/// A bot paused for other reasons is left alone
+#[derive(Debug, Clone, Default)]
+pub struct GameGate {
+    halted: Option<GameHalt>,
+}
+
+impl GameGate {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Why we paused, if we did
+    pub fn reason(&self) -> Option<&GameHalt> {
+        self.halted.as_ref()
+    }
+
+    /// Status the bot should move to after this board check
+    pub fn update(&mut self, status: BotStatus, check: Result<(), GameHalt>) -> BotStatus {
+        match check {
+            Err(halt) => {
+                if status != BotStatus::Running && self.halted.is_none() {
+                    return status;
+                }
+                if self.halted.is_none() {
+                    warn!("⏸️  Game not accepting deploys: {} - pausing", halt);
+                }
+                self.halted = Some(halt);
+                BotStatus::Paused
+            }
+            Ok(()) => match self.halted.take() {
+                Some(halt) if status == BotStatus::Paused => {
+                    info!("▶️  Game accepting deploys again (was: {}) - resuming", halt);
+                    BotStatus::Running
+                }
+                _ => status,
+            },
+        }
+    }
+}
+
+/// Catch-up mode for the coordinator
+/// After startup or a gap longer than `max_gap_secs` between cycles, the round in
+/// progress was only partly observed, so the coordinator ingests data without
+/// sending deploy signals until it has seen a new round begin live.
+#[derive(Debug, Clone)]
+pub struct CatchUp {
+    max_gap_secs: i64,
+    last_cycle: Option<i64>,
+    /// Round in progress when catch-up began
+    behind_round: Option<u64>,
+}
+
+impl CatchUp {
+    pub fn new(max_gap_secs: u64) -> Self {
+        Self {
+            max_gap_secs: max_gap_secs as i64,
+            last_cycle: None,
+            behind_round: None,
+        }
+    }
+
+    /// Start of a cycle at `now` (unix secs) with the board on `round_id`
+    /// Returns true while deploy signals should be held back
+    pub fn observe(&mut self, now: i64, round_id: u64) -> bool {
+        let gap = self.last_cycle.map(|last| now - last > self.max_gap_secs).unwrap_or(true);
+        self.last_cycle = Some(now);
+
+        if gap {
+            if self.behind_round.is_none() {
+                info!("⏩ Catching up from round {} - ingesting only until the next round starts", round_id);
+            }
+            self.behind_round = Some(round_id);
+        }
+
+        match self.behind_round {
+            Some(behind) if round_id > behind => {
+                info!("✅ Synced to live round {} - deploy signals resumed", round_id);
+                self.behind_round = None;
+                false
+            }
+            Some(_) => true,
+            None => false,
+        }
+    }
+
+    pub fn is_catching_up(&self) -> bool {
+        self.behind_round.is_some()
+    }
+
+    /// Whether to send a signal of this type right now
+    /// Only deploy triggers are held back; round and win bookkeeping still goes out
+    pub fn allows(&self, signal_type: &SignalType) -> bool {
+        !self.is_catching_up()
+            || !matches!(signal_type, SignalType::DeployOpportunity | SignalType::RoundEndingSoon)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phase_from_slots_per_mode() {
+        let manual = PhaseThresholds::for_mode("live");
+        let cases = [
+            (0, RoundPhase::Ended),
+            (4, RoundPhase::TooLate),   // 1.48s
+            (5, RoundPhase::Signing),   // 1.85s
+            (8, RoundPhase::Signing),   // 2.96s
+            (9, RoundPhase::Decision),  // 3.33s
+            (13, RoundPhase::Decision), // 4.81s
+            (14, RoundPhase::Early),    // 5.19s
+            (150, RoundPhase::Early),
+        ];
+        for (slots, phase) in cases {
+            assert_eq!(RoundPhase::from_slots(slots, &manual), phase, "manual, {} slots", slots);
+        }
+
+        let executor = PhaseThresholds::for_mode("executor");
+        let cases = [
+            (0, RoundPhase::Ended),
+            (1, RoundPhase::TooLate),  // 0.37s
+            (2, RoundPhase::Signing),  // 0.74s
+            (3, RoundPhase::Decision), // 1.11s
+            (5, RoundPhase::Decision), // 1.85s
+            (6, RoundPhase::Early),    // 2.22s
+        ];
+        for (slots, phase) in cases {
+            assert_eq!(RoundPhase::from_slots(slots, &executor), phase, "executor, {} slots", slots);
+        }
+
+        // Past the end slot counts as ended, not a huge remaining time
+        assert_eq!(RoundPhase::from_board(1_010, 1_000, &executor), RoundPhase::Ended);
+    }
+
+    #[test]
+    fn test_ending_soon_fires_once_per_round_at_default_interval() {
+        // COORDINATOR_INTERVAL defaults to 15s; a 60s round is ~162 slots
+        let interval = 15.0;
+        let poll_slots = (interval * SLOTS_PER_SECOND) as u64;
+        let manual = PhaseThresholds::MANUAL;
+
+        // Wherever the polls fall in the round, one warns ahead of the decision window
+        // (a second may follow inside it; the coordinator only sends the first)
+        for offset in 0..poll_slots {
+            let warned: Vec<u64> = (0..)
+                .map(|i| 162u64.saturating_sub(offset + i * poll_slots))
+                .take_while(|&slots| slots > 0)
+                .filter(|&slots| RoundPhase::ending_before_next_poll(slots, interval, &manual))
+                .collect();
+            assert!(!warned.is_empty(), "offset {}: no warning", offset);
+            assert!(
+                warned.iter().any(|&slots| secs_remaining(slots) >= manual.decision_secs),
+                "offset {}: only warned inside the window ({:?})", offset, warned
+            );
+            assert!(warned.len() <= 2, "offset {}: {:?}", offset, warned);
+        }
+
+        // The decision window alone is narrower than the poll interval and gets skipped over
+        let polls = [162u64, 122, 81, 41, 1];
+        assert!(!polls.iter().any(|&slots| RoundPhase::from_slots(slots, &manual).is_closing()));
+        assert!(polls.iter().any(|&slots| RoundPhase::ending_before_next_poll(slots, interval, &manual)));
+    }
+
+    #[test]
+    fn test_halted_board_pauses_bot() {
+        // Normal boards, including one waiting for its first deploy
+        assert_eq!(check_accepting_deploys(10, 1_000, 1_150, 1_100), Ok(()));
+        assert_eq!(check_accepting_deploys(10, 1_000, 1_150, 1_200), Ok(()));
+        assert_eq!(check_accepting_deploys(10, 1_000, u64::MAX, 9_999), Ok(()));
+
+        let stalled = check_accepting_deploys(10, 1_000, 1_150, 1_500);
+        assert_eq!(stalled, Err(GameHalt::ResetOverdue { round_id: 10, slots_past_end: 350 }));
+        assert!(check_accepting_deploys(10, 2_000, 1_150, 1_100).is_err());
+
+        let mut gate = GameGate::new();
+        let status = gate.update(BotStatus::Running, stalled.clone());
+        assert_eq!(status, BotStatus::Paused);
+        assert!(gate.reason().unwrap().to_string().contains("no reset"));
+        assert!(matches!(DeployError::from(gate.reason().cloned().unwrap()), DeployError::GameHalted(_)));
+
+        // Stays paused while halted, resumes once the board is normal again
+        assert_eq!(gate.update(status, stalled.clone()), BotStatus::Paused);
+        assert_eq!(gate.update(BotStatus::Paused, Ok(())), BotStatus::Running);
+        assert!(gate.reason().is_none());
+
+        // A bot paused by the operator is not resumed by the gate
+        let mut gate = GameGate::new();
+        assert_eq!(gate.update(BotStatus::Paused, stalled), BotStatus::Paused);
+        assert_eq!(gate.update(BotStatus::Paused, Ok(())), BotStatus::Paused);
+    }
+
+    #[test]
+    fn test_first_cycle_after_gap_holds_deploy_signals() {
+        let mut catch_up = CatchUp::new(120);
+
+        // Startup counts as a gap: the round in progress was only partly seen
+        assert!(catch_up.observe(1_000, 50));
+        assert!(!catch_up.allows(&SignalType::DeployOpportunity));
+        assert!(!catch_up.allows(&SignalType::RoundEndingSoon));
+        assert!(catch_up.allows(&SignalType::RoundCompleted));
+        assert!(catch_up.observe(1_010, 50));
+
+        // Synced once a round starts while we're watching
+        assert!(!catch_up.observe(1_020, 51));
+        assert!(catch_up.allows(&SignalType::DeployOpportunity));
+        assert!(!catch_up.observe(1_030, 51));
+
+        // A long gap puts it back into catch-up for the round it lands in
+        assert!(catch_up.observe(1_400, 55));
+        assert!(!catch_up.allows(&SignalType::DeployOpportunity));
+        assert!(!catch_up.observe(1_410, 56));
+    }
+}
@@ -0,0 +1,423 @@
+//! Live state storage for the coordinator
+//!
+//! The coordinator publishes board status, consensus and heartbeats through
+//! `StateStore`. With a database this is the `bot_state`/`signals` tables;
+//! without one, `MemoryStateStore` keeps the latest values in memory and can
+//! mirror them to a JSON snapshot file the web layer reads, written once per
+//! cycle by `flush`. History (rounds,
+//! wins, learning) is only kept when a database is configured. `SignalBudget`
+//! keeps a fast cycle interval from flooding the signals table.
+
+use crate::db::Signal;
+use crate::error::{BotError, Result};
+use log::warn;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "database")]
+use crate::db::SharedDb;
+
+/// Signals kept by the in-memory store before the oldest are discarded
+const MAX_MEMORY_SIGNALS: usize = 500;
+
+/// Key-value state plus outgoing signals
+pub trait StateStore {
+    fn set_state(&self, key: &str, value: serde_json::Value) -> impl Future<Output = Result<()>> + Send;
+
+    fn get_state(&self, key: &str) -> impl Future<Output = Result<Option<serde_json::Value>>> + Send;
+
+    fn send_signal(&self, signal: &Signal) -> impl Future<Output = Result<()>> + Send;
+
+    /// Persist anything buffered since the last call; the coordinator calls this once per cycle
+    fn flush(&self) -> impl Future<Output = Result<()>> + Send {
+        std::future::ready(Ok(()))
+    }
+}
+
+#[cfg(feature = "database")]
+impl StateStore for SharedDb {
+    fn set_state(&self, key: &str, value: serde_json::Value) -> impl Future<Output = Result<()>> + Send {
+        SharedDb::set_state(self, key, value)
+    }
+
+    fn get_state(&self, key: &str) -> impl Future<Output = Result<Option<serde_json::Value>>> + Send {
+        SharedDb::get_state(self, key)
+    }
+
+    fn send_signal(&self, signal: &Signal) -> impl Future<Output = Result<()>> + Send {
+        SharedDb::send_signal(self, signal)
+    }
+}
+
+/// In-memory state used when no database is configured
+#[derive(Clone, Default)]
+pub struct MemoryStateStore {
+    state: Arc<Mutex<HashMap<String, serde_json::Value>>>,
+    signals: Arc<Mutex<VecDeque<Signal>>>,
+    snapshot_path: Option<PathBuf>,
+    /// State changed since the snapshot file was last written
+    dirty: Arc<AtomicBool>,
+}
+
+impl MemoryStateStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also write the full state map to `path` on `flush`
+    pub fn with_snapshot_file(path: impl Into<PathBuf>) -> Self {
+        Self {
+            snapshot_path: Some(path.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Snapshot file from `STATE_SNAPSHOT_PATH`, if set
+    pub fn from_env() -> Self {
+        match std::env::var("STATE_SNAPSHOT_PATH") {
+            Ok(path) if !path.is_empty() => Self::with_snapshot_file(path),
+            _ => Self::new(),
+        }
+    }
+
+    /// Copy of every state key
+    pub fn snapshot(&self) -> HashMap<String, serde_json::Value> {
+        self.state.lock().unwrap().clone()
+    }
+
+    /// Signals sent so far, oldest first
+    pub fn recent_signals(&self) -> Vec<Signal> {
+        self.signals.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// Write the state map to the snapshot file if it changed, off the async runtime
+    async fn write_snapshot(&self) -> Result<()> {
+        let Some(path) = self.snapshot_path.clone() else {
+            return Ok(());
+        };
+        if !self.dirty.swap(false, Ordering::SeqCst) {
+            return Ok(());
+        }
+        let data = serde_json::to_vec(&*self.state.lock().unwrap())
+            .map_err(|e| BotError::Serialization(e.to_string()))?;
+        tokio::task::spawn_blocking(move || std::fs::write(&path, data))
+            .await
+            .map_err(|e| BotError::Other(format!("State snapshot task failed: {}", e)))?
+            .map_err(|e| BotError::Other(format!("Failed to write state snapshot: {}", e)))
+    }
+}
+
+impl StateStore for MemoryStateStore {
+    fn set_state(&self, key: &str, value: serde_json::Value) -> impl Future<Output = Result<()>> + Send {
+        self.state.lock().unwrap().insert(key.to_string(), value);
+        self.dirty.store(true, Ordering::SeqCst);
+        std::future::ready(Ok(()))
+    }
+
+    fn get_state(&self, key: &str) -> impl Future<Output = Result<Option<serde_json::Value>>> + Send {
+        std::future::ready(Ok(self.state.lock().unwrap().get(key).cloned()))
+    }
+
+    fn send_signal(&self, signal: &Signal) -> impl Future<Output = Result<()>> + Send {
+        let mut signals = self.signals.lock().unwrap();
+        if signals.len() >= MAX_MEMORY_SIGNALS {
+            signals.pop_front();
+        }
+        signals.push_back(signal.clone());
+        std::future::ready(Ok(()))
+    }
+
+    fn flush(&self) -> impl Future<Output = Result<()>> + Send {
+        self.write_snapshot()
+    }
+}
+
+/// The coordinator's store: Postgres when available, memory otherwise
+#[derive(Clone)]
+pub enum StateBackend {
+    #[cfg(feature = "database")]
+    Postgres(SharedDb),
+    Memory(MemoryStateStore),
+}
+
+impl StateBackend {
+    pub fn is_persistent(&self) -> bool {
+        !matches!(self, StateBackend::Memory(_))
+    }
+}
+
+impl StateStore for StateBackend {
+    fn set_state(&self, key: &str, value: serde_json::Value) -> impl Future<Output = Result<()>> + Send {
+        async move {
+            match self {
+                #[cfg(feature = "database")]
+                StateBackend::Postgres(db) => StateStore::set_state(db, key, value).await,
+                StateBackend::Memory(mem) => StateStore::set_state(mem, key, value).await,
+            }
+        }
+    }
+
+    fn get_state(&self, key: &str) -> impl Future<Output = Result<Option<serde_json::Value>>> + Send {
+        async move {
+            match self {
+                #[cfg(feature = "database")]
+                StateBackend::Postgres(db) => StateStore::get_state(db, key).await,
+                StateBackend::Memory(mem) => StateStore::get_state(mem, key).await,
+            }
+        }
+    }
+
+    fn send_signal(&self, signal: &Signal) -> impl Future<Output = Result<()>> + Send {
+        async move {
+            match self {
+                #[cfg(feature = "database")]
+                StateBackend::Postgres(db) => StateStore::send_signal(db, signal).await,
+                StateBackend::Memory(mem) => StateStore::send_signal(mem, signal).await,
+            }
+        }
+    }
+
+    fn flush(&self) -> impl Future<Output = Result<()>> + Send {
+        async move {
+            match self {
+                #[cfg(feature = "database")]
+                StateBackend::Postgres(_) => Ok(()),
+                StateBackend::Memory(mem) => mem.flush().await,
+            }
+        }
+    }
+}
+
+/// Caps what the coordinator writes to the signals table
+/// Within a round a signal repeating an earlier one (same type, target and
+/// squares, or same payload apart from its timestamp) is dropped; each type
+/// gets at most `max_per_type` signals per cycle; heartbeats go out at most
+/// once per `heartbeat_interval_secs`.
+pub struct SignalBudget {
+    max_per_type: u32,
+    heartbeat_interval_secs: i64,
+    round_id: u64,
+    sent_this_round: HashSet<String>,
+    sent_this_cycle: HashMap<String, u32>,
+    last_heartbeat: Option<i64>,
+}
+
+impl SignalBudget {
+    /// `max_per_type` of 0 means no per-cycle cap
+    pub fn new(max_per_type: u32, heartbeat_interval_secs: u64) -> Self {
+        Self {
+            max_per_type,
+            heartbeat_interval_secs: heartbeat_interval_secs as i64,
+            round_id: 0,
+            sent_this_round: HashSet::new(),
+            sent_this_cycle: HashMap::new(),
+            last_heartbeat: None,
+        }
+    }
+
+    /// Start a coordinator cycle; a new round forgets what was sent in the last one
+    pub fn begin_cycle(&mut self, round_id: u64) {
+        self.sent_this_cycle.clear();
+        if round_id != self.round_id {
+            self.round_id = round_id;
+            self.sent_this_round.clear();
+        }
+    }
+
+    /// Whether to write `signal`; counts it against the budget if so
+    pub fn allow(&mut self, signal: &Signal) -> bool {
+        let kind = signal.signal_type.to_string();
+        let sent = self.sent_this_cycle.get(&kind).copied().unwrap_or(0);
+        if self.max_per_type > 0 && sent >= self.max_per_type {
+            return false;
+        }
+        if !self.sent_this_round.insert(Self::fingerprint(signal)) {
+            return false;
+        }
+        self.sent_this_cycle.insert(kind, sent + 1);
+        true
+    }
+
+    /// Whether a heartbeat is due at `now` (unix seconds); records it if so
+    pub fn heartbeat_due(&mut self, now: i64) -> bool {
+        if self.last_heartbeat.is_some_and(|last| now - last < self.heartbeat_interval_secs) {
+            return false;
+        }
+        self.last_heartbeat = Some(now);
+        true
+    }
+
+    fn fingerprint(signal: &Signal) -> String {
+        let body = match signal.payload.get("squares") {
+            Some(squares) => squares.clone(),
+            None => {
+                let mut payload = signal.payload.clone();
+                if let Some(obj) = payload.as_object_mut() {
+                    obj.remove("timestamp");
+                }
+                payload
+            }
+        };
+        format!("{}|{}|{}", signal.signal_type, signal.target_bot.as_deref().unwrap_or(""), body)
+    }
+}
+
+/// The `current_round`/`last_update` keys, sent once per cycle, plus a
+/// heartbeat signal when `send_heartbeat` is set
+pub async fn publish_cycle_heartbeat<S: StateStore>(
+    store: &S,
+    bot_name: &str,
+    last_round_id: u64,
+    send_heartbeat: bool,
+) -> Result<()> {
+    let now = chrono::Utc::now().to_rfc3339();
+    if send_heartbeat {
+        let heartbeat = Signal::new(
+            crate::db::SignalType::Heartbeat,
+            bot_name,
+            serde_json::json!({
+                "timestamp": now,
+                "last_round": last_round_id,
+            }),
+        );
+        store.send_signal(&heartbeat).await?;
+    }
+    store.set_state("current_round", serde_json::json!(last_round_id)).await?;
+    store.set_state("last_update", serde_json::json!(now)).await
+}
+
+/// How far behind the board a stored round may be and still be resumed
+/// Past this, the round's deploys are long gone and starting fresh is cleaner
+pub const MAX_RESUME_ROUNDS: u64 = 5;
+
+/// Last round the coordinator saw before it stopped (the `current_round` key)
+pub async fn load_last_round<S: StateStore>(store: &S) -> Option<u64> {
+    match store.get_state("current_round").await {
+        Ok(value) => value.and_then(|v| v.as_u64()).filter(|&round| round > 0),
+        Err(e) => {
+            warn!("Failed to load last round: {}", e);
+            None
+        }
+    }
+}
+
+/// `last_round_id` to start from after a restart: the stored round when it is
+/// at most `MAX_RESUME_ROUNDS` behind the board, else 0 (fresh start)
+pub fn resume_last_round(stored: Option<u64>, current_round: u64) -> u64 {
+    match stored {
+        Some(round) if round <= current_round && current_round - round <= MAX_RESUME_ROUNDS => round,
+        _ => 0,
+    }
+}
+
+/// The round that just completed, if the board moved past `last_round_id`
+/// A `last_round_id` of 0 means nothing has been seen yet, so there is nothing to complete
+pub fn completed_round(last_round_id: u64, current_round: u64) -> Option<u64> {
+    (last_round_id != 0 && current_round != last_round_id).then_some(last_round_id)
+}
+
+/// Read a state key written by `MemoryStateStore::with_snapshot_file`
+pub fn read_snapshot_key(path: &std::path::Path, key: &str) -> Result<Option<serde_json::Value>> {
+    let data = std::fs::read(path)
+        .map_err(|e| BotError::Other(format!("Failed to read state snapshot: {}", e)))?;
+    let state: HashMap<String, serde_json::Value> = serde_json::from_slice(&data)
+        .map_err(|e| BotError::Other(format!("Failed to parse state snapshot: {}", e)))?;
+    Ok(state.get(key).cloned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::SignalType;
+
+    #[tokio::test]
+    async fn test_coordinator_cycle_with_memory_store() {
+        let path = std::env::temp_dir().join(format!("clawdbot-state-{}.json", std::process::id()));
+        let store = StateBackend::Memory(MemoryStateStore::with_snapshot_file(&path));
+        assert!(!store.is_persistent());
+
+        // What one coordinator cycle publishes for the dashboard
+        store.set_state("monitor_status", serde_json::json!({
+            "round_id": 1234,
+            "deployed_squares": vec![1_000u64; 25],
+            "num_deployers": 3,
+        })).await.unwrap();
+        store.set_state("consensus_recommendation", serde_json::json!({
+            "squares": [4, 9, 17],
+            "confidence": 0.62,
+        })).await.unwrap();
+        store.send_signal(&Signal::round_started("coordinator", 1234)).await.unwrap();
+        publish_cycle_heartbeat(&store, "coordinator", 1234, true).await.unwrap();
+        assert!(!path.exists(), "snapshot is only written on flush");
+        store.flush().await.unwrap();
+
+        assert_eq!(store.get_state("current_round").await.unwrap(), Some(serde_json::json!(1234)));
+        let consensus = store.get_state("consensus_recommendation").await.unwrap().unwrap();
+        assert_eq!(consensus["squares"], serde_json::json!([4, 9, 17]));
+
+        let StateBackend::Memory(ref mem) = store else { unreachable!() };
+        let signal_types: Vec<SignalType> = mem.recent_signals().into_iter().map(|s| s.signal_type).collect();
+        assert_eq!(signal_types, vec![SignalType::RoundStarted, SignalType::Heartbeat]);
+
+        // The web layer reads the same status back from the snapshot file
+        let status = read_snapshot_key(&path, "monitor_status").unwrap().unwrap();
+        assert_eq!(status["round_id"], 1234);
+        assert_eq!(status["num_deployers"], 3);
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_duplicate_deploy_opportunities_collapsed() {
+        let mut budget = SignalBudget::new(2, 30);
+        let consensus = |reason: &str| Signal::deploy_opportunity("coordinator", vec![4, 9, 17], reason);
+
+        // Same squares every cycle of round 100: written once
+        budget.begin_cycle(100);
+        assert!(budget.allow(&consensus("Consensus (62% confidence)")));
+        assert!(!budget.allow(&consensus("Consensus (62% confidence)")));
+        budget.begin_cycle(100);
+        assert!(!budget.allow(&consensus("Consensus (64% confidence)")));
+
+        // New squares are news; so is the same pick in the next round
+        assert!(budget.allow(&Signal::deploy_opportunity("coordinator", vec![4, 9, 18], "shifted")));
+        budget.begin_cycle(101);
+        assert!(budget.allow(&consensus("Consensus (60% confidence)")));
+
+        // Per-cycle cap per type
+        assert!(budget.allow(&Signal::deploy_opportunity("coordinator", vec![1], "a")));
+        assert!(!budget.allow(&Signal::deploy_opportunity("coordinator", vec![2], "b")));
+        assert!(budget.allow(&Signal::round_started("coordinator", 101)));
+
+        // Heartbeats at most every 30s
+        assert!(budget.heartbeat_due(1_000));
+        assert!(!budget.heartbeat_due(1_020));
+        assert!(budget.heartbeat_due(1_030));
+    }
+
+    #[tokio::test]
+    async fn test_restored_last_round_processes_next_flip() {
+        // Before the restart: the coordinator last saw round 500
+        let store = MemoryStateStore::new();
+        publish_cycle_heartbeat(&store, "coordinator", 500, true).await.unwrap();
+
+        // A fresh start would swallow the first flip
+        assert_eq!(completed_round(0, 501), None);
+
+        // Restored, the flip to 501 completes round 500 as usual
+        let last_round_id = resume_last_round(load_last_round(&store).await, 501);
+        assert_eq!(last_round_id, 500);
+        assert_eq!(completed_round(last_round_id, 501), Some(500));
+
+        // Restarted within the same round: nothing to complete until it flips
+        assert_eq!(completed_round(resume_last_round(Some(501), 501), 501), None);
+        assert_eq!(completed_round(501, 502), Some(501));
+
+        // Too stale, or nothing stored: start fresh
+        assert_eq!(resume_last_round(Some(500), 500 + MAX_RESUME_ROUNDS + 1), 0);
+        assert_eq!(resume_last_round(None, 501), 0);
+        assert_eq!(load_last_round(&MemoryStateStore::new()).await, None);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::strategy::BettingStrategy;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -8,6 +9,7 @@ use std::collections::HashMap;
 #[derive(Debug, Clone)]
 pub struct RoundHistory {
     pub round_id: u64,
+    /// 0-24
     pub winning_square: u8,
     pub deployed: [u64; 25],
     pub total_pot: u64,
@@ -35,6 +37,7 @@ pub struct SquareStats {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StrategyRecommendation {
     pub strategy_name: String,
+    /// 0-24 from the individual strategies; the consensus returns 1-25
     pub squares: Vec<usize>,
     pub weights: Vec<f64>,         // How much to allocate to each square (0.0 - 1.0)
     pub confidence: f64,           // 0.0 - 1.0
@@ -42,12 +45,139 @@ pub struct StrategyRecommendation {
     pub reasoning: String,
 }
 
+/// One strategy's vote for a square in the consensus
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StrategyContribution {
+    pub strategy: String,
+    /// Square weight times the strategy's confidence - what it added to the square's score
+    pub weight: f64,
+}
+
+/// Which strategies voted for each square, strongest first (index 0 = square 1)
+/// These are the terms the consensus score sums, so they explain its picks
+pub fn square_contributions(recs: &[StrategyRecommendation]) -> Vec<Vec<StrategyContribution>> {
+    let mut contributions: Vec<Vec<StrategyContribution>> = vec![Vec::new(); 25];
+
+    for rec in recs {
+        // Strategy squares are 0-24
+        for (&idx, weight) in rec.squares.iter().zip(&rec.weights) {
+            if idx < 25 {
+                contributions[idx].push(StrategyContribution {
+                    strategy: rec.strategy_name.clone(),
+                    weight: weight * rec.confidence,
+                });
+            }
+        }
+    }
+
+    for square in &mut contributions {
+        square.sort_by(|a, b| b.weight.partial_cmp(&a.weight).unwrap_or(std::cmp::Ordering::Equal));
+    }
+    contributions
+}
+
+/// Cutoffs the edge-based strategies use before calling a square or area hot
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EdgeThresholds {
+    /// Edge Hunting: win rate above 4% a square needs (0.005 = 0.5%)
+    pub min_square_edge: f64,
+    /// Edge Hunting: rounds of history before a square's edge counts
+    pub min_square_rounds: u32,
+    /// Quadrant Analysis: win rate above expected corners or center need
+    pub min_quadrant_edge: f64,
+    /// Mean Reversion: wins below expected before a square counts as due
+    pub min_win_deficit: f64,
+}
+
+impl Default for EdgeThresholds {
+    fn default() -> Self {
+        Self {
+            min_square_edge: 0.005,
+            min_square_rounds: 50,
+            min_quadrant_edge: 0.02,
+            min_win_deficit: 2.0,
+        }
+    }
+}
+
+/// The last winner plus every square touching it (0-24), winner first
+/// Interior squares have 8 neighbors, edges 5, corners 3
+pub fn winner_neighborhood(last_winner: usize) -> Vec<usize> {
+    let mut squares = vec![last_winner];
+    squares.extend(BettingStrategy::get_adjacent_squares(last_winner));
+    squares
+}
+
+/// Board areas used by quadrant analysis
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Quadrant {
+    /// 0, 4, 20, 24
+    Corner,
+    /// Outer ring minus the corners
+    Edge,
+    /// Inner 3x3
+    Center,
+}
+
+impl Quadrant {
+    /// Quadrant of a 0-24 square
+    pub fn of(square: usize) -> Self {
+        let (row, col) = (square / 5, square % 5);
+        let row_edge = row == 0 || row == 4;
+        let col_edge = col == 0 || col == 4;
+        match (row_edge, col_edge) {
+            (true, true) => Quadrant::Corner,
+            (false, false) => Quadrant::Center,
+            _ => Quadrant::Edge,
+        }
+    }
+}
+
+/// Swap picks (0-24, best first) until they cover at least `min_quadrants` quadrants
+/// `ranked` is every square best first. Each swap drops the lowest-ranked pick from
+/// a quadrant that has picks to spare and adds the best unpicked square from a
+/// quadrant not yet covered. Returns the (dropped, added) pairs.
+pub fn spread_across_quadrants(picks: &mut [usize], ranked: &[usize], min_quadrants: usize) -> Vec<(usize, usize)> {
+    let target = min_quadrants.min(3).min(picks.len());
+    let mut swaps = Vec::new();
+
+    loop {
+        let covered: Vec<Quadrant> = picks.iter().map(|&s| Quadrant::of(s)).collect();
+        let distinct = [Quadrant::Corner, Quadrant::Edge, Quadrant::Center]
+            .iter()
+            .filter(|q| covered.contains(q))
+            .count();
+        if distinct >= target {
+            break;
+        }
+
+        let Some(&add) = ranked.iter().find(|&&s| s < 25 && !covered.contains(&Quadrant::of(s))) else {
+            break;
+        };
+        let Some(slot) = (0..picks.len())
+            .rev()
+            .find(|&i| covered.iter().filter(|&&q| q == covered[i]).count() > 1)
+        else {
+            break;
+        };
+
+        swaps.push((picks[slot], add));
+        picks[slot] = add;
+    }
+
+    swaps
+}
+
 /// Main strategy engine
 pub struct StrategyEngine {
     history: Vec<RoundHistory>,
     square_stats: [SquareStats; 25],
     whale_positions: HashMap<String, Vec<usize>>, // Whale address -> their favorite squares
     strategy_weights: HashMap<String, f64>,       // Learned strategy performance
+    min_quadrants: usize,                         // Consensus must span this many quadrants (0 = off)
+    edge_thresholds: EdgeThresholds,
+    neighbor_overlay_weight: f64,                 // Score added to the last winner's neighborhood (0 = off)
 }
 
 impl StrategyEngine {
@@ -57,9 +187,32 @@ impl StrategyEngine {
             square_stats: Default::default(),
             whale_positions: HashMap::new(),
             strategy_weights: HashMap::new(),
+            min_quadrants: 0,
+            edge_thresholds: EdgeThresholds::default(),
+            neighbor_overlay_weight: 0.0,
         }
     }
 
+    /// Always vote for the last winner and its neighbors, adding `weight`
+    /// to each of their consensus scores whatever the other strategies say (0 = off)
+    pub fn set_neighbor_overlay(&mut self, weight: f64) {
+        self.neighbor_overlay_weight = weight.max(0.0);
+    }
+
+    /// Tune how much edge the edge-based strategies need to act
+    pub fn set_edge_thresholds(&mut self, thresholds: EdgeThresholds) {
+        self.edge_thresholds = thresholds;
+    }
+
+    pub fn edge_thresholds(&self) -> &EdgeThresholds {
+        &self.edge_thresholds
+    }
+
+    /// Make consensus picks span at least `k` of corners/edges/center (0 = off)
+    pub fn set_min_quadrants(&mut self, k: usize) {
+        self.min_quadrants = k;
+    }
+
     /// Load persisted square stats from database
     pub fn load_square_stats_from_db(&mut self, stats: Vec<(i16, i32, i32, i64, f32, f32, i32, i64)>) {
         for (square_id, wins, rounds, deployed, win_rate, edge, streak, avg_comp) in stats {
@@ -91,17 +244,17 @@ impl StrategyEngine {
         }
     }
 
-    /// Load historical rounds from database
+    /// Load historical rounds from database (winning squares stored 1-25)
     pub fn load_rounds_from_db(&mut self, rounds: Vec<(i64, i16, Vec<i64>, i64, bool)>) {
         for (round_id, winning_square, deployed_vec, total, motherlode) in rounds {
-            if winning_square >= 0 && deployed_vec.len() == 25 {
+            if (1..=25).contains(&winning_square) && deployed_vec.len() == 25 {
                 let mut deployed = [0u64; 25];
                 for (i, &d) in deployed_vec.iter().enumerate() {
                     deployed[i] = d as u64;
                 }
                 self.history.push(RoundHistory {
                     round_id: round_id as u64,
-                    winning_square: winning_square as u8,
+                    winning_square: (winning_square - 1) as u8,
                     deployed,
                     total_pot: total as u64,
                     motherlode,
@@ -126,6 +279,16 @@ impl StrategyEngine {
         self.history.len()
     }
 
+    /// Loaded rounds, oldest first
+    pub fn history(&self) -> &[RoundHistory] {
+        &self.history
+    }
+
+    /// Per-square stats, indexed 0-24
+    pub fn square_stats(&self) -> &[SquareStats; 25] {
+        &self.square_stats
+    }
+
     /// Get loaded whale count
     pub fn whale_count(&self) -> usize {
         self.whale_positions.len()
@@ -232,6 +395,9 @@ impl StrategyEngine {
         recs.push(self.kelly_criterion_strategy(current_deployed));
         recs.push(self.quadrant_analysis_strategy());
         recs.push(self.mean_reversion_strategy());
+        if let Some(overlay) = self.neighbor_overlay() {
+            recs.push(overlay);
+        }
 
         // Sort by confidence
         recs.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap_or(std::cmp::Ordering::Equal));
@@ -320,10 +486,11 @@ impl StrategyEngine {
     /// 3. EDGE HUNTING STRATEGY
     /// Pure statistical edge - squares that win more than 4%
     fn edge_hunting_strategy(&self) -> StrategyRecommendation {
+        let t = &self.edge_thresholds;
         let mut with_edge: Vec<(usize, f64)> = self.square_stats
             .iter()
             .enumerate()
-            .filter(|(_, s)| s.edge > 0.005 && s.total_rounds > 50) // At least 0.5% edge with data
+            .filter(|(_, s)| s.edge > t.min_square_edge && s.total_rounds > t.min_square_rounds) // Enough edge with data
             .map(|(i, s)| (i, s.edge))
             .collect();
 
@@ -650,9 +817,10 @@ impl StrategyEngine {
         let corner_edge = corner_rate - expected_corner;
         let center_edge = center_rate - expected_center;
 
-        let (squares, reasoning) = if corner_edge > center_edge && corner_edge > 0.02 {
+        let min_edge = self.edge_thresholds.min_quadrant_edge;
+        let (squares, reasoning) = if corner_edge > center_edge && corner_edge > min_edge {
             (corners.to_vec(), format!("Corners overperforming by {:.1}%", corner_edge * 100.0))
-        } else if center_edge > 0.02 {
+        } else if center_edge > min_edge {
             (center.to_vec(), format!("Center overperforming by {:.1}%", center_edge * 100.0))
         } else {
             (vec![12], "No significant quadrant edge - defaulting to center".to_string())
@@ -670,6 +838,25 @@ impl StrategyEngine {
         }
     }
 
+    /// Momentum overlay: the last winner's neighborhood, for players who think wins cluster
+    /// Unlike Pattern Detection it includes the winner itself and is only on when configured
+    fn neighbor_overlay(&self) -> Option<StrategyRecommendation> {
+        if self.neighbor_overlay_weight <= 0.0 {
+            return None;
+        }
+        let last_winner = self.history.last()?.winning_square as usize;
+        let squares = winner_neighborhood(last_winner);
+
+        Some(StrategyRecommendation {
+            strategy_name: "Neighbor Overlay".to_string(),
+            weights: vec![1.0; squares.len()],
+            squares,
+            confidence: self.neighbor_overlay_weight,
+            expected_roi: 0.0,
+            reasoning: format!("Last winner (square {}) and its neighbors - wins cluster thesis", last_winner + 1),
+        })
+    }
+
     /// 10. MEAN REVERSION STRATEGY
     /// Bet on squares that are statistically due
     fn mean_reversion_strategy(&self) -> StrategyRecommendation {
@@ -694,7 +881,7 @@ impl StrategyEngine {
                 let deviation = expected - s.wins as f64;
                 (i, deviation)
             })
-            .filter(|(_, d)| *d > 2.0) // At least 2 wins below expected
+            .filter(|(_, d)| *d > self.edge_thresholds.min_win_deficit) // Enough wins below expected
             .collect();
 
         underperformers.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
@@ -736,29 +923,31 @@ impl StrategyEngine {
         let recs = self.get_recommendations(current_deployed);
         
         // Weight squares by appearing in multiple strategies
-        let mut square_scores: [f64; 25] = [0.0; 25];
-        
-        for rec in &recs {
-            for (sq, weight) in rec.squares.iter().zip(&rec.weights) {
-                // rec.squares may be 0-24 or 1-25 depending on source, normalize to 0-24 for indexing
-                let idx = if *sq > 0 && *sq <= 25 { sq - 1 } else { *sq };
-                if idx < 25 {
-                    square_scores[idx] += weight * rec.confidence;
-                }
-            }
-        }
-
-        let mut scored: Vec<(usize, f64)> = square_scores
+        let mut scored: Vec<(usize, f64)> = square_contributions(&recs)
             .iter()
+            .map(|votes| votes.iter().map(|v| v.weight).sum::<f64>())
             .enumerate()
-            .filter(|(_, &s)| s > 0.0)
-            .map(|(i, &s)| (i, s))
+            .filter(|(_, s)| *s > 0.0)
             .collect();
 
         scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
 
+        let mut picks: Vec<usize> = scored.iter().take(num_squares).map(|(i, _)| *i).collect();
+        let mut spread_note = String::new();
+        if self.min_quadrants > 0 {
+            // Unscored squares rank last, so a quadrant nobody voted for can still be filled
+            let mut ranked: Vec<usize> = scored.iter().map(|(i, _)| *i).collect();
+            ranked.extend((0..25).filter(|i| !ranked.contains(i)));
+            let swaps = spread_across_quadrants(&mut picks, &ranked, self.min_quadrants);
+            if !swaps.is_empty() {
+                let swapped: Vec<String> = swaps.iter().map(|(out, add)| format!("{}→{}", out + 1, add + 1)).collect();
+                spread_note = format!(" | spread across {} quadrants (swapped {})", self.min_quadrants.min(3), swapped.join(", "));
+            }
+        }
+
         // Convert 0-24 indices to 1-25 for output
-        let top_squares: Vec<usize> = scored.iter().take(num_squares).map(|(i, _)| i + 1).collect();
+        // A swapped-in square takes the allocation of the pick it replaced
+        let top_squares: Vec<usize> = picks.iter().map(|i| i + 1).collect();
         let total_score: f64 = scored.iter().take(num_squares).map(|(_, s)| s).sum();
         let weights: Vec<f64> = if total_score > 0.0 {
             scored.iter().take(num_squares).map(|(_, s)| s / total_score).collect()
@@ -774,7 +963,7 @@ impl StrategyEngine {
             weights,
             confidence,
             expected_roi: 0.2,
-            reasoning: format!("Weighted consensus across all strategies ({} squares, 1-25 range)", num_squares),
+            reasoning: format!("Weighted consensus across all strategies ({} squares, 1-25 range){}", num_squares, spread_note),
         }
     }
 }
@@ -813,4 +1002,131 @@ mod tests {
             println!("{}: {:?} (conf: {:.2})", rec.strategy_name, rec.squares, rec.confidence);
         }
     }
+
+    #[test]
+    fn test_quadrant_spread_diversifies_single_quadrant_picks() {
+        // Three center picks; best unpicked corner is 24, best edge is 2
+        let ranked = [12, 7, 17, 6, 2, 24, 0, 1];
+        let mut picks = vec![12, 7, 17];
+        assert!(picks.iter().all(|&s| Quadrant::of(s) == Quadrant::Center));
+
+        let swaps = spread_across_quadrants(&mut picks, &ranked, 3);
+        assert_eq!(swaps, vec![(17, 2), (7, 24)]);
+        assert_eq!(picks, vec![12, 24, 2]);
+
+        // Already spread, or the constraint off: nothing changes
+        assert!(spread_across_quadrants(&mut picks, &ranked, 3).is_empty());
+        let mut center = vec![12, 7, 17];
+        assert!(spread_across_quadrants(&mut center, &ranked, 0).is_empty());
+
+        // Center-heavy history: the consensus covers every quadrant once constrained
+        let mut engine = StrategyEngine::new();
+        let center_squares = [6u8, 7, 8, 11, 12, 13, 16, 17, 18];
+        for i in 0..200u64 {
+            engine.add_round(RoundHistory {
+                round_id: i,
+                winning_square: center_squares[i as usize % center_squares.len()],
+                deployed: [1_000_000_000; 25],
+                total_pot: 25_000_000_000,
+                motherlode: false,
+                timestamp: Some(i as i64),
+            });
+        }
+        engine.set_min_quadrants(3);
+        let consensus = engine.get_consensus_recommendation_n(&[1_000_000_000u64; 25], 3);
+        let quadrants: std::collections::HashSet<Quadrant> =
+            consensus.squares.iter().map(|&s| Quadrant::of(s - 1)).collect();
+        assert_eq!(quadrants.len(), 3, "{:?}", consensus.squares);
+        assert_eq!(consensus.squares.len(), 3);
+    }
+
+    #[test]
+    fn test_edge_threshold_controls_surfaced_squares() {
+        // Square 0 wins 4x as often as the tail, square 1 3x, square 2 2x
+        let mut cycle: Vec<u8> = vec![0, 0, 0, 0, 1, 1, 1, 2, 2];
+        cycle.extend(3..25);
+        let mut engine = StrategyEngine::new();
+        engine.load_history(
+            (0..cycle.len() as u64 * 10)
+                .map(|i| RoundHistory {
+                    round_id: i,
+                    winning_square: cycle[i as usize % cycle.len()],
+                    deployed: [1_000_000_000; 25],
+                    total_pot: 25_000_000_000,
+                    motherlode: false,
+                    timestamp: Some(i as i64),
+                })
+                .collect(),
+        );
+
+        let surfaced = |engine: &mut StrategyEngine, min_square_edge: f64| {
+            engine.set_edge_thresholds(EdgeThresholds { min_square_edge, ..Default::default() });
+            engine.edge_hunting_strategy().squares.len()
+        };
+
+        // Default 0.5% keeps all three overweight squares (edges ~8.9%, ~5.7%, ~2.5%)
+        let default = surfaced(&mut engine, EdgeThresholds::default().min_square_edge);
+        assert_eq!(default, 3);
+        assert!(surfaced(&mut engine, 0.03) < default);
+        assert_eq!(surfaced(&mut engine, 0.06), 1);
+        assert_eq!(surfaced(&mut engine, 0.2), 0);
+
+        // Dropping the cutoff below every tail square's (negative) edge surfaces the whole board
+        assert!(surfaced(&mut engine, -0.02) > default);
+    }
+
+    #[test]
+    fn test_neighbor_overlay_covers_winner_and_neighbors() {
+        let sorted = |sq: usize| {
+            let mut n = winner_neighborhood(sq);
+            assert_eq!(n[0], sq, "winner comes first");
+            n.sort();
+            n
+        };
+        assert_eq!(sorted(12), vec![6, 7, 8, 11, 12, 13, 16, 17, 18]);
+        assert_eq!(sorted(0), vec![0, 1, 5, 6]);
+        assert_eq!(sorted(24), vec![18, 19, 23, 24]);
+        assert_eq!(sorted(2), vec![1, 2, 3, 6, 7, 8]);
+        assert_eq!(sorted(14), vec![8, 9, 13, 14, 18, 19]);
+
+        let mut engine = StrategyEngine::new();
+        engine.add_round(RoundHistory {
+            round_id: 1,
+            winning_square: 20,
+            deployed: [1_000_000_000; 25],
+            total_pot: 25_000_000_000,
+            motherlode: false,
+            timestamp: None,
+        });
+        let current = [1_000_000_000u64; 25];
+        let overlay = |engine: &StrategyEngine| {
+            engine.get_recommendations(&current).into_iter().find(|r| r.strategy_name == "Neighbor Overlay")
+        };
+        assert!(overlay(&engine).is_none(), "off by default");
+
+        engine.set_neighbor_overlay(0.3);
+        let rec = overlay(&engine).unwrap();
+        assert_eq!(rec.squares, winner_neighborhood(20));
+        assert_eq!(rec.confidence, 0.3);
+        let votes: Vec<f64> = square_contributions(&[rec]).into_iter().flatten().map(|c| c.weight).collect();
+        assert_eq!(votes, vec![0.3; 4]);
+    }
+
+    #[test]
+    fn test_neighbor_overlay_consensus_from_db_history() {
+        // Winners as stored in the rounds table (1-25), overlay strong enough to decide the picks
+        let consensus = |winner: i16, n: usize| {
+            let mut engine = StrategyEngine::new();
+            engine.load_rounds_from_db(vec![(1, winner, vec![1_000_000_000; 25], 25_000_000_000, false)]);
+            engine.set_neighbor_overlay(10.0);
+            let mut squares = engine.get_consensus_recommendation_n(&[1_000_000_000; 25], n).squares;
+            squares.sort();
+            squares
+        };
+
+        // Corner: square 25
+        assert_eq!(consensus(25, 4), vec![19, 20, 24, 25]);
+        // Top edge: square 3
+        assert_eq!(consensus(3, 6), vec![2, 3, 4, 7, 8, 9]);
+    }
 }
@@ -0,0 +1,150 @@
+//! Treasury health alerts
+//!
+//! The coordinator reads the treasury every cycle. `TreasuryAlerts` compares
+//! the motherlode pool and the treasury SOL balance against configured
+//! thresholds and reports edge crossings only, so a pool sitting above its
+//! threshold for fifty cycles raises one alert, not fifty. A watch re-arms
+//! once the value falls back below its threshold.
+
+use crate::db::{Signal, SignalType};
+use crate::units::{lamports_to_sol, raw_ore_to_ore};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Crossing {
+    /// Value rose to or above the threshold
+    Up,
+    /// Value fell back below the threshold
+    Down,
+}
+
+impl Crossing {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Crossing::Up => "up",
+            Crossing::Down => "down",
+        }
+    }
+}
+
+/// Edge detector for one value against one threshold
+#[derive(Debug, Clone)]
+pub struct ThresholdWatch {
+    threshold: f64,
+    above: bool,
+}
+
+impl ThresholdWatch {
+    /// Starts below the threshold, so a value already above it on the first
+    /// reading counts as a crossing
+    pub fn new(threshold: f64) -> Self {
+        Self { threshold, above: false }
+    }
+
+    pub fn threshold(&self) -> f64 {
+        self.threshold
+    }
+
+    /// Record a reading; returns the crossing if the side changed
+    pub fn observe(&mut self, value: f64) -> Option<Crossing> {
+        let above = value >= self.threshold;
+        if above == self.above {
+            return None;
+        }
+        self.above = above;
+        Some(if above { Crossing::Up } else { Crossing::Down })
+    }
+}
+
+/// Motherlode (ORE) and treasury balance (SOL) watches; `None` disables one
+#[derive(Debug, Clone, Default)]
+pub struct TreasuryAlerts {
+    motherlode: Option<ThresholdWatch>,
+    balance: Option<ThresholdWatch>,
+}
+
+impl TreasuryAlerts {
+    /// Thresholds of zero or less are treated as off
+    pub fn new(motherlode_ore: f64, treasury_sol: f64) -> Self {
+        let watch = |t: f64| (t > 0.0).then(|| ThresholdWatch::new(t));
+        Self {
+            motherlode: watch(motherlode_ore),
+            balance: watch(treasury_sol),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.motherlode.is_some() || self.balance.is_some()
+    }
+
+    /// Signals to send for this treasury reading
+    /// The motherlode alert fires when the pool grows past its threshold; the
+    /// balance alert fires on both crossings so a draining treasury is seen too
+    pub fn check(&mut self, source: &str, motherlode_raw: u64, balance_lamports: u64) -> Vec<Signal> {
+        let mut signals = Vec::new();
+
+        if let Some(ref mut watch) = self.motherlode {
+            let ore = raw_ore_to_ore(motherlode_raw);
+            if watch.observe(ore) == Some(Crossing::Up) {
+                signals.push(Signal::new(
+                    SignalType::MotherlodeAlert,
+                    source,
+                    serde_json::json!({
+                        "motherlode_ore": ore,
+                        "threshold_ore": watch.threshold(),
+                    }),
+                ));
+            }
+        }
+
+        if let Some(ref mut watch) = self.balance {
+            let sol = lamports_to_sol(balance_lamports);
+            if let Some(crossing) = watch.observe(sol) {
+                signals.push(Signal::new(
+                    SignalType::PriceAlert,
+                    source,
+                    serde_json::json!({
+                        "treasury_sol": sol,
+                        "threshold_sol": watch.threshold(),
+                        "direction": crossing.as_str(),
+                    }),
+                ));
+            }
+        }
+
+        signals
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::units::ore_to_raw;
+
+    #[test]
+    fn test_motherlode_alert_once_per_crossing() {
+        let mut alerts = TreasuryAlerts::new(100.0, 0.0);
+        assert!(alerts.is_enabled());
+
+        // Rises past 100, sits there, dips, then crosses again
+        let readings = [20.0, 80.0, 120.0, 150.0, 130.0, 90.0, 95.0, 101.0, 200.0];
+        let fired: Vec<usize> = readings
+            .iter()
+            .enumerate()
+            .filter(|(_, &ore)| !alerts.check("coordinator", ore_to_raw(ore), 0).is_empty())
+            .map(|(i, _)| i)
+            .collect();
+        assert_eq!(fired, vec![2, 7]);
+
+        let signals = TreasuryAlerts::new(100.0, 0.0).check("coordinator", ore_to_raw(250.0), 0);
+        assert_eq!(signals.len(), 1);
+        assert_eq!(signals[0].signal_type, SignalType::MotherlodeAlert);
+        assert_eq!(signals[0].payload["threshold_ore"], 100.0);
+
+        // Disabled thresholds never alert
+        let mut off = TreasuryAlerts::new(0.0, 0.0);
+        assert!(!off.is_enabled());
+        assert!(off.check("coordinator", ore_to_raw(1_000.0), u64::MAX).is_empty());
+    }
+}
@@ -0,0 +1,52 @@
+//! SOL and ORE unit conversions
+//!
+//! SOL has 9 decimals (lamports), ORE has 11. Like `progress.rs`, this file
+//! has no dependencies so the dashboard can include it with `#[path]`.
+
+pub const SOL_DECIMALS: u32 = 9;
+pub const LAMPORTS_PER_SOL: u64 = 10u64.pow(SOL_DECIMALS);
+
+pub const ORE_DECIMALS: u32 = 11;
+/// Smallest ORE unit per whole ORE
+pub const RAW_PER_ORE: u64 = 10u64.pow(ORE_DECIMALS);
+
+pub fn lamports_to_sol(lamports: u64) -> f64 {
+    lamports as f64 / LAMPORTS_PER_SOL as f64
+}
+
+/// Rounded to the nearest lamport; negative amounts become 0
+pub fn sol_to_lamports(sol: f64) -> u64 {
+    (sol * LAMPORTS_PER_SOL as f64).round() as u64
+}
+
+pub fn raw_ore_to_ore(raw: u64) -> f64 {
+    raw as f64 / RAW_PER_ORE as f64
+}
+
+/// Rounded to the nearest raw unit; negative amounts become 0
+pub fn ore_to_raw(ore: f64) -> u64 {
+    (ore * RAW_PER_ORE as f64).round() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unit_conversions() {
+        assert_eq!(LAMPORTS_PER_SOL, 1_000_000_000);
+        assert_eq!(RAW_PER_ORE, 100_000_000_000);
+
+        assert_eq!(lamports_to_sol(1_500_000_000), 1.5);
+        assert_eq!(lamports_to_sol(40_000_000), 0.04);
+        assert_eq!(sol_to_lamports(0.04), 40_000_000);
+        assert_eq!(sol_to_lamports(0.07), 70_000_000);
+        assert_eq!(sol_to_lamports(-1.0), 0);
+
+        // ORE is 11 decimals, not 9
+        assert_eq!(raw_ore_to_ore(100_000_000_000), 1.0);
+        assert_eq!(raw_ore_to_ore(1_000_000_000), 0.01);
+        assert_eq!(ore_to_raw(2.5), 250_000_000_000);
+        assert_eq!(ore_to_raw(raw_ore_to_ore(123_456_789_012)), 123_456_789_012);
+    }
+}
@@ -0,0 +1,265 @@
+//! Deploy/win notifications for external alerting (Discord or any JSON endpoint)
+//!
+//! Posts a small JSON payload to `webhook_url`, or a Discord message when the
+//! URL is a Discord webhook. Sends run on a spawned task with a short timeout
+//! so a slow endpoint never holds up the mining loop.
+
+use crate::units::lamports_to_sol;
+use log::{debug, warn};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+const WEBHOOK_TIMEOUT_SECS: u64 = 5;
+
+/// Payload POSTed to the webhook
+/// Squares are 1-25 as shown in the game UI
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WebhookPayload {
+    /// "deploy", "win" or "balance_drop"
+    pub event: String,
+    pub bot: String,
+    pub round_id: u64,
+    pub squares: Vec<u8>,
+    pub amount_lamports: u64,
+    pub amount_sol: f64,
+    pub signature: String,
+    pub winning_square: Option<u8>,
+    pub timestamp: String,
+}
+
+impl WebhookPayload {
+    /// Deploy sent; `squares` are 0-24 board indexes
+    pub fn deploy(bot: &str, round_id: u64, squares: &[usize], amount_lamports: u64, signature: &str) -> Self {
+        Self {
+            event: "deploy".to_string(),
+            bot: bot.to_string(),
+            round_id,
+            squares: squares.iter().map(|&s| (s + 1) as u8).collect(),
+            amount_lamports,
+            amount_sol: lamports_to_sol(amount_lamports),
+            signature: signature.to_string(),
+            winning_square: None,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    /// Our deploy covered the winning square; `winning_square` is 0-24 like the chain
+    pub fn win(
+        bot: &str,
+        round_id: u64,
+        squares: &[usize],
+        amount_lamports: u64,
+        signature: &str,
+        winning_square: u8,
+    ) -> Self {
+        Self {
+            event: "win".to_string(),
+            winning_square: Some(winning_square + 1),
+            ..Self::deploy(bot, round_id, squares, amount_lamports, signature)
+        }
+    }
+
+    /// Wallet lost `shortfall_lamports` more than our deploys explain; the bot paused
+    pub fn balance_drop(bot: &str, round_id: u64, shortfall_lamports: u64) -> Self {
+        Self {
+            event: "balance_drop".to_string(),
+            ..Self::deploy(bot, round_id, &[], shortfall_lamports, "")
+        }
+    }
+
+    /// One-line human readable message
+    pub fn summary(&self) -> String {
+        match self.event.as_str() {
+            "win" => format!(
+                "🏆 {} won round {} on square {} ({:.4} SOL on {:?}) {}",
+                self.bot,
+                self.round_id,
+                self.winning_square.unwrap_or(0),
+                self.amount_sol,
+                self.squares,
+                self.signature
+            ),
+            "balance_drop" => format!(
+                "⚠️ {} paused in round {}: wallet dropped {:.4} SOL more than deploys explain",
+                self.bot, self.round_id, self.amount_sol
+            ),
+            _ => format!(
+                "🚀 {} deployed {:.4} SOL on {:?} in round {} {}",
+                self.bot, self.amount_sol, self.squares, self.round_id, self.signature
+            ),
+        }
+    }
+}
+
+/// Body shape the webhook endpoint accepts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookFormat {
+    /// `WebhookPayload` as-is
+    Json,
+    /// `{"content": ...}`; Discord rejects anything else with 400
+    Discord,
+    /// `{"text": ...}` for Slack incoming webhooks
+    Slack,
+}
+
+impl WebhookFormat {
+    pub fn for_url(url: &str) -> Self {
+        let host = reqwest::Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_ascii_lowercase))
+            .unwrap_or_default();
+        let on = |domains: &[&str]| domains.iter().any(|d| host == *d || host.ends_with(&format!(".{}", d)));
+        if on(&["discord.com", "discordapp.com"]) {
+            WebhookFormat::Discord
+        } else if host == "hooks.slack.com" {
+            WebhookFormat::Slack
+        } else {
+            WebhookFormat::Json
+        }
+    }
+
+    pub fn body(&self, payload: &WebhookPayload) -> serde_json::Value {
+        match self {
+            WebhookFormat::Json => serde_json::to_value(payload).unwrap_or_default(),
+            WebhookFormat::Discord => serde_json::json!({ "content": payload.summary() }),
+            WebhookFormat::Slack => serde_json::json!({ "text": payload.summary() }),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WebhookNotifier {
+    client: Client,
+    url: String,
+    format: WebhookFormat,
+}
+
+impl WebhookNotifier {
+    pub fn new(url: &str) -> Self {
+        Self {
+            client: Client::builder()
+                .timeout(Duration::from_secs(WEBHOOK_TIMEOUT_SECS))
+                .build()
+                .unwrap_or_default(),
+            url: url.to_string(),
+            format: WebhookFormat::for_url(url),
+        }
+    }
+
+    /// Build a notifier only if a URL is configured
+    pub fn from_url(url: Option<&str>) -> Option<Self> {
+        url.filter(|u| !u.is_empty()).map(Self::new)
+    }
+
+    /// POST the payload and wait for the response
+    pub async fn send(&self, payload: &WebhookPayload) -> Result<(), reqwest::Error> {
+        self.client
+            .post(&self.url)
+            .json(&self.format.body(payload))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    /// Fire-and-forget send; failures are only logged
+    pub fn notify(&self, payload: WebhookPayload) {
+        let notifier = self.clone();
+        tokio::spawn(async move {
+            match notifier.send(&payload).await {
+                Ok(()) => debug!("Webhook sent ({} round {})", payload.event, payload.round_id),
+                Err(e) => warn!("Webhook failed ({} round {}): {}", payload.event, payload.round_id, e),
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Accept one HTTP request and return its body
+    async fn capture_one_request(listener: TcpListener) -> String {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut buf = Vec::new();
+        let mut chunk = [0u8; 1024];
+
+        loop {
+            let n = socket.read(&mut chunk).await.unwrap();
+            buf.extend_from_slice(&chunk[..n]);
+            let text = String::from_utf8_lossy(&buf).to_string();
+            if let Some(header_end) = text.find("\r\n\r\n") {
+                let content_length = text[..header_end]
+                    .lines()
+                    .find_map(|l| l.to_ascii_lowercase().strip_prefix("content-length:").map(|v| v.trim().to_string()))
+                    .and_then(|v| v.parse::<usize>().ok())
+                    .unwrap_or(0);
+                if buf.len() >= header_end + 4 + content_length {
+                    socket.write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n").await.unwrap();
+                    return text[header_end + 4..].to_string();
+                }
+            }
+            if n == 0 {
+                panic!("connection closed before full request");
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_deploy_webhook_payload() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let server = tokio::spawn(capture_one_request(listener));
+
+        let notifier = WebhookNotifier::from_url(Some(&url)).unwrap();
+        notifier.notify(WebhookPayload::deploy("miner-bot", 1234, &[0, 4, 24], 40_000_000, "5igSig"));
+
+        let body = tokio::time::timeout(Duration::from_secs(5), server)
+            .await
+            .expect("webhook was not called")
+            .unwrap();
+        let payload: WebhookPayload = serde_json::from_str(&body).unwrap();
+
+        assert_eq!(payload.event, "deploy");
+        assert_eq!(payload.round_id, 1234);
+        assert_eq!(payload.squares, vec![1, 5, 25]);
+        assert_eq!(payload.amount_lamports, 40_000_000);
+        assert_eq!(payload.amount_sol, 0.04);
+        assert_eq!(payload.signature, "5igSig");
+        assert_eq!(payload.winning_square, None);
+    }
+
+    #[test]
+    fn test_discord_webhook_gets_content_message() {
+        for url in ["https://discord.com/api/webhooks/1/token", "https://canary.discordapp.com/api/webhooks/1/token"] {
+            assert_eq!(WebhookFormat::for_url(url), WebhookFormat::Discord, "{}", url);
+        }
+        assert_eq!(WebhookFormat::for_url("https://example.com/discord.com/hook"), WebhookFormat::Json);
+        assert_eq!(WebhookFormat::for_url("http://127.0.0.1:8080/hook"), WebhookFormat::Json);
+        assert_eq!(WebhookFormat::for_url("https://hooks.slack.com/services/T0/B0/token"), WebhookFormat::Slack);
+        assert_eq!(WebhookFormat::for_url("https://slack.com/hooks"), WebhookFormat::Json);
+
+        let payload = WebhookPayload::win("miner-bot", 1234, &[0, 4], 40_000_000, "5igSig", 4);
+        let body = WebhookFormat::Discord.body(&payload);
+        let obj = body.as_object().unwrap();
+        assert_eq!(obj.len(), 1);
+        let content = obj["content"].as_str().unwrap();
+        assert!(content.contains("round 1234") && content.contains("square 5"), "{}", content);
+
+        let body = WebhookFormat::Slack.body(&payload);
+        let obj = body.as_object().unwrap();
+        assert_eq!(obj.len(), 1);
+        assert_eq!(obj["text"].as_str().unwrap(), content);
+
+        assert_eq!(WebhookFormat::Json.body(&payload), serde_json::to_value(&payload).unwrap());
+    }
+
+    #[test]
+    fn test_no_notifier_without_url() {
+        assert!(WebhookNotifier::from_url(None).is_none());
+        assert!(WebhookNotifier::from_url(Some("")).is_none());
+    }
+}
@@ -0,0 +1,154 @@
+//! Bounded background writer
+//!
+//! Moves slow persistence (DB inserts) off the hot path. Producers push items
+//! into a bounded channel that a dedicated task drains. When the consumer falls
+//! behind, producers wait briefly for space (back-pressure) and then drop the
+//! item with a warning rather than stall parsing and win detection.
+
+use log::warn;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc::{self, error::SendTimeoutError};
+use tokio::task::JoinHandle;
+
+pub struct BackgroundWriter<T> {
+    name: String,
+    sender: mpsc::Sender<T>,
+    max_wait: Duration,
+    dropped: Arc<AtomicU64>,
+}
+
+impl<T: Send + 'static> BackgroundWriter<T> {
+    /// Start the writer task. `write` is called once per item, in order.
+    /// The returned handle resolves to the number of items written once every
+    /// writer clone has been dropped and the queue has drained.
+    pub fn spawn<F, Fut>(name: &str, capacity: usize, max_wait: Duration, mut write: F) -> (Self, JoinHandle<u64>)
+    where
+        F: FnMut(T) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send,
+    {
+        let (sender, mut receiver) = mpsc::channel::<T>(capacity.max(1));
+
+        let handle = tokio::spawn(async move {
+            let mut written = 0u64;
+            while let Some(item) = receiver.recv().await {
+                write(item).await;
+                written += 1;
+            }
+            written
+        });
+
+        let writer = Self {
+            name: name.to_string(),
+            sender,
+            max_wait,
+            dropped: Arc::new(AtomicU64::new(0)),
+        };
+
+        (writer, handle)
+    }
+
+    /// Queue an item, waiting up to `max_wait` for space
+    /// Returns false if the item was dropped
+    pub async fn submit(&self, item: T) -> bool {
+        match self.sender.send_timeout(item, self.max_wait).await {
+            Ok(()) => true,
+            Err(SendTimeoutError::Timeout(_)) => {
+                let dropped = self.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+                warn!("⚠️ {} queue full, dropped item ({} dropped so far)", self.name, dropped);
+                false
+            }
+            Err(SendTimeoutError::Closed(_)) => {
+                warn!("⚠️ {} has stopped, dropped item", self.name);
+                false
+            }
+        }
+    }
+
+    /// Items dropped because the queue stayed full
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Items waiting to be written
+    pub fn pending(&self) -> usize {
+        self.sender.max_capacity() - self.sender.capacity()
+    }
+}
+
+impl<T> Clone for BackgroundWriter<T> {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name.clone(),
+            sender: self.sender.clone(),
+            max_wait: self.max_wait,
+            dropped: self.dropped.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[tokio::test]
+    async fn test_writes_complete_when_producer_is_faster() {
+        let stored = Arc::new(Mutex::new(Vec::new()));
+        let sink = stored.clone();
+
+        // Slow consumer, small queue: the producer has to wait for space
+        let (writer, handle) = BackgroundWriter::spawn("test-writer", 4, Duration::from_secs(5), move |item: u32| {
+            let sink = sink.clone();
+            async move {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+                sink.lock().unwrap().push(item);
+            }
+        });
+
+        for i in 0..20 {
+            assert!(writer.submit(i).await);
+        }
+        assert_eq!(writer.dropped(), 0);
+
+        drop(writer);
+        let written = handle.await.unwrap();
+
+        assert_eq!(written, 20);
+        assert_eq!(*stored.lock().unwrap(), (0..20).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn test_overflow_drops_instead_of_blocking() {
+        let (release_tx, release_rx) = tokio::sync::watch::channel(false);
+
+        // Consumer is stuck until released
+        let (writer, handle) = BackgroundWriter::spawn("stuck-writer", 1, Duration::from_millis(10), move |_item: u32| {
+            let mut release = release_rx.clone();
+            async move {
+                while !*release.borrow() {
+                    if release.changed().await.is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+
+        let mut accepted = 0;
+        for i in 0..5 {
+            if writer.submit(i).await {
+                accepted += 1;
+            }
+        }
+
+        // One item is being written, one sits in the queue, the rest are dropped
+        assert_eq!(accepted, 2);
+        assert_eq!(writer.dropped(), 3);
+
+        release_tx.send(true).unwrap();
+        drop(writer);
+        assert_eq!(handle.await.unwrap(), 2);
+    }
+}
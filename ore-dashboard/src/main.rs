@@ -7,11 +7,19 @@ use gloo_timers::future::TimeoutFuture;
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 
+// Shared with the bots so progress is computed identically everywhere
+#[path = "../../clawdbot/src/progress.rs"]
+mod progress;
+use progress::round_progress_pct;
+#[path = "../../clawdbot/src/units.rs"]
+#[allow(dead_code)]
+mod units;
+use units::lamports_to_sol;
+
 // Asset for the stylesheet
 static MAIN_CSS: Asset = asset!("/assets/main.css");
 
 // Constants
-const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
 const API_BASE_URL: &str = "";  // Same origin
 const POLL_INTERVAL_MS: u32 = 2000;
 
@@ -33,6 +41,8 @@ pub struct BoardState {
     pub time_remaining_secs: u64,
     pub round_duration_secs: u64,
     pub slots_remaining: u64,
+    #[serde(default)]
+    pub num_deployers: u64,
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
@@ -58,6 +68,24 @@ pub struct ApiResponse {
     pub last_winner: Option<WinnerInfo>,
     pub stats: Option<DashboardStats>,
     pub recent_rounds: Option<Vec<RecentRound>>,
+    #[serde(default)]
+    pub recommendation: Option<Recommendation>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct StrategyContribution {
+    pub strategy: String,
+    pub weight: f64,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct Recommendation {
+    /// 1-25
+    pub squares: Vec<usize>,
+    pub confidence: f64,
+    /// Per square, index 0 = square 1
+    #[serde(default)]
+    pub contributions: Vec<Vec<StrategyContribution>>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
@@ -81,6 +109,8 @@ fn app() -> Element {
     let mut is_connected = use_signal(|| false);
     let mut show_winner_reveal = use_signal(|| false);
     let mut local_time_remaining = use_signal(|| 0u64);
+    let mut recommendation = use_signal(Recommendation::default);
+    let mut selected_square = use_signal(|| None::<usize>);
 
     // Fetch data from API
     let fetch_data = move || async move {
@@ -112,6 +142,7 @@ fn app() -> Element {
                         }
                         recent_rounds.set(deque);
                     }
+                    recommendation.set(data.recommendation.unwrap_or_default());
                     is_connected.set(true);
                 }
             }
@@ -154,11 +185,7 @@ fn app() -> Element {
     let total_deployed: u64 = board_data.deployed.iter().sum();
     let active_squares = board_data.deployed.iter().filter(|&&d| d > 0).count();
     let time_remaining = *local_time_remaining.read();
-    let progress = if board_data.round_duration_secs > 0 {
-        ((board_data.round_duration_secs - time_remaining) as f64 / board_data.round_duration_secs as f64 * 100.0) as u32
-    } else {
-        0
-    };
+    let progress = round_progress_pct(board_data.round_duration_secs, time_remaining);
 
     rsx! {
         Stylesheet { href: MAIN_CSS }
@@ -202,6 +229,12 @@ fn app() -> Element {
                             winning_square: last_winner.read().as_ref().map(|w| w.winning_square),
                             current_round: board_data.round_id,
                             winner_round: last_winner.read().as_ref().map(|w| w.round_id),
+                            recommended: recommendation.read().squares.clone(),
+                            selected: *selected_square.read(),
+                            on_select: move |square: usize| {
+                                let current = *selected_square.read();
+                                selected_square.set(if current == Some(square) { None } else { Some(square) });
+                            },
                         }
                         
                         // Timer
@@ -222,13 +255,17 @@ fn app() -> Element {
                         children: rsx! {
                             div { class: "stats-grid",
                                 StatItem {
-                                    value: format!("{:.4}", total_deployed as f64 / LAMPORTS_PER_SOL),
+                                    value: format!("{:.4}", lamports_to_sol(total_deployed)),
                                     label: "Total SOL",
                                 }
                                 StatItem {
                                     value: format!("{}", active_squares),
                                     label: "Active Squares",
                                 }
+                                StatItem {
+                                    value: format!("{}", board_data.num_deployers),
+                                    label: "Deployers",
+                                }
                                 StatItem {
                                     value: format!("{}", board_data.slots_remaining),
                                     label: "Slots Left",
@@ -241,6 +278,17 @@ fn app() -> Element {
                         }
                     }
                     
+                    // Why the bot recommends the selected square
+                    StatsCard {
+                        title: "🧠 Strategy Contributions",
+                        children: rsx! {
+                            StrategyContributions {
+                                recommendation: recommendation.read().clone(),
+                                square: *selected_square.read(),
+                            }
+                        }
+                    }
+                    
                     // Session stats
                     StatsCard {
                         title: "🏆 Session Stats",
@@ -331,6 +379,9 @@ fn RegolithGrid(
     winning_square: Option<u8>,
     current_round: u64,
     winner_round: Option<u64>,
+    recommended: Vec<usize>,
+    selected: Option<usize>,
+    on_select: EventHandler<usize>,
 ) -> Element {
     let max_deploy = deployed.iter().max().copied().unwrap_or(1);
     
@@ -353,21 +404,28 @@ fn RegolithGrid(
                         0.0
                     };
                     
+                    let is_recommended = recommended.contains(&square_num);
                     let cell_class = format!(
-                        "grid-cell {} {} {}",
+                        "grid-cell {} {} {} {}",
                         if is_winner { "winner" } else { "" },
                         if has_deploys { format!("has-deploys heat-{}", heat_level) } else { "".to_string() },
-                        ""
+                        if is_recommended { "recommended" } else { "" },
+                        if selected == Some(square_num) { "selected" } else { "" }
                     );
                     
                     rsx! {
                         div {
                             key: "{idx}",
                             class: "{cell_class}",
+                            onclick: move |_| {
+                                if is_recommended {
+                                    on_select.call(square_num);
+                                }
+                            },
                             span { class: "cell-number", "#{square_num}" }
                             if amount > 0 {
                                 span { class: "cell-amount", 
-                                    "{format!(\"{:.3}\", amount as f64 / LAMPORTS_PER_SOL)}"
+                                    "{format!(\"{:.3}\", lamports_to_sol(amount))}"
                                 }
                                 span { class: "cell-percentage", 
                                     "{percentage:.1}%"
@@ -383,6 +441,41 @@ fn RegolithGrid(
     }
 }
 
+#[component]
+fn StrategyContributions(recommendation: Recommendation, square: Option<usize>) -> Element {
+    let Some(square) = square.filter(|s| recommendation.squares.contains(s)) else {
+        return rsx! {
+            p { class: "contributions-hint", "Select a recommended square to see which strategies voted for it" }
+        };
+    };
+    let votes = recommendation.contributions.get(square - 1).cloned().unwrap_or_default();
+    let total: f64 = votes.iter().map(|v| v.weight).sum();
+
+    rsx! {
+        div { class: "contributions",
+            div { class: "contributions-square", "Square #{square}" }
+            if votes.is_empty() {
+                p { class: "contributions-hint", "No strategy breakdown for this square" }
+            }
+            for vote in votes.iter() {
+                {
+                    let share = if total > 0.0 { vote.weight / total * 100.0 } else { 0.0 };
+                    rsx! {
+                        div { class: "contribution-item",
+                            key: "{vote.strategy}",
+                            span { class: "contribution-name", "{vote.strategy}" }
+                            div { class: "contribution-bar",
+                                div { class: "contribution-bar-fill", style: "width: {share:.0}%" }
+                            }
+                            span { class: "contribution-weight", "{vote.weight:.3}" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
 #[component]
 fn Timer(time_remaining: u64, round_duration: u64, slots_remaining: u64, progress: u32) -> Element {
     let minutes = time_remaining / 60;
@@ -425,7 +518,7 @@ fn WinnerReveal(winner: WinnerInfo) -> Element {
             div { class: "winner-details",
                 div { class: "winner-detail",
                     span { class: "winner-detail-value", 
-                        "{format!(\"{:.4}\", winner.total_pot as f64 / LAMPORTS_PER_SOL)} SOL"
+                        "{format!(\"{:.4}\", lamports_to_sol(winner.total_pot))} SOL"
                     }
                     span { "Total Pot" }
                 }